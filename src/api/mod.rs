@@ -1,18 +1,128 @@
 //! Serve a solver engine API.
 
 use {
-    crate::domain::solver::Solver,
-    axum::extract::DefaultBodyLimit,
+    crate::{
+        domain::solver::Solver,
+        infra::{cli, fixture::Recorder},
+    },
+    axum::extract::{ConnectInfo, DefaultBodyLimit},
+    hyper_util::{
+        rt::{TokioExecutor, TokioIo},
+        server::{self, graceful::GracefulShutdown},
+        service::TowerToHyperService,
+    },
     observe::tracing::distributed::axum::{make_span, record_trace_id},
-    std::{future::Future, io, net::SocketAddr, sync::Arc},
-    tokio::{net::TcpListener, sync::oneshot},
+    rustls_pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+    std::{
+        future::Future,
+        io,
+        net::SocketAddr,
+        num::NonZeroUsize,
+        path::PathBuf,
+        sync::{Arc, atomic::Ordering},
+        time::Duration,
+    },
+    tokio::{
+        net::TcpListener,
+        sync::{Semaphore, oneshot},
+    },
+    tower::ServiceExt,
 };
 
+pub(crate) mod openapi;
 mod routes;
 
+pub(crate) use routes::{Auth, solve::dto};
+
 pub struct Api {
     pub addr: SocketAddr,
-    pub solver: Solver,
+    /// The solvers to serve, each under the path of its chain ID, prefixed by
+    /// its instance name for named instances. A single solver, or the single
+    /// solver of a named instance, is also served without the chain ID.
+    pub solvers: Vec<Arc<Solver>>,
+    /// Records the traffic of every auction into fixtures, if enabled.
+    pub recorder: Option<Arc<Recorder>>,
+    /// How requests to the solver endpoints get authenticated.
+    pub auth: Auth,
+    /// The bearer token for the `/debug` API. The API is disabled without it.
+    pub debug_token: Option<cli::Secret>,
+    /// How long to keep serving requests while reporting not being ready
+    /// after the shutdown signal, before shutting down.
+    pub shutdown_delay: Duration,
+    /// Serves the API over HTTPS instead of HTTP, if set.
+    pub tls: Option<Tls>,
+    /// Whether to decompress gzip request bodies and compress responses for
+    /// clients that accept it.
+    pub compression: bool,
+    /// The maximum time to handle a request. `/solve` requests stop solving
+    /// before the timeout and return the solutions found until then.
+    pub request_timeout: Option<Duration>,
+    /// The maximum number of `/solve` requests to handle at once, across all
+    /// solvers. Further requests get rejected.
+    pub max_concurrent_solves: Option<NonZeroUsize>,
+    /// Whether to log `/solve` requests and responses that violate the
+    /// OpenAPI specification.
+    pub validate_openapi: bool,
+}
+
+/// The certificate chain and private key to serve the API over HTTPS with.
+pub struct Tls {
+    /// A PEM file with the certificate chain.
+    pub cert: PathBuf,
+    /// A PEM file with the private key.
+    pub key: PathBuf,
+}
+
+/// Every route of the API by method and path, with the path parameters of the
+/// OpenAPI specification. The routes of a solver, i.e. all but
+/// [`GLOBAL_ROUTES`], are also served under its chain ID and instance name.
+/// Routes get added here when they are added to the router.
+pub const ROUTES: &[(&str, &str)] = &[
+    ("get", "/metrics"),
+    ("get", "/healthz"),
+    ("get", "/openapi.yml"),
+    ("post", "/solve"),
+    ("post", "/notify"),
+    ("post", "/quote"),
+    ("get", "/native_price/{token}"),
+    ("get", "/readyz"),
+    ("get", "/debug/fills"),
+    ("delete", "/debug/fills/{uid}"),
+    ("get", "/debug/auctions"),
+    ("get", "/debug/status"),
+];
+
+/// The routes that don't belong to a solver.
+pub const GLOBAL_ROUTES: &[&str] = &["/metrics", "/healthz", "/openapi.yml"];
+
+/// The maximum size of decompressed request bodies in bytes if no maximum body
+/// size is configured.
+const MAX_DECOMPRESSED_BODY_SIZE: usize = 256 * 1024 * 1024;
+
+/// How long clients get to complete the TLS handshake before their connection
+/// gets dropped, so that stalled handshakes don't hold on to connections.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+impl Tls {
+    /// Loads the certificate chain and private key into a server
+    /// configuration that accepts HTTP/2 and HTTP/1.1.
+    fn config(&self) -> Result<rustls::ServerConfig, io::Error> {
+        let certs = CertificateDer::pem_file_iter(&self.cert)
+            .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+            .map_err(|err| io::Error::other(format!("{}: {err}", self.cert.display())))?;
+        let key = PrivateKeyDer::from_pem_file(&self.key)
+            .map_err(|err| io::Error::other(format!("{}: {err}", self.key.display())))?;
+        let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::aws_lc_rs::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .map_err(io::Error::other)?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(io::Error::other)?;
+        config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        Ok(config)
+    }
 }
 
 impl Api {
@@ -21,26 +131,256 @@ impl Api {
         bind: Option<oneshot::Sender<SocketAddr>>,
         shutdown: impl Future<Output = ()> + Send + 'static,
     ) -> Result<(), io::Error> {
-        let app = axum::Router::new()
+        let mut app = axum::Router::new()
             .route("/metrics", axum::routing::get(routes::metrics))
             .route("/healthz", axum::routing::get(routes::healthz))
-            .route("/solve", axum::routing::post(routes::solve))
+            .route("/openapi.yml", axum::routing::get(routes::openapi));
+        let solves = self
+            .max_concurrent_solves
+            .map(|limit| Arc::new(Semaphore::new(limit.get())));
+        let schemas = self
+            .validate_openapi
+            .then(|| Arc::new(routes::SolveSchemas::new(self.auth.max_body_size)));
+        for solver in &self.solvers {
+            let router = self.router(solver.clone(), solves.clone(), schemas.clone());
+            let prefix = solver
+                .name()
+                .map(|name| format!("/{name}"))
+                .unwrap_or_default();
+            app = app.nest(
+                &format!("{prefix}/{}", solver.chain_id().value()),
+                router.clone(),
+            );
+            let siblings = self
+                .solvers
+                .iter()
+                .filter(|other| other.name() == solver.name())
+                .count();
+            if siblings == 1 {
+                app = if prefix.is_empty() {
+                    app.merge(router)
+                } else {
+                    app.nest(&prefix, router)
+                };
+            }
+        }
+        if self.solvers.len() > 1 {
+            app = app.route(
+                "/readyz",
+                axum::routing::get(routes::readyz_all).with_state(self.solvers.clone()),
+            );
+        }
+
+        if let Some(timeout) = self.request_timeout {
+            app = app
+                .layer(tower_http::timeout::TimeoutLayer::with_status_code(
+                    axum::http::StatusCode::REQUEST_TIMEOUT,
+                    timeout,
+                ))
+                .layer(axum::Extension(routes::RequestTimeout(timeout)));
+        }
+        if self.compression {
+            // The limit applies to the decompressed body, so that small
+            // compressed requests can't make the solver engine decompress
+            // arbitrarily large bodies.
+            app = app
+                .layer(tower_http::compression::CompressionLayer::new())
+                .layer(tower_http::limit::RequestBodyLimitLayer::new(
+                    self.auth
+                        .max_body_size
+                        .unwrap_or(MAX_DECOMPRESSED_BODY_SIZE),
+                ))
+                .layer(tower_http::decompression::RequestDecompressionLayer::new());
+        }
+
+        let shutting_down = routes::ShuttingDown::default();
+        let app = app
+            .layer(axum::Extension(shutting_down.clone()))
             .layer(tower_http::trace::TraceLayer::new_for_http().make_span_with(make_span))
             .layer(axum::middleware::from_fn(
                 |request: axum::extract::Request, next: axum::middleware::Next| async {
                     next.run(record_trace_id(request)).await
                 },
             ))
-            .layer(DefaultBodyLimit::disable())
-            .with_state(Arc::new(self.solver));
+            .layer(match self.auth.max_body_size {
+                Some(limit) => DefaultBodyLimit::max(limit),
+                None => DefaultBodyLimit::disable(),
+            });
 
         let listener = TcpListener::bind(self.addr).await?;
         if let Some(bind) = bind {
             let _ = bind.send(listener.local_addr()?);
         }
 
-        axum::serve(listener, app)
-            .with_graceful_shutdown(shutdown)
-            .await
+        let shutdown_delay = self.shutdown_delay;
+        let shutdown = async move {
+            shutdown.await;
+            shutting_down.0.store(true, Ordering::Relaxed);
+            tracing::info!(?shutdown_delay, "shutting down");
+            tokio::time::sleep(shutdown_delay).await;
+        };
+        match &self.tls {
+            None => {
+                let app = app.into_make_service_with_connect_info::<SocketAddr>();
+                axum::serve(listener, app)
+                    .with_graceful_shutdown(shutdown)
+                    .await
+            }
+            Some(tls) => serve_tls(listener, app, tls, shutdown).await,
+        }
+    }
+
+    /// Creates the router for the endpoints of a single solver. Requests
+    /// record their metrics for the solver's instance, including rejected
+    /// ones.
+    fn router(
+        &self,
+        solver: Arc<Solver>,
+        solves: Option<Arc<Semaphore>>,
+        schemas: Option<Arc<routes::SolveSchemas>>,
+    ) -> axum::Router {
+        let solve = axum::routing::post(routes::solve);
+        let solve = match schemas {
+            Some(schemas) => solve.layer(axum::middleware::from_fn_with_state(
+                schemas,
+                routes::validate,
+            )),
+            None => solve,
+        };
+        let solve = match &self.recorder {
+            Some(recorder) => solve.layer(axum::middleware::from_fn_with_state(
+                recorder.clone(),
+                routes::record,
+            )),
+            None => solve,
+        };
+        let solve = match solves {
+            Some(solves) => solve.layer(axum::middleware::from_fn_with_state(
+                solves,
+                routes::limit_concurrency,
+            )),
+            None => solve,
+        };
+
+        let mut router = axum::Router::new()
+            .route("/solve", solve)
+            .route("/notify", axum::routing::post(routes::notify))
+            .route("/quote", axum::routing::post(routes::quote))
+            .route(
+                "/native_price/{token}",
+                axum::routing::get(routes::native_price),
+            );
+        if self.auth.is_enabled() {
+            router = router.route_layer(axum::middleware::from_fn_with_state(
+                Arc::new(self.auth.clone()),
+                routes::authorize,
+            ));
+        }
+        // Probes and the debug API aren't subject to the solver endpoints'
+        // authentication.
+        router = router.route("/readyz", axum::routing::get(routes::readyz));
+        if let Some(token) = &self.debug_token {
+            router = router.nest("/debug", routes::debug(token.clone()));
+        }
+        let instance = solver.instance().clone();
+        router
+            .layer(axum::middleware::from_fn(
+                move |request: axum::extract::Request, next: axum::middleware::Next| {
+                    instance.clone().scope(next.run(request))
+                },
+            ))
+            .with_state(solver)
+    }
+}
+
+/// Serves the app over HTTPS until the shutdown future completes, and then
+/// waits for open connections to finish their requests.
+async fn serve_tls(
+    listener: TcpListener,
+    app: axum::Router,
+    tls: &Tls,
+    shutdown: impl Future<Output = ()>,
+) -> Result<(), io::Error> {
+    let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(tls.config()?));
+    let graceful = GracefulShutdown::new();
+    let mut shutdown = std::pin::pin!(shutdown);
+    loop {
+        let (stream, peer) = tokio::select! {
+            connection = listener.accept() => match connection {
+                Ok(connection) => connection,
+                Err(err) => {
+                    tracing::warn!(?err, "failed to accept connection");
+                    continue;
+                }
+            },
+            () = &mut shutdown => break,
+        };
+        let acceptor = acceptor.clone();
+        // Handlers expect the peer address like with `axum::serve`.
+        let service = TowerToHyperService::new(app.clone().map_request(
+            move |mut request: axum::http::Request<hyper::body::Incoming>| {
+                request.extensions_mut().insert(ConnectInfo(peer));
+                request
+            },
+        ));
+        let watcher = graceful.watcher();
+        tokio::spawn(async move {
+            let stream =
+                match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                    Ok(Ok(stream)) => stream,
+                    Ok(Err(err)) => {
+                        tracing::debug!(%peer, ?err, "TLS handshake failed");
+                        return;
+                    }
+                    Err(_) => {
+                        tracing::debug!(%peer, "TLS handshake timed out");
+                        return;
+                    }
+                };
+            let builder = server::conn::auto::Builder::new(TokioExecutor::new());
+            let connection = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+            if let Err(err) = watcher.watch(connection).await {
+                tracing::debug!(%peer, ?err, "failed to serve connection");
+            }
+        });
+    }
+    drop(listener);
+    graceful.shutdown().await;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spec_documents_every_route() {
+        let spec = openapi::spec();
+        let documented = spec["paths"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(path, operations)| {
+                operations
+                    .as_object()
+                    .unwrap()
+                    .keys()
+                    .filter(|key| *key != "parameters")
+                    .map(move |method| (method.clone(), path.clone()))
+            })
+            .collect::<Vec<_>>();
+
+        for (method, path) in ROUTES {
+            assert!(
+                documented.contains(&(method.to_string(), path.to_string())),
+                "{method} {path} is not documented"
+            );
+        }
+        for (method, path) in &documented {
+            assert!(
+                ROUTES.contains(&(method.as_str(), path.as_str())),
+                "{method} {path} is documented but not served"
+            );
+        }
     }
 }
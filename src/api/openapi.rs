@@ -0,0 +1,135 @@
+//! The OpenAPI specification of the solver engine API and validation of
+//! requests and responses against it.
+
+use serde_json::{Value, json};
+
+/// The OpenAPI specification, as served at `/openapi.yml`.
+pub const SPEC: &str = include_str!("../../openapi.yml");
+
+/// A schema of the OpenAPI specification that JSON values can be validated
+/// against.
+pub struct Schema(jsonschema::Validator);
+
+impl Schema {
+    /// Compiles the schema at the JSON pointer into the specification, e.g.
+    /// `/components/schemas/Auction`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the specification or the schema is invalid.
+    pub fn new(pointer: &str) -> Self {
+        let mut root = spec();
+        assert!(
+            root.pointer(pointer).is_some(),
+            "no schema at {pointer} in the OpenAPI specification"
+        );
+        root["$ref"] = json!(format!("#{pointer}"));
+        Self(jsonschema::validator_for(&root).expect("invalid OpenAPI specification"))
+    }
+
+    /// The schema of the JSON request body of an endpoint.
+    pub fn request(path: &str, method: &str) -> Self {
+        Self::new(&format!(
+            "/paths/{}/{method}/requestBody/content/application~1json/schema",
+            escape(path),
+        ))
+    }
+
+    /// The schema of the JSON response body of an endpoint with the status.
+    pub fn response(path: &str, method: &str, status: u16) -> Self {
+        Self::new(&format!(
+            "/paths/{}/{method}/responses/{status}/content/application~1json/schema",
+            escape(path),
+        ))
+    }
+
+    /// Returns every violation of the schema, with the path of the offending
+    /// value.
+    pub fn violations(&self, value: &Value) -> Vec<String> {
+        self.0
+            .iter_errors(value)
+            .map(|err| format!("{}: {err}", err.instance_path))
+            .collect()
+    }
+}
+
+/// Parses the specification into a JSON schema document. OpenAPI 3.0 marks
+/// nullable values with `nullable`, which JSON schema doesn't know, so it gets
+/// converted to an explicit `null` alternative.
+pub fn spec() -> Value {
+    let spec = serde_yaml_ng::from_str(SPEC).expect("invalid OpenAPI specification");
+    convert_nullable(spec)
+}
+
+fn convert_nullable(value: Value) -> Value {
+    match value {
+        Value::Object(object) => {
+            let mut object = object
+                .into_iter()
+                .map(|(key, value)| (key, convert_nullable(value)))
+                .collect::<serde_json::Map<_, _>>();
+            match object.remove("nullable") {
+                Some(Value::Bool(true)) => json!({ "anyOf": [{ "type": "null" }, object] }),
+                _ => Value::Object(object),
+            }
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(convert_nullable).collect()),
+        value => value,
+    }
+}
+
+/// Escapes a path for use in a JSON pointer.
+fn escape(path: &str) -> String {
+    path.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_reference_resolves() {
+        fn check(root: &Value, value: &Value) {
+            match value {
+                Value::Object(object) => {
+                    if let Some(Value::String(reference)) = object.get("$ref") {
+                        let pointer = reference.strip_prefix('#').unwrap();
+                        assert!(root.pointer(pointer).is_some(), "unresolved {reference}");
+                    }
+                    object.values().for_each(|value| check(root, value));
+                }
+                Value::Array(values) => values.iter().for_each(|value| check(root, value)),
+                _ => (),
+            }
+        }
+
+        let spec = spec();
+        check(&spec, &spec);
+    }
+
+    #[test]
+    fn converts_nullable_values() {
+        let schema = Schema::new("/components/schemas/QuoteResponse");
+        let quote = json!({
+            "sellAmount": "1",
+            "buyAmount": "2",
+            "gas": "100000",
+            "calls": [],
+            "solution": null,
+        });
+        assert!(schema.violations(&quote).is_empty());
+
+        let quote = json!({ "sellAmount": 1 });
+        let violations = schema.violations(&quote);
+        assert!(
+            violations
+                .iter()
+                .any(|violation| violation.starts_with("/sellAmount"))
+        );
+        assert!(
+            violations
+                .iter()
+                .any(|violation| violation.contains("\"calls\""))
+        );
+    }
+}
@@ -0,0 +1,213 @@
+//! Authentication and size limits for requests to the solver endpoints.
+
+use {
+    crate::infra::{cli, metrics},
+    axum::{
+        body::Body,
+        extract::{ConnectInfo, Request, State},
+        http::{HeaderName, StatusCode, header},
+        middleware::Next,
+        response::{IntoResponse, Response},
+    },
+    hmac::{Hmac, Mac},
+    sha2::Sha256,
+    std::{
+        collections::HashMap,
+        net::SocketAddr,
+        sync::{Arc, Mutex},
+        time::{Duration, SystemTime},
+    },
+};
+
+/// The header with the hex-encoded HMAC-SHA256 signature of the timestamp and
+/// the request body, see [`signed_message`].
+const SIGNATURE: HeaderName = HeaderName::from_static("x-signature");
+
+/// The header with the Unix timestamp in seconds at which the request was
+/// signed.
+const TIMESTAMP: HeaderName = HeaderName::from_static("x-timestamp");
+
+/// How far the timestamp of a signed request may be off from the current time.
+/// Signed requests can't be replayed after that, and within that window they
+/// get rejected by the [`SeenSignatures`] cache.
+const MAX_SIGNATURE_AGE: Duration = Duration::from_secs(300);
+
+/// How requests to the solver endpoints get authenticated. Requests are
+/// accepted if they come from an allowed network and, if any credentials are
+/// configured, send either the bearer token or a valid body signature.
+#[derive(Clone, Debug, Default)]
+pub struct Auth {
+    /// The static bearer token.
+    pub token: Option<cli::Secret>,
+    /// The shared secret for HMAC-SHA256 signatures of the timestamp and the
+    /// request body.
+    pub hmac_secret: Option<cli::Secret>,
+    /// The networks that requests may come from. Requests from any address
+    /// are accepted if empty.
+    pub allowlist: Vec<ipnet::IpNet>,
+    /// The maximum size of request bodies in bytes.
+    pub max_body_size: Option<usize>,
+    /// The signatures of accepted signed requests.
+    pub seen_signatures: SeenSignatures,
+}
+
+impl Auth {
+    /// Whether any requests get rejected at all.
+    pub fn is_enabled(&self) -> bool {
+        self.token.is_some()
+            || self.hmac_secret.is_some()
+            || !self.allowlist.is_empty()
+            || self.max_body_size.is_some()
+    }
+}
+
+/// The signatures of accepted signed requests, together with their
+/// timestamps, for as long as the timestamps are recent enough to be accepted.
+#[derive(Clone, Debug, Default)]
+pub struct SeenSignatures(Arc<Mutex<HashMap<Vec<u8>, u64>>>);
+
+impl SeenSignatures {
+    /// Records the signature of a request and returns whether it is new.
+    fn insert(&self, signature: Vec<u8>, timestamp: u64, now: u64) -> bool {
+        let mut seen = self.0.lock().unwrap();
+        seen.retain(|_, timestamp| now.abs_diff(*timestamp) <= MAX_SIGNATURE_AGE.as_secs());
+        seen.insert(signature, timestamp).is_none()
+    }
+}
+
+/// Rejects requests that aren't authorized by the [`Auth`] configuration.
+pub async fn authorize(
+    State(auth): State<Arc<Auth>>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    match check(&auth, peer, request).await {
+        Ok(request) => next.run(request).await,
+        Err(rejection) => {
+            tracing::debug!(%peer, reason = rejection.as_str(), "rejected request");
+            metrics::api_rejection(rejection.as_str());
+            rejection.status().into_response()
+        }
+    }
+}
+
+async fn check(auth: &Auth, peer: SocketAddr, request: Request) -> Result<Request, Rejection> {
+    if !auth.allowlist.is_empty()
+        && !auth
+            .allowlist
+            .iter()
+            .any(|network| network.contains(&peer.ip().to_canonical()))
+    {
+        return Err(Rejection::AddressNotAllowed);
+    }
+
+    let content_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if let (Some(limit), Some(length)) = (auth.max_body_size, content_length)
+        && length > limit
+    {
+        return Err(Rejection::BodyTooLarge);
+    }
+
+    if auth.token.is_none() && auth.hmac_secret.is_none() {
+        return Ok(request);
+    }
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if let (Some(token), Some(bearer)) = (&auth.token, bearer)
+        && constant_time_eq(bearer.as_bytes(), token.0.as_bytes())
+    {
+        return Ok(request);
+    }
+
+    let (Some(secret), Some(signature)) = (&auth.hmac_secret, request.headers().get(SIGNATURE))
+    else {
+        return Err(Rejection::Unauthorized);
+    };
+    let signature = signature
+        .to_str()
+        .ok()
+        .and_then(|signature| hex::decode(signature.trim_start_matches("sha256=")).ok())
+        .ok_or(Rejection::InvalidSignature)?;
+    let timestamp = request
+        .headers()
+        .get(TIMESTAMP)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .ok_or(Rejection::InvalidSignature)?;
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    if now.abs_diff(timestamp) > MAX_SIGNATURE_AGE.as_secs() {
+        return Err(Rejection::ExpiredSignature);
+    }
+
+    // Verifying the signature needs the whole body, so buffer it and pass it
+    // on to the handler afterwards.
+    let (parts, body) = request.into_parts();
+    let body = axum::body::to_bytes(body, auth.max_body_size.unwrap_or(usize::MAX))
+        .await
+        .map_err(|_| Rejection::BodyTooLarge)?;
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.0.as_bytes()).expect("HMAC accepts any key size");
+    mac.update(&signed_message(timestamp, &body));
+    mac.verify_slice(&signature)
+        .map_err(|_| Rejection::InvalidSignature)?;
+    if !auth.seen_signatures.insert(signature, timestamp, now) {
+        return Err(Rejection::ReplayedSignature);
+    }
+    Ok(Request::from_parts(parts, Body::from(body)))
+}
+
+/// The message that gets signed: the timestamp in decimal, a `.` and the
+/// body. Signing the timestamp prevents replaying signed requests later.
+fn signed_message(timestamp: u64, body: &[u8]) -> Vec<u8> {
+    [format!("{timestamp}.").as_bytes(), body].concat()
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Rejection {
+    AddressNotAllowed,
+    BodyTooLarge,
+    Unauthorized,
+    InvalidSignature,
+    ExpiredSignature,
+    ReplayedSignature,
+}
+
+impl Rejection {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::AddressNotAllowed => "address_not_allowed",
+            Self::BodyTooLarge => "body_too_large",
+            Self::Unauthorized => "unauthorized",
+            Self::InvalidSignature => "invalid_signature",
+            Self::ExpiredSignature => "expired_signature",
+            Self::ReplayedSignature => "replayed_signature",
+        }
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            Self::AddressNotAllowed => StatusCode::FORBIDDEN,
+            Self::BodyTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::Unauthorized
+            | Self::InvalidSignature
+            | Self::ExpiredSignature
+            | Self::ReplayedSignature => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+/// Compares two byte strings in time that only depends on their lengths.
+pub(super) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
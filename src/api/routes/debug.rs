@@ -0,0 +1,162 @@
+//! Endpoints for inspecting the internal state of the solver engine.
+
+use {
+    crate::{
+        domain::{order, solver::Solver},
+        infra::cli,
+        util::serialize,
+    },
+    axum::{
+        extract::{Path, Request, State},
+        http::{StatusCode, header},
+        middleware::Next,
+        response::{IntoResponse, Json, Response},
+    },
+    serde::{Deserialize, Serialize},
+    serde_with::serde_as,
+    std::sync::Arc,
+};
+
+/// Creates the router of the debug API. Every request needs to send the
+/// specified token as a bearer token.
+pub fn debug(token: cli::Secret) -> axum::Router<Arc<Solver>> {
+    axum::Router::new()
+        .route("/fills", axum::routing::get(fills))
+        .route("/fills/{uid}", axum::routing::delete(reset_fill))
+        .route("/auctions", axum::routing::get(auctions))
+        .route("/status", axum::routing::get(status))
+        .route_layer(axum::middleware::from_fn_with_state(
+            Arc::new(token),
+            authorize,
+        ))
+}
+
+async fn authorize(
+    State(token): State<Arc<cli::Secret>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|bearer| super::auth::constant_time_eq(bearer.as_bytes(), token.0.as_bytes()));
+    if !authorized {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    next.run(request).await
+}
+
+#[serde_as]
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Fill {
+    order: String,
+    #[serde_as(as = "serialize::U256")]
+    next_amount: alloy::primitives::U256,
+    #[serde_as(as = "serialize::U256")]
+    total_amount: alloy::primitives::U256,
+    last_requested_seconds_ago: f64,
+}
+
+async fn fills(State(solver): State<Arc<Solver>>) -> Json<Vec<Fill>> {
+    Json(
+        solver
+            .fills()
+            .into_iter()
+            .map(|entry| Fill {
+                order: entry.uid.to_string(),
+                next_amount: entry.next_amount,
+                total_amount: entry.total_amount,
+                last_requested_seconds_ago: entry.last_requested.as_secs_f64(),
+            })
+            .collect(),
+    )
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+struct OrderUid(#[serde_as(as = "serialize::Hex")] [u8; 56]);
+
+async fn reset_fill(State(solver): State<Arc<Solver>>, Path(uid): Path<OrderUid>) -> StatusCode {
+    if solver.reset_fill(order::Uid(uid.0)) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Auction {
+    id: String,
+    started: chrono::DateTime<chrono::Utc>,
+    orders: Vec<Order>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Order {
+    order: String,
+    /// The outcome of solving the order, or `null` if it was skipped.
+    outcome: Option<&'static str>,
+    error: Option<String>,
+}
+
+async fn auctions(State(solver): State<Arc<Solver>>) -> Json<Vec<Auction>> {
+    Json(
+        solver
+            .auctions()
+            .into_iter()
+            .map(|auction| Auction {
+                id: auction.id.to_string(),
+                started: auction.started,
+                orders: auction
+                    .orders
+                    .into_iter()
+                    .map(|order| Order {
+                        order: order.uid.to_string(),
+                        outcome: order.outcome.map(|outcome| outcome.as_str()),
+                        error: order.error,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    )
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Status {
+    rate_limiter: RateLimiter,
+    block: Option<Block>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RateLimiter {
+    consecutive_failures: u32,
+    backoff_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Block {
+    number: u64,
+    age_seconds: u64,
+}
+
+async fn status(State(solver): State<Arc<Solver>>) -> Json<Status> {
+    let status = solver.status();
+    Json(Status {
+        rate_limiter: RateLimiter {
+            consecutive_failures: status.rate_limiter.consecutive_failures,
+            backoff_until: status.rate_limiter.backoff_until,
+        },
+        block: status.block.map(|block| Block {
+            number: block.number,
+            age_seconds: block.age.as_secs(),
+        }),
+    })
+}
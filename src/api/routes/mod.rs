@@ -1,10 +1,35 @@
-use serde::Serialize;
+use {
+    crate::infra,
+    serde::Serialize,
+    std::fmt::{self, Display, Formatter},
+};
 
+mod auth;
+mod debug;
 mod healthz;
 mod metrics;
-mod solve;
+mod native_price;
+mod notify;
+mod openapi;
+mod quote;
+mod readyz;
+mod record;
+pub(super) mod solve;
 
-pub(super) use {healthz::healthz, metrics::metrics, solve::solve};
+pub(crate) use auth::Auth;
+pub(super) use {
+    auth::authorize,
+    debug::debug,
+    healthz::healthz,
+    metrics::metrics,
+    native_price::native_price,
+    notify::notify,
+    openapi::{SolveSchemas, openapi, validate},
+    quote::quote,
+    readyz::{ShuttingDown, readyz, readyz_all},
+    record::record,
+    solve::{RequestTimeout, limit_concurrency, solve},
+};
 
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
@@ -14,12 +39,73 @@ pub enum Response<T> {
 }
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Error {
     pub message: &'static str,
+    /// The fields of the request that are invalid, if the request was
+    /// rejected because of them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub invalid_fields: Vec<InvalidField>,
 }
 
 impl From<&'static str> for Error {
     fn from(message: &'static str) -> Self {
-        Self { message }
+        Self {
+            message,
+            invalid_fields: Vec::new(),
+        }
     }
 }
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.message)?;
+        for field in &self.invalid_fields {
+            write!(f, "; {}: {}", field.path, field.description)?;
+        }
+        Ok(())
+    }
+}
+
+/// A field of a request that is invalid.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidField {
+    /// The path of the field, e.g. `orders[0].sellAmount`.
+    pub path: String,
+    pub kind: InvalidFieldKind,
+    /// A human readable description of the problem.
+    pub description: String,
+}
+
+/// What is wrong with an invalid field.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InvalidFieldKind {
+    /// The field is missing or can't be parsed, e.g. a UID of the wrong
+    /// length.
+    Malformed,
+    /// An order UID that appears more than once.
+    DuplicateUid,
+    /// An order amount that is zero.
+    ZeroAmount,
+    /// An order token that is missing from the auction's tokens.
+    UnknownToken,
+    /// A deadline that already passed.
+    DeadlinePassed,
+}
+
+/// Returns the HTTP status and error for a failed DEX API request.
+fn dex_error(err: &infra::dex::Error) -> (axum::http::StatusCode, Error) {
+    let (status, message) = match err {
+        infra::dex::Error::NotFound => (axum::http::StatusCode::NOT_FOUND, "no swap found"),
+        infra::dex::Error::RateLimited => {
+            (axum::http::StatusCode::TOO_MANY_REQUESTS, "rate limited")
+        }
+        infra::dex::Error::OrderNotSupported | infra::dex::Error::UnavailableForLegalReasons => {
+            (axum::http::StatusCode::BAD_REQUEST, "swap not supported")
+        }
+        infra::dex::Error::Other(_) => (axum::http::StatusCode::BAD_GATEWAY, "DEX API error"),
+    };
+    (status, message.into())
+}
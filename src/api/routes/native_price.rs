@@ -0,0 +1,53 @@
+use {
+    super::Response,
+    crate::{
+        domain::{eth, solver::Solver},
+        util::serialize,
+    },
+    serde::{Deserialize, Serialize},
+    serde_with::serde_as,
+    std::sync::Arc,
+    tracing::Instrument,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct Query {
+    /// The decimals of the token, for DEX APIs that need them.
+    decimals: Option<u8>,
+}
+
+/// The price of a token in wei for 10**18 of its atoms.
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub struct NativePrice {
+    #[serde_as(as = "serialize::U256")]
+    price: eth::U256,
+}
+
+pub async fn native_price(
+    state: axum::extract::State<Arc<Solver>>,
+    token: axum::extract::Path<eth::Address>,
+    query: axum::extract::Query<Query>,
+) -> (
+    axum::http::StatusCode,
+    axum::response::Json<Response<NativePrice>>,
+) {
+    let token = eth::TokenAddress(token.0);
+    let handle_request = async {
+        match state.native_price(token, query.decimals).await {
+            Ok(price) => (
+                axum::http::StatusCode::OK,
+                axum::response::Json(Response::Ok(NativePrice { price: price.0.0 })),
+            ),
+            Err(err) => {
+                tracing::debug!(?err, "failed to estimate native price");
+                let (status, err) = super::dex_error(&err);
+                (status, axum::response::Json(Response::Err(err)))
+            }
+        }
+    };
+
+    handle_request
+        .instrument(tracing::info_span!("/native_price", token = ?token.0))
+        .await
+}
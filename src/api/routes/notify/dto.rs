@@ -0,0 +1,122 @@
+//! The notification DTO of the solver engine API. Unknown notification kinds
+//! are accepted, so that new kinds in the driver don't break notifications.
+
+use {
+    crate::{
+        domain::{auction, eth, notification, solution},
+        util::serialize,
+    },
+    alloy::primitives::{Address, B256, U256},
+    serde::Deserialize,
+    serde_with::serde_as,
+    std::collections::BTreeSet,
+};
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Notification {
+    #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+    #[serde(default)]
+    auction_id: Option<i64>,
+    #[serde(default)]
+    solution_id: Option<SolutionId>,
+    #[serde(flatten)]
+    kind: Kind,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SolutionId {
+    Single(u64),
+    Merged(Vec<u64>),
+}
+
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+enum Kind {
+    Timeout,
+    EmptySolution,
+    DuplicatedSolutionId,
+    #[serde(rename_all = "camelCase")]
+    SimulationFailed {
+        #[serde(default)]
+        succeeded_once: bool,
+    },
+    InvalidClearingPrices,
+    #[serde(rename_all = "camelCase")]
+    MissingPrice {
+        token_address: Address,
+    },
+    InvalidExecutedAmount,
+    NonBufferableTokensUsed {
+        tokens: BTreeSet<Address>,
+    },
+    SolverAccountInsufficientBalance {
+        #[serde_as(as = "serialize::U256")]
+        required: U256,
+    },
+    Success {
+        transaction: B256,
+    },
+    Revert {
+        transaction: B256,
+    },
+    DriverError {
+        reason: String,
+    },
+    Cancelled,
+    Fail,
+    #[serde(other)]
+    Unknown,
+}
+
+impl Notification {
+    /// Converts the data transfer object into its domain object
+    /// representation.
+    pub fn to_domain(&self) -> notification::Notification {
+        notification::Notification {
+            auction_id: match self.auction_id {
+                Some(id) => auction::Id::Solve(id),
+                None => auction::Id::Quote,
+            },
+            solution_ids: match &self.solution_id {
+                Some(SolutionId::Single(id)) => vec![solution::Id(*id)],
+                Some(SolutionId::Merged(ids)) => ids.iter().copied().map(solution::Id).collect(),
+                None => Vec::new(),
+            },
+            kind: match &self.kind {
+                Kind::Timeout => notification::Kind::Timeout,
+                Kind::EmptySolution => notification::Kind::EmptySolution,
+                Kind::DuplicatedSolutionId => notification::Kind::DuplicatedSolutionId,
+                Kind::SimulationFailed { succeeded_once } => notification::Kind::SimulationFailed {
+                    succeeded_once: *succeeded_once,
+                },
+                Kind::InvalidClearingPrices => notification::Kind::InvalidClearingPrices,
+                Kind::MissingPrice { token_address } => {
+                    notification::Kind::MissingPrice(eth::TokenAddress(*token_address))
+                }
+                Kind::InvalidExecutedAmount => notification::Kind::InvalidExecutedAmount,
+                Kind::NonBufferableTokensUsed { tokens } => {
+                    notification::Kind::NonBufferableTokensUsed(
+                        tokens.iter().copied().map(eth::TokenAddress).collect(),
+                    )
+                }
+                Kind::SolverAccountInsufficientBalance { required } => {
+                    notification::Kind::SolverAccountInsufficientBalance(eth::Ether(*required))
+                }
+                Kind::Success { transaction } => {
+                    notification::Kind::Settled(eth::TxHash(*transaction))
+                }
+                Kind::Revert { transaction } => {
+                    notification::Kind::Reverted(eth::TxHash(*transaction))
+                }
+                Kind::DriverError { reason } => notification::Kind::DriverError(reason.clone()),
+                Kind::Cancelled => notification::Kind::Cancelled,
+                Kind::Fail => notification::Kind::Failed,
+                Kind::Unknown => notification::Kind::Unknown,
+            },
+        }
+    }
+}
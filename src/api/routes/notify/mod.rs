@@ -0,0 +1,13 @@
+use {crate::domain::solver::Solver, std::sync::Arc};
+
+mod dto;
+
+pub async fn notify(
+    state: axum::extract::State<Arc<Solver>>,
+    notification: axum::extract::Json<dto::Notification>,
+) -> axum::http::StatusCode {
+    let notification = notification.to_domain();
+    tracing::info_span!("/notify", auction_id = %notification.auction_id)
+        .in_scope(|| state.notify(notification));
+    axum::http::StatusCode::OK
+}
@@ -0,0 +1,84 @@
+//! Serves the OpenAPI specification and checks `/solve` traffic against it.
+
+use {
+    crate::api::openapi::{SPEC, Schema},
+    axum::{
+        RequestExt,
+        body::{Body, HttpBody},
+        extract::{Request, State},
+        http::{StatusCode, header},
+        middleware::Next,
+        response::{IntoResponse, Response},
+    },
+    std::sync::Arc,
+};
+
+pub async fn openapi() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "application/yaml")], SPEC)
+}
+
+/// The schemas of the `/solve` endpoint.
+pub struct SolveSchemas {
+    auction: Schema,
+    response: Schema,
+    max_body_size: Option<usize>,
+}
+
+impl SolveSchemas {
+    /// Bodies larger than the maximum body size don't get buffered for
+    /// validation.
+    pub fn new(max_body_size: Option<usize>) -> Self {
+        Self {
+            auction: Schema::request("/solve", "post"),
+            response: Schema::response("/solve", "post", 200),
+            max_body_size,
+        }
+    }
+}
+
+/// Validates auctions and successful responses against the OpenAPI
+/// specification and logs every violation. Requests are handled either way.
+pub async fn validate(
+    State(schemas): State<Arc<SolveSchemas>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // The body is buffered before any extractor runs, so apply the
+    // configured body limit here.
+    let (parts, body) = request.with_limited_body().into_parts();
+    let Ok(body) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    };
+    check(&schemas.auction, "auction", &body);
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if !response.status().is_success() {
+        return response;
+    }
+    let limit = schemas.max_body_size.unwrap_or(usize::MAX);
+    let size = response.body().size_hint().upper();
+    if size.is_none_or(|size| size > limit as u64) {
+        tracing::debug!(
+            ?size,
+            limit,
+            "not validating response of unknown or large size"
+        );
+        return response;
+    }
+    let (parts, body) = response.into_parts();
+    let Ok(body) = axum::body::to_bytes(body, limit).await else {
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    };
+    check(&schemas.response, "response", &body);
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn check(schema: &Schema, kind: &str, body: &[u8]) {
+    let violations = match serde_json::from_slice(body) {
+        Ok(value) => schema.violations(&value),
+        Err(err) => vec![err.to_string()],
+    };
+    if !violations.is_empty() {
+        tracing::warn!(kind, ?violations, "OpenAPI specification violated");
+    }
+}
@@ -0,0 +1,133 @@
+use {
+    crate::{
+        api::routes::solve::dto,
+        domain::{auction, eth, order, quote},
+        util::serialize,
+    },
+    alloy::primitives::{Address, U256},
+    serde::{Deserialize, Serialize},
+    serde_with::serde_as,
+    std::collections::HashMap,
+};
+
+/// A request for quoting a single swap. Token decimals and prices are only
+/// needed by DEX APIs and slippage configurations that rely on them.
+#[serde_as]
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Request {
+    sell_token: Address,
+    buy_token: Address,
+    side: Side,
+    #[serde_as(as = "serialize::U256")]
+    amount: U256,
+    #[serde(default)]
+    owner: Option<Address>,
+    #[serde(default)]
+    sell_token_decimals: Option<u8>,
+    #[serde(default)]
+    buy_token_decimals: Option<u8>,
+    #[serde_as(as = "Option<serialize::U256>")]
+    #[serde(default)]
+    sell_token_price: Option<U256>,
+    #[serde_as(as = "Option<serialize::U256>")]
+    #[serde(default)]
+    buy_token_price: Option<U256>,
+    #[serde_as(as = "Option<serialize::U256>")]
+    #[serde(default)]
+    gas_price: Option<U256>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Side {
+    Sell,
+    Buy,
+}
+
+impl Request {
+    /// Converts the data transfer object into its domain object
+    /// representation.
+    pub fn to_domain(&self) -> quote::Request {
+        let token = |decimals, price: Option<U256>| auction::Token {
+            decimals,
+            reference_price: price.map(eth::Ether).map(auction::Price),
+            available_balance: U256::ZERO,
+            trusted: false,
+        };
+        quote::Request {
+            sell: eth::TokenAddress(self.sell_token),
+            buy: eth::TokenAddress(self.buy_token),
+            side: match self.side {
+                Side::Sell => order::Side::Sell,
+                Side::Buy => order::Side::Buy,
+            },
+            amount: self.amount,
+            owner: self.owner.unwrap_or_default(),
+            tokens: auction::Tokens(HashMap::from([
+                (
+                    eth::TokenAddress(self.sell_token),
+                    token(self.sell_token_decimals, self.sell_token_price),
+                ),
+                (
+                    eth::TokenAddress(self.buy_token),
+                    token(self.buy_token_decimals, self.buy_token_price),
+                ),
+            ])),
+            gas_price: auction::GasPrice(eth::Ether(self.gas_price.unwrap_or_default())),
+        }
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Quote {
+    #[serde_as(as = "serialize::U256")]
+    sell_amount: U256,
+    #[serde_as(as = "serialize::U256")]
+    buy_amount: U256,
+    #[serde_as(as = "serialize::U256")]
+    gas: U256,
+    calls: Vec<Call>,
+    /// The would-be solution in the format of the `/solve` response.
+    solution: Option<serde_json::Value>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Call {
+    to: Address,
+    #[serde_as(as = "serialize::U256")]
+    value: U256,
+    #[serde_as(as = "serialize::Hex")]
+    calldata: Vec<u8>,
+}
+
+impl Quote {
+    /// Creates a new quote DTO from its domain object.
+    pub fn from_domain(quote: &quote::Quote) -> Self {
+        Self {
+            sell_amount: quote.sell.amount,
+            buy_amount: quote.buy.amount,
+            gas: quote.gas.0,
+            calls: quote
+                .calls
+                .iter()
+                .map(|call| Call {
+                    to: call.target,
+                    value: call.value.0,
+                    calldata: call.calldata.clone(),
+                })
+                .collect(),
+            solution: quote.solution.as_ref().and_then(|solution| {
+                let mut response = serde_json::to_value(dto::solution::from_domain(
+                    std::slice::from_ref(solution),
+                ))
+                .ok()?;
+                Some(response["solutions"][0].take())
+            }),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use {super::Response, crate::domain::solver::Solver, std::sync::Arc, tracing::Instrument};
+
+mod dto;
+
+pub async fn quote(
+    state: axum::extract::State<Arc<Solver>>,
+    request: axum::extract::Json<dto::Request>,
+) -> (
+    axum::http::StatusCode,
+    axum::response::Json<Response<dto::Quote>>,
+) {
+    let handle_request = async {
+        let quote = match state.quote(request.to_domain()).await {
+            Ok(quote) => quote,
+            Err(err) => {
+                tracing::debug!(?err, "failed to quote");
+                let (status, err) = super::dex_error(&err);
+                return (status, axum::response::Json(Response::Err(err)));
+            }
+        };
+
+        tracing::trace!(?quote);
+        (
+            axum::http::StatusCode::OK,
+            axum::response::Json(Response::Ok(dto::Quote::from_domain(&quote))),
+        )
+    };
+
+    handle_request
+        .instrument(tracing::info_span!("/quote"))
+        .await
+}
@@ -0,0 +1,128 @@
+use {
+    crate::domain::solver::Solver,
+    serde::Serialize,
+    std::{
+        collections::BTreeMap,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    },
+};
+
+/// Set once the API starts shutting down gracefully, so that the solver engine
+/// reports not being ready while it finishes the requests in flight.
+#[derive(Clone, Debug, Default)]
+pub struct ShuttingDown(pub Arc<AtomicBool>);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Readiness {
+    ready: bool,
+    checks: Checks,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Checks {
+    shutdown: Check,
+    node: Check,
+    block_stream: Check,
+    dex: Check,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "camelCase")]
+enum Check {
+    Ok,
+    Failed {
+        error: String,
+    },
+    /// The check does not apply to the configuration of the solver.
+    Skipped,
+}
+
+impl From<Result<(), String>> for Check {
+    fn from(result: Result<(), String>) -> Self {
+        match result {
+            Ok(()) => Self::Ok,
+            Err(error) => Self::Failed { error },
+        }
+    }
+}
+
+/// The readiness of every solver of a solver engine serving several solvers,
+/// by chain ID, prefixed by the instance name for named instances.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessAll {
+    ready: bool,
+    solvers: BTreeMap<String, Readiness>,
+}
+
+pub async fn readyz(
+    state: axum::extract::State<Arc<Solver>>,
+    shutting_down: axum::Extension<ShuttingDown>,
+) -> (axum::http::StatusCode, axum::response::Json<Readiness>) {
+    let readiness = readiness(&state, &shutting_down).await;
+    (status(readiness.ready), axum::response::Json(readiness))
+}
+
+pub async fn readyz_all(
+    state: axum::extract::State<Vec<Arc<Solver>>>,
+    shutting_down: axum::Extension<ShuttingDown>,
+) -> (axum::http::StatusCode, axum::response::Json<ReadinessAll>) {
+    let solvers = futures::future::join_all(state.iter().map(|solver| async {
+        let chain = solver.chain_id().value();
+        let key = match solver.name() {
+            Some(name) => format!("{name}/{chain}"),
+            None => chain.to_string(),
+        };
+        (key, readiness(solver, &shutting_down).await)
+    }))
+    .await
+    .into_iter()
+    .collect::<BTreeMap<_, _>>();
+    let ready = solvers.values().all(|readiness| readiness.ready);
+    (
+        status(ready),
+        axum::response::Json(ReadinessAll { ready, solvers }),
+    )
+}
+
+async fn readiness(solver: &Solver, shutting_down: &ShuttingDown) -> Readiness {
+    let shutdown = if shutting_down.0.load(Ordering::Relaxed) {
+        Err("shutting down".to_string())
+    } else {
+        Ok(())
+    };
+    let readiness = solver.readiness().await;
+    let ready = shutdown.is_ok() && readiness.is_ready();
+    if !ready {
+        tracing::debug!(
+            chain = solver.chain_id().value(),
+            name = solver.name(),
+            ?readiness,
+            ?shutdown,
+            "solver engine is not ready"
+        );
+    }
+
+    Readiness {
+        ready,
+        checks: Checks {
+            shutdown: shutdown.into(),
+            node: readiness.node.into(),
+            block_stream: readiness.block_stream.map_or(Check::Skipped, Check::from),
+            dex: readiness.dex.into(),
+        },
+    }
+}
+
+fn status(ready: bool) -> axum::http::StatusCode {
+    if ready {
+        axum::http::StatusCode::OK
+    } else {
+        axum::http::StatusCode::SERVICE_UNAVAILABLE
+    }
+}
@@ -0,0 +1,50 @@
+use {
+    crate::infra::fixture::Recorder,
+    axum::{
+        RequestExt,
+        body::Body,
+        extract::{Request, State},
+        http::StatusCode,
+        middleware::Next,
+        response::{IntoResponse, Response},
+    },
+    std::sync::Arc,
+};
+
+/// Records the DEX API and node traffic of solving an auction into a fixture
+/// named after the auction ID. Auctions without an ID (i.e. quotes) are not
+/// recorded.
+pub async fn record(
+    State(recorder): State<Arc<Recorder>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // The body is buffered before any extractor runs, so apply the
+    // configured body limit here.
+    let (parts, body) = request.with_limited_body().into_parts();
+    let Ok(body) = axum::body::to_bytes(body, usize::MAX).await else {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    };
+    let id = serde_json::from_slice::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|auction| match auction.get("id")? {
+            serde_json::Value::String(id) => Some(id.clone()),
+            serde_json::Value::Number(id) => Some(id.to_string()),
+            _ => None,
+        })
+        // The ID is used as the file name of the fixture.
+        .filter(|id| {
+            id.chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        });
+    let Some(id) = id else {
+        return next.run(Request::from_parts(parts, Body::from(body))).await;
+    };
+
+    let recording = recorder.record();
+    let response = recording
+        .scope(next.run(Request::from_parts(parts, Body::from(body.clone()))))
+        .await;
+    recording.finish(&id, Some(&body)).await;
+    response
+}
@@ -1,13 +1,170 @@
 use {
     crate::{
-        api::routes::Error,
+        api::routes::{Error, InvalidField, InvalidFieldKind},
         domain::{auction, eth, order},
     },
+    alloy::primitives::Address,
+    chrono::{DateTime, Utc},
     dto::auction::*,
+    serde::{Deserialize, de::IgnoredAny},
+    std::collections::HashMap,
 };
 
+/// Parses a JSON auction and checks its orders. Auctions with malformed
+/// fields or invalid orders are rejected with every such field, except that
+/// only the first missing field of the auction itself is reported. The
+/// deadline is checked by [`to_domain`].
+pub fn from_json(json: &[u8]) -> Result<Auction, Error> {
+    parse(json).map_err(|(invalid_fields, _)| Error {
+        message: "invalid auction",
+        invalid_fields,
+    })
+}
+
+/// Parses a JSON auction for solving it now. Auctions that can't be solved
+/// are rejected with every invalid field, including a deadline that already
+/// passed.
+pub fn solvable_from_json(json: &[u8]) -> Result<auction::Auction, Error> {
+    match parse(json) {
+        Ok(auction) => to_domain(&auction),
+        Err((mut invalid_fields, deadline)) => {
+            if let Some(field) = deadline.and_then(deadline_passed) {
+                invalid_fields.insert(0, field);
+            }
+            Err(Error {
+                message: "invalid auction",
+                invalid_fields,
+            })
+        }
+    }
+}
+
+/// Parses a JSON auction and checks its orders. Failures come with every
+/// invalid field and the deadline, if it could be parsed.
+fn parse(json: &[u8]) -> Result<Auction, (Vec<InvalidField>, Option<DateTime<Utc>>)> {
+    let malformed = |description: String| InvalidField {
+        path: ".".to_owned(),
+        kind: InvalidFieldKind::Malformed,
+        description,
+    };
+    let mut value = match serde_json::from_slice::<serde_json::Value>(json) {
+        Ok(value @ serde_json::Value::Object(_)) => value,
+        Ok(_) => return Err((vec![malformed("expected an object".to_owned())], None)),
+        Err(err) => return Err((vec![malformed(err.to_string())], None)),
+    };
+    let deadline = value
+        .get("deadline")
+        .and_then(|deadline| DateTime::deserialize(deadline).ok());
+    // Tokens are only used for checking orders, which is skipped for tokens
+    // that can't be parsed, since they are reported already.
+    let tokens = value
+        .get("tokens")
+        .and_then(|tokens| HashMap::<Address, IgnoredAny>::deserialize(tokens).ok());
+
+    // Orders are parsed one by one, so that every malformed order gets
+    // reported.
+    let orders = match value.get_mut("orders") {
+        Some(serde_json::Value::Array(orders)) => std::mem::take(orders),
+        _ => Vec::new(),
+    };
+
+    // Parsing stops at the first malformed field, so malformed fields are
+    // removed one at a time to find the next one.
+    let mut invalid_fields = Vec::new();
+    let mut removed = Vec::new();
+    let auction = loop {
+        match deserialize::<Auction>(&value, None) {
+            Ok(auction) => break Some(auction),
+            Err(field) => {
+                let key = field
+                    .path
+                    .split(['.', '['])
+                    .next()
+                    .unwrap_or_default()
+                    .to_owned();
+                if let Some(object) = value.as_object_mut()
+                    && object.remove(&key).is_some()
+                {
+                    invalid_fields.push(field);
+                    removed.push(key);
+                    continue;
+                }
+                // Fields that are missing since they were removed are
+                // reported already.
+                if !removed
+                    .iter()
+                    .any(|key| field.description == format!("missing field `{key}`"))
+                {
+                    invalid_fields.push(field);
+                }
+                break None;
+            }
+        }
+    };
+
+    let mut parsed = Vec::new();
+    let mut uids = HashMap::new();
+    for (i, order) in orders.iter().enumerate() {
+        let order = match deserialize::<Order>(order, Some(&format!("orders[{i}]"))) {
+            Ok(order) => order,
+            Err(field) => {
+                invalid_fields.push(field);
+                continue;
+            }
+        };
+        invalid_fields.extend(validate_order(
+            i,
+            &order,
+            |token| {
+                tokens
+                    .as_ref()
+                    .is_none_or(|tokens| tokens.contains_key(token))
+            },
+            &mut uids,
+        ));
+        parsed.push(order);
+    }
+
+    match auction {
+        Some(mut auction) if invalid_fields.is_empty() => {
+            auction.orders = parsed;
+            Ok(auction)
+        }
+        _ => Err((invalid_fields, deadline)),
+    }
+}
+
+/// Deserializes a JSON value, reporting the path of the first field that
+/// can't be parsed below the specified path.
+fn deserialize<T: serde::de::DeserializeOwned>(
+    value: &serde_json::Value,
+    prefix: Option<&str>,
+) -> Result<T, InvalidField> {
+    serde_path_to_error::deserialize(value).map_err(|err| {
+        let path = err.path().to_string();
+        InvalidField {
+            path: match prefix {
+                Some(prefix) if path == "." => prefix.to_owned(),
+                Some(prefix) => format!("{prefix}.{path}"),
+                None => path,
+            },
+            kind: InvalidFieldKind::Malformed,
+            description: err.inner().to_string(),
+        }
+    })
+}
+
 /// Converts a data transfer object into its domain object representation.
+/// Auctions whose deadline already passed are rejected. Orders are expected
+/// to be checked by [`from_json`].
 pub fn to_domain(auction: &Auction) -> Result<auction::Auction, Error> {
+    if let Some(field) = deadline_passed(auction.deadline) {
+        return Err(Error {
+            message: "invalid auction",
+            invalid_fields: vec![field],
+        });
+    }
+
     Ok(auction::Auction {
         id: match auction.id {
             Some(id) => auction::Id::Solve(id),
@@ -55,9 +212,107 @@ pub fn to_domain(auction: &Auction) -> Result<auction::Auction, Error> {
                     Class::Limit => order::Class::Limit,
                 },
                 partially_fillable: order.partially_fillable,
+                signed: order::Signed {
+                    sell_amount: order.full_sell_amount,
+                    buy_amount: order.full_buy_amount,
+                    receiver: order.receiver,
+                    valid_to: order.valid_to,
+                    app_data: order.app_data.0,
+                    sell_token_balance: match order.sell_token_source {
+                        SellTokenSource::Erc20 => order::SellTokenBalance::Erc20,
+                        SellTokenSource::External => order::SellTokenBalance::External,
+                        SellTokenSource::Internal => order::SellTokenBalance::Internal,
+                    },
+                    buy_token_balance: match order.buy_token_destination {
+                        BuyTokenDestination::Erc20 => order::BuyTokenBalance::Erc20,
+                        BuyTokenDestination::Internal => order::BuyTokenBalance::Internal,
+                    },
+                    signing_scheme: match order.signing_scheme {
+                        SigningScheme::Eip712 => order::SigningScheme::Eip712,
+                        SigningScheme::EthSign => order::SigningScheme::EthSign,
+                        SigningScheme::Eip1271 => order::SigningScheme::Eip1271,
+                        SigningScheme::PreSign => order::SigningScheme::PreSign,
+                    },
+                    signature: order.signature.clone(),
+                    pre_interactions: interactions_to_domain(&order.pre_interactions),
+                    post_interactions: interactions_to_domain(&order.post_interactions),
+                },
             })
             .collect(),
         gas_price: auction::GasPrice(eth::Ether(auction.effective_gas_price)),
         deadline: auction::Deadline(auction.deadline),
     })
 }
+
+/// Checks whether an order can be solved, reporting every invalid field. The
+/// UIDs of the orders checked so far are used to detect duplicates.
+fn validate_order(
+    i: usize,
+    order: &Order,
+    is_known_token: impl Fn(&Address) -> bool,
+    uids: &mut HashMap<[u8; 56], usize>,
+) -> Vec<InvalidField> {
+    let mut invalid = Vec::new();
+    let mut push = |path: String, kind, description: String| {
+        invalid.push(InvalidField {
+            path,
+            kind,
+            description,
+        })
+    };
+
+    if let Some(first) = uids.insert(order.uid, i) {
+        push(
+            format!("orders[{i}].uid"),
+            InvalidFieldKind::DuplicateUid,
+            format!("same UID as orders[{first}]"),
+        );
+    }
+    for (field, amount) in [
+        ("sellAmount", order.sell_amount),
+        ("buyAmount", order.buy_amount),
+        ("fullSellAmount", order.full_sell_amount),
+        ("fullBuyAmount", order.full_buy_amount),
+    ] {
+        if amount.is_zero() {
+            push(
+                format!("orders[{i}].{field}"),
+                InvalidFieldKind::ZeroAmount,
+                "amount must not be zero".to_owned(),
+            );
+        }
+    }
+    for (field, token) in [
+        ("sellToken", order.sell_token),
+        ("buyToken", order.buy_token),
+    ] {
+        if !is_known_token(&token) {
+            push(
+                format!("orders[{i}].{field}"),
+                InvalidFieldKind::UnknownToken,
+                format!("token {token} is missing from tokens"),
+            );
+        }
+    }
+
+    invalid
+}
+
+fn deadline_passed(deadline: DateTime<Utc>) -> Option<InvalidField> {
+    (deadline <= Utc::now()).then(|| InvalidField {
+        path: "deadline".to_owned(),
+        kind: InvalidFieldKind::DeadlinePassed,
+        description: format!("deadline {deadline} already passed"),
+    })
+}
+
+fn interactions_to_domain(interactions: &[InteractionData]) -> Vec<eth::Interaction> {
+    interactions
+        .iter()
+        .map(|interaction| eth::Interaction {
+            target: interaction.target,
+            value: eth::Ether(interaction.value),
+            calldata: interaction.call_data.clone(),
+        })
+        .collect()
+}
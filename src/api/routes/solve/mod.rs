@@ -1,21 +1,44 @@
 use {super::Response, tracing::Instrument};
 
-mod dto;
+pub(crate) mod dto;
 
-use {crate::domain::solver::Solver, std::sync::Arc};
+use {
+    crate::{
+        domain::{auction, solver::Solver},
+        infra::metrics,
+    },
+    axum::response::IntoResponse,
+    std::{sync::Arc, time::Duration},
+    tokio::sync::Semaphore,
+};
+
+/// The maximum time to handle a request, if configured.
+#[derive(Clone, Copy, Debug)]
+pub struct RequestTimeout(pub Duration);
 
 pub async fn solve(
     state: axum::extract::State<Arc<Solver>>,
-    auction: axum::extract::Json<dto::Auction>,
+    timeout: Option<axum::Extension<RequestTimeout>>,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
 ) -> (
     axum::http::StatusCode,
     axum::response::Json<Response<dto::SolverResponse>>,
 ) {
     let handle_request = async {
-        let auction = match dto::auction::to_domain(&auction) {
-            Ok(value) => value,
+        if !is_json(&headers) {
+            tracing::warn!("auction is not JSON");
+            return (
+                axum::http::StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                axum::response::Json(Response::Err(
+                    "expected request with `Content-Type: application/json`".into(),
+                )),
+            );
+        }
+        let mut auction = match dto::auction::solvable_from_json(&body) {
+            Ok(auction) => auction,
             Err(err) => {
-                tracing::warn!(?err, "invalid auction");
+                tracing::warn!(%err, "invalid auction");
                 return (
                     axum::http::StatusCode::BAD_REQUEST,
                     axum::response::Json(Response::Err(err)),
@@ -23,6 +46,19 @@ pub async fn solve(
             }
         };
 
+        // The solver stops shortly before the deadline and returns the
+        // solutions it found until then. Requests that time out before the
+        // auction deadline stop solving at the request timeout instead, so
+        // that these solutions get returned rather than a timeout.
+        if let Some(axum::Extension(RequestTimeout(timeout))) = timeout
+            && let Ok(timeout) = chrono::Duration::from_std(timeout)
+        {
+            let timeout = auction::Deadline(chrono::Utc::now() + timeout);
+            if timeout.0 < auction.deadline.0 {
+                auction.deadline = timeout;
+            }
+        }
+
         let auction_id = auction.id;
         let solutions = state
             .solve(auction)
@@ -42,3 +78,38 @@ pub async fn solve(
         .instrument(tracing::info_span!("/solve"))
         .await
 }
+
+/// Whether the request declares a JSON body with `application/json` or an
+/// `application/*+json` content type, like [`axum::Json`] requires.
+fn is_json(headers: &axum::http::HeaderMap) -> bool {
+    let Some(content_type) = headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return false;
+    };
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    essence
+        .strip_prefix("application/")
+        .is_some_and(|subtype| subtype == "json" || subtype.ends_with("+json"))
+}
+
+/// Rejects requests while the maximum number of auctions are being solved
+/// concurrently.
+pub async fn limit_concurrency(
+    state: axum::extract::State<Arc<Semaphore>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let Ok(_permit) = state.try_acquire() else {
+        tracing::debug!("too many concurrent auctions; rejecting request");
+        metrics::api_rejection("too_many_solves");
+        return axum::http::StatusCode::SERVICE_UNAVAILABLE.into_response();
+    };
+    next.run(request).await
+}
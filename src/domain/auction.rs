@@ -80,6 +80,16 @@ impl Price {
             .checked_mul(U256::from(Self::BASE))?
             .checked_div(self.0.0)
     }
+
+    /// Computes the [`eth::Ether`] value of the specified token amount at the
+    /// given price.
+    pub fn in_eth(&self, amount: U256) -> Option<eth::Ether> {
+        Some(eth::Ether(
+            amount
+                .checked_mul(self.0.0)?
+                .checked_div(U256::from(Self::BASE))?,
+        ))
+    }
 }
 
 /// The estimated effective gas price that will likely be used for executing the
@@ -105,3 +115,24 @@ impl Deadline {
         Self(self.0 - duration)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn price_in_eth() {
+        // Half an ETH for 10**18 of the token.
+        let price = Price(eth::Ether(U256::from(500_000_000_000_000_000_u64)));
+        assert_eq!(
+            price
+                .in_eth(U256::from(3_000_000_000_000_000_000_u64))
+                .unwrap()
+                .0,
+            U256::from(1_500_000_000_000_000_000_u64),
+        );
+        // Amounts are rounded down to the wei.
+        assert_eq!(price.in_eth(U256::from(3)).unwrap().0, U256::from(1));
+        assert!(price.in_eth(U256::MAX).is_none());
+    }
+}
@@ -26,6 +26,18 @@ impl MinimumSurplusLimits {
         Ok(Self { relative, absolute })
     }
 
+    /// Returns a copy of the limits with the specified values overridden.
+    pub fn with_overrides(
+        &self,
+        relative: Option<BigDecimal>,
+        absolute: Option<eth::Ether>,
+    ) -> Self {
+        Self {
+            relative: relative.unwrap_or_else(|| self.relative.clone()),
+            absolute: absolute.or(self.absolute),
+        }
+    }
+
     /// Returns the minimum surplus for the specified token amount.
     pub fn relative(&self, asset: &eth::Asset, tokens: &auction::Tokens) -> MinimumSurplus {
         let absolute_as_relative = shared::absolute_to_relative(self.absolute, asset, tokens);
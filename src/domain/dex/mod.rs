@@ -13,6 +13,7 @@ use {
 };
 
 pub mod minimum_surplus;
+pub mod overrides;
 mod shared;
 pub mod slippage;
 
@@ -54,6 +55,20 @@ impl Order {
             amount: self.amount.0,
         }
     }
+
+    /// Returns the order with the native token replaced by its wrapped
+    /// version, since DEX APIs only quote ERC20 tokens. The wrapped native
+    /// token gets unwrapped as part of the solution.
+    pub fn with_wrapped_native_token(self, wrapped: eth::WrappedNativeToken) -> Self {
+        if self.buy == eth::NATIVE_TOKEN {
+            Self {
+                buy: wrapped.0,
+                ..self
+            }
+        } else {
+            self
+        }
+    }
 }
 
 /// An on-chain Ethereum call for executing a DEX swap.
@@ -62,6 +77,8 @@ pub struct Call {
     pub to: Address,
     /// The associated calldata for the on-chain call.
     pub calldata: Vec<u8>,
+    /// The amount of native token sent along with the call.
+    pub value: eth::Ether,
 }
 
 impl Debug for Call {
@@ -69,6 +86,7 @@ impl Debug for Call {
         f.debug_struct("Call")
             .field("to", &self.to)
             .field("calldata", &util::fmt::Hex(&self.calldata))
+            .field("value", &self.value)
             .finish()
     }
 }
@@ -104,52 +122,141 @@ impl Swap {
         }
     }
 
-    /// Constructs a single order `solution::Solution` for this swap. Returns
-    /// `None` if the swap is not valid for the specified order.
-    pub async fn into_solution(
+    /// Returns the unwraps of the wrapped native token needed for executing
+    /// the swap for the specified order. Returns `None` if the swap requires
+    /// native token input without selling the wrapped native token.
+    pub fn unwraps(
+        &self,
+        order: &order::Order,
+        wrapped: eth::WrappedNativeToken,
+    ) -> Option<Unwraps> {
+        // Routes that require native token input get it by unwrapping the
+        // sold wrapped native token.
+        let value = self
+            .calls
+            .iter()
+            .try_fold(U256::ZERO, |total, call| total.checked_add(call.value.0))?;
+        if !value.is_zero() && self.input.token != wrapped.0 {
+            tracing::debug!(
+                ?value,
+                "swap requires native token input without selling the wrapped native token"
+            );
+            return None;
+        }
+
+        // Orders buying the native token get quoted for the wrapped native
+        // token, so unwrap the swap output before paying out the order.
+        let output = order.buy.token == eth::NATIVE_TOKEN && self.output.token == wrapped.0;
+
+        Some(Unwraps {
+            input: (!value.is_zero()).then_some(value),
+            output: output.then_some(self.output.amount),
+        })
+    }
+
+    /// Returns the calls executing the swap including the unwraps, in the
+    /// order they get executed.
+    pub fn interactions(
+        &self,
+        unwraps: &Unwraps,
+        wrapped: eth::WrappedNativeToken,
+    ) -> Vec<eth::Interaction> {
+        unwraps
+            .input
+            .map(|amount| wrapped.withdraw(amount))
+            .into_iter()
+            .chain(self.calls.iter().map(|call| eth::Interaction {
+                target: call.to,
+                value: call.value,
+                calldata: call.calldata.clone(),
+            }))
+            .chain(unwraps.output.map(|amount| wrapped.withdraw(amount)))
+            .collect()
+    }
+
+    /// The heuristic gas needed for executing the swap for the specified
+    /// order, which is the DEX API estimate plus the gas of the unwraps.
+    pub fn heuristic_gas(
+        &self,
+        order: &order::Order,
+        wrapped: eth::WrappedNativeToken,
+    ) -> eth::Gas {
+        self.gas
+            + self
+                .unwraps(order, wrapped)
+                .map(|unwraps| unwraps.gas())
+                .unwrap_or_default()
+    }
+
+    /// Simulates the gas needed for executing the swap for the specified
+    /// order, including the unwraps. The gas of limit orders gets simulated,
+    /// since it determines the surplus fee. Returns `None` if the heuristic
+    /// gas should be used instead.
+    pub async fn simulated_gas(
+        &self,
+        order: &order::Order,
+        simulator: &infra::dex::Simulator,
+        wrapped: eth::WrappedNativeToken,
+    ) -> Result<Option<eth::Gas>, infra::dex::simulator::Error> {
+        if order.class != order::Class::Limit {
+            // We are fine with just using heuristic gas for market orders,
+            // since it doesn't really play a role in the final solution.
+            return Ok(None);
+        }
+        let Some(unwraps) = self.unwraps(order, wrapped) else {
+            // The swap doesn't make for a solution anyway.
+            return Ok(None);
+        };
+        match simulator.gas(order.owner(), self, &unwraps, wrapped).await {
+            Ok(gas) => Ok(gas),
+            Err(infra::dex::simulator::Error::SettlementContractIsOwner) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Constructs a single order `solution::Solution` for this swap, using
+    /// the gas computed with [`Swap::simulated_gas`] or
+    /// [`Swap::heuristic_gas`], which include the unwraps. Returns `None` if
+    /// the swap is not valid for the specified order.
+    pub fn into_solution(
         self,
         order: order::Order,
+        gas: eth::Gas,
         gas_price: auction::GasPrice,
         sell_token: Option<auction::Price>,
-        simulator: &infra::dex::Simulator,
         gas_offset: eth::Gas,
+        wrapped: eth::WrappedNativeToken,
     ) -> Option<solution::Solution> {
-        let gas = if order.class == order::Class::Limit {
-            match simulator.gas(order.owner(), &self).await {
-                Ok(value) => value,
-                Err(infra::dex::simulator::Error::SettlementContractIsOwner) => self.gas,
-                Err(err) => {
-                    tracing::warn!(?err, "gas simulation failed");
-                    return None;
-                }
-            }
-        } else {
-            // We are fine with just using heuristic gas for market orders,
-            // since it doesn't really play a role in the final solution.
-            self.gas
-        };
-
+        let unwraps = self.unwraps(&order, wrapped)?;
         let allowance = self.allowance();
-        let interactions = self
-            .calls
-            .into_iter()
-            .map(|call| {
-                solution::Interaction::Custom(solution::CustomInteraction {
-                    target: call.to,
-                    value: eth::Ether::default(),
-                    calldata: call.calldata,
-                    inputs: vec![self.input],
-                    outputs: vec![self.output],
-                    internalize: false,
-                    allowances: vec![allowance],
-                })
+        let mut interactions = Vec::new();
+
+        if let Some(amount) = unwraps.input {
+            interactions.push(unwrap(wrapped, amount));
+        }
+
+        interactions.extend(self.calls.into_iter().map(|call| {
+            solution::Interaction::Custom(solution::CustomInteraction {
+                target: call.to,
+                value: call.value,
+                calldata: call.calldata,
+                inputs: vec![self.input],
+                outputs: vec![self.output],
+                internalize: false,
+                allowances: vec![allowance],
             })
-            .collect();
+        }));
+
+        let mut output = self.output;
+        if let Some(amount) = unwraps.output {
+            interactions.push(unwrap(wrapped, amount));
+            output.token = eth::NATIVE_TOKEN;
+        }
 
         solution::Single {
             order,
             input: self.input,
-            output: self.output,
+            output,
             interactions,
             gas,
         }
@@ -176,6 +283,52 @@ impl Swap {
     }
 }
 
+/// The approximate gas needed for unwrapping the wrapped native token.
+const UNWRAP_GAS: eth::Gas = eth::Gas(U256::from_limbs([30_000, 0, 0, 0]));
+
+/// The unwraps of the wrapped native token needed for executing a swap.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Unwraps {
+    /// The amount unwrapped before the swap, for calls that need native token
+    /// input.
+    pub input: Option<U256>,
+    /// The amount unwrapped after the swap, for orders buying the native
+    /// token.
+    pub output: Option<U256>,
+}
+
+impl Unwraps {
+    /// The approximate gas needed for the unwraps.
+    pub fn gas(&self) -> eth::Gas {
+        [self.input, self.output]
+            .into_iter()
+            .flatten()
+            .fold(eth::Gas::default(), |gas, _| gas + UNWRAP_GAS)
+    }
+}
+
+/// Creates the interaction for unwrapping `amount` of the wrapped native
+/// token. The interaction is never internalized, since the settlement
+/// contract needs the native token it unwraps for the swap or the payout.
+fn unwrap(wrapped: eth::WrappedNativeToken, amount: U256) -> solution::Interaction {
+    let withdraw = wrapped.withdraw(amount);
+    solution::Interaction::Custom(solution::CustomInteraction {
+        target: withdraw.target,
+        value: withdraw.value,
+        calldata: withdraw.calldata,
+        inputs: vec![eth::Asset {
+            token: wrapped.0,
+            amount,
+        }],
+        outputs: vec![eth::Asset {
+            token: eth::NATIVE_TOKEN,
+            amount,
+        }],
+        internalize: false,
+        allowances: vec![],
+    })
+}
+
 /// A swap allowance.
 #[derive(Debug)]
 pub struct Allowance {
@@ -199,3 +352,104 @@ impl Amount {
         self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(buy: eth::TokenAddress, class: order::Class) -> order::Order {
+        order::test_order(
+            eth::Asset {
+                token: eth::TokenAddress(Address::repeat_byte(1)),
+                amount: U256::from(100),
+            },
+            eth::Asset {
+                token: buy,
+                amount: U256::from(100),
+            },
+            order::Side::Sell,
+            class,
+        )
+    }
+
+    fn swap(input: eth::TokenAddress, output: eth::TokenAddress, value: u64) -> Swap {
+        Swap {
+            calls: vec![Call {
+                to: Address::repeat_byte(3),
+                calldata: vec![0x42],
+                value: eth::Ether(U256::from(value)),
+            }],
+            input: eth::Asset {
+                token: input,
+                amount: U256::from(100),
+            },
+            output: eth::Asset {
+                token: output,
+                amount: U256::from(200),
+            },
+            allowance: Allowance {
+                spender: Address::repeat_byte(3),
+                amount: Amount::new(U256::from(100)),
+            },
+            gas: eth::Gas(U256::from(100_000)),
+        }
+    }
+
+    #[test]
+    fn unwraps_around_the_swap() {
+        let wrapped = eth::WrappedNativeToken(eth::TokenAddress(Address::repeat_byte(2)));
+        let order = order(eth::NATIVE_TOKEN, order::Class::Limit);
+        let swap = swap(wrapped.0, wrapped.0, 50);
+
+        let unwraps = swap.unwraps(&order, wrapped).unwrap();
+        assert_eq!(
+            unwraps,
+            Unwraps {
+                input: Some(U256::from(50)),
+                output: Some(U256::from(200)),
+            }
+        );
+        assert_eq!(
+            swap.heuristic_gas(&order, wrapped).0,
+            U256::from(100_000) + UNWRAP_GAS.0 + UNWRAP_GAS.0,
+        );
+
+        // The native token input is unwrapped before the value-carrying call
+        // and the output after it, so that simulations execute the same calls
+        // as the solution.
+        let interactions = swap.interactions(&unwraps, wrapped);
+        let calls = interactions
+            .iter()
+            .map(|interaction| (interaction.target, interaction.calldata.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            calls,
+            vec![
+                (wrapped.0.0, wrapped.withdraw(U256::from(50)).calldata),
+                (Address::repeat_byte(3), vec![0x42]),
+                (wrapped.0.0, wrapped.withdraw(U256::from(200)).calldata),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_unwraps_for_erc20_swaps() {
+        let wrapped = eth::WrappedNativeToken(eth::TokenAddress(Address::repeat_byte(2)));
+        let order = order(wrapped.0, order::Class::Market);
+        let swap = swap(eth::TokenAddress(Address::repeat_byte(1)), wrapped.0, 0);
+
+        let unwraps = swap.unwraps(&order, wrapped).unwrap();
+        assert_eq!(unwraps, Unwraps::default());
+        assert_eq!(swap.interactions(&unwraps, wrapped).len(), 1);
+        assert_eq!(swap.heuristic_gas(&order, wrapped).0, U256::from(100_000));
+    }
+
+    #[test]
+    fn native_token_input_requires_selling_the_wrapped_native_token() {
+        let wrapped = eth::WrappedNativeToken(eth::TokenAddress(Address::repeat_byte(2)));
+        let order = order(eth::NATIVE_TOKEN, order::Class::Market);
+        let swap = swap(eth::TokenAddress(Address::repeat_byte(1)), wrapped.0, 50);
+
+        assert!(swap.unwraps(&order, wrapped).is_none());
+    }
+}
@@ -0,0 +1,311 @@
+//! Overrides of the slippage, minimum surplus and gas offset parameters for
+//! specific tokens, token pairs or order classes.
+
+use {
+    crate::domain::{
+        dex::{minimum_surplus::MinimumSurplusLimits, slippage::SlippageLimits},
+        eth,
+        order,
+    },
+    bigdecimal::{BigDecimal, One, Zero},
+    std::borrow::Cow,
+};
+
+/// The parameters used by DEX solvers for solving an order.
+#[derive(Clone, Debug)]
+pub struct Parameters {
+    pub slippage: SlippageLimits,
+    pub minimum_surplus: MinimumSurplusLimits,
+    pub gas_offset: eth::Gas,
+}
+
+/// The tokens an override applies to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Tokens {
+    /// Matches all orders.
+    Any,
+    /// Matches orders that buy or sell the token.
+    Token(eth::TokenAddress),
+    /// Matches orders trading between the two tokens, in either direction.
+    Pair(eth::TokenAddress, eth::TokenAddress),
+}
+
+/// An override of the solver parameters for the orders that it matches.
+#[derive(Clone, Debug)]
+pub struct Override {
+    pub tokens: Tokens,
+    pub class: Option<order::Class>,
+    pub relative_slippage: Option<BigDecimal>,
+    pub absolute_slippage: Option<eth::Ether>,
+    pub relative_minimum_surplus: Option<BigDecimal>,
+    pub absolute_minimum_surplus: Option<eth::Ether>,
+    pub gas_offset: Option<eth::Gas>,
+}
+
+impl Override {
+    fn matches(
+        &self,
+        sell: eth::TokenAddress,
+        buy: eth::TokenAddress,
+        class: order::Class,
+    ) -> bool {
+        let tokens = match self.tokens {
+            Tokens::Any => true,
+            Tokens::Token(token) => token == sell || token == buy,
+            Tokens::Pair(a, b) => (a, b) == (sell, buy) || (a, b) == (buy, sell),
+        };
+        tokens && self.class.is_none_or(|c| c == class)
+    }
+
+    /// Overrides with a higher precedence take priority over the ones with a
+    /// lower precedence when both match an order.
+    fn precedence(&self) -> u8 {
+        let tokens = match self.tokens {
+            Tokens::Pair(..) => 4,
+            Tokens::Token(_) => 2,
+            Tokens::Any => 0,
+        };
+        tokens + u8::from(self.class.is_some())
+    }
+
+    fn is_same_matcher(&self, other: &Self) -> bool {
+        let tokens = match (self.tokens, other.tokens) {
+            (Tokens::Pair(a, b), Tokens::Pair(c, d)) => (a, b) == (c, d) || (a, b) == (d, c),
+            (a, b) => a == b,
+        };
+        tokens && self.class == other.class
+    }
+}
+
+/// A set of parameter overrides.
+///
+/// When multiple overrides match an order, each parameter is taken from the
+/// matching override with the highest precedence that sets it, falling back
+/// to the default parameters. From highest to lowest, the precedence is:
+/// 1. token pair and order class
+/// 2. token pair
+/// 3. token and order class
+/// 4. token
+/// 5. order class
+///
+/// Overrides with the same precedence are applied in the order in which they
+/// were specified.
+#[derive(Clone, Debug, Default)]
+pub struct Overrides(Vec<Override>);
+
+impl Overrides {
+    /// Creates a new set of overrides, verifying that they are valid and
+    /// unambiguous.
+    pub fn new(overrides: Vec<Override>) -> Result<Self, anyhow::Error> {
+        for (i, o) in overrides.iter().enumerate() {
+            anyhow::ensure!(
+                o.tokens != Tokens::Any || o.class.is_some(),
+                "override #{i} must match on a token, a token pair or an order class"
+            );
+            if let Tokens::Pair(a, b) = o.tokens {
+                anyhow::ensure!(a != b, "override #{i} specifies a pair of identical tokens");
+            }
+            anyhow::ensure!(
+                o.relative_slippage.is_some()
+                    || o.absolute_slippage.is_some()
+                    || o.relative_minimum_surplus.is_some()
+                    || o.absolute_minimum_surplus.is_some()
+                    || o.gas_offset.is_some(),
+                "override #{i} does not override any parameter"
+            );
+            if let Some(relative) = &o.relative_slippage {
+                anyhow::ensure!(
+                    *relative >= BigDecimal::zero() && *relative <= BigDecimal::one(),
+                    "override #{i}: slippage relative tolerance must be in the range [0, 1]"
+                );
+            }
+            if let Some(relative) = &o.relative_minimum_surplus {
+                anyhow::ensure!(
+                    *relative >= BigDecimal::zero(),
+                    "override #{i}: minimum surplus relative tolerance must be non-negative"
+                );
+            }
+            if let Some(j) = overrides[..i]
+                .iter()
+                .position(|other| o.is_same_matcher(other))
+            {
+                anyhow::bail!("overrides #{j} and #{i} match the same orders");
+            }
+        }
+
+        let mut overrides = overrides;
+        // Stable sort, so overrides with the same precedence keep their order.
+        overrides.sort_by_key(|o| std::cmp::Reverse(o.precedence()));
+        Ok(Self(overrides))
+    }
+
+    /// Returns the parameters to use for the specified order.
+    pub fn resolve<'a>(
+        &self,
+        order: &order::Order,
+        defaults: &'a Parameters,
+    ) -> Cow<'a, Parameters> {
+        self.resolve_for(order.sell.token, order.buy.token, order.class, defaults)
+    }
+
+    fn resolve_for<'a>(
+        &self,
+        sell: eth::TokenAddress,
+        buy: eth::TokenAddress,
+        class: order::Class,
+        defaults: &'a Parameters,
+    ) -> Cow<'a, Parameters> {
+        let matching = self
+            .0
+            .iter()
+            .filter(|o| o.matches(sell, buy, class))
+            .collect::<Vec<_>>();
+        if matching.is_empty() {
+            return Cow::Borrowed(defaults);
+        }
+
+        Cow::Owned(Parameters {
+            slippage: defaults.slippage.with_overrides(
+                matching.iter().find_map(|o| o.relative_slippage.clone()),
+                matching.iter().find_map(|o| o.absolute_slippage),
+            ),
+            minimum_surplus: defaults.minimum_surplus.with_overrides(
+                matching
+                    .iter()
+                    .find_map(|o| o.relative_minimum_surplus.clone()),
+                matching.iter().find_map(|o| o.absolute_minimum_surplus),
+            ),
+            gas_offset: matching
+                .iter()
+                .find_map(|o| o.gas_offset)
+                .unwrap_or(defaults.gas_offset),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, alloy::primitives::U256};
+
+    fn token(byte: u8) -> eth::TokenAddress {
+        eth::TokenAddress(eth::Address::repeat_byte(byte))
+    }
+
+    fn defaults() -> Parameters {
+        Parameters {
+            slippage: SlippageLimits::new("0.01".parse().unwrap(), None).unwrap(),
+            minimum_surplus: MinimumSurplusLimits::new(BigDecimal::zero(), None).unwrap(),
+            gas_offset: eth::Gas(U256::from(100_000)),
+        }
+    }
+
+    fn with(tokens: Tokens, class: Option<order::Class>) -> Override {
+        Override {
+            tokens,
+            class,
+            relative_slippage: None,
+            absolute_slippage: None,
+            relative_minimum_surplus: None,
+            absolute_minimum_surplus: None,
+            gas_offset: None,
+        }
+    }
+
+    #[test]
+    fn resolves_by_precedence() {
+        let overrides = Overrides::new(vec![
+            Override {
+                gas_offset: Some(eth::Gas(U256::from(1))),
+                ..with(Tokens::Any, Some(order::Class::Limit))
+            },
+            Override {
+                relative_slippage: Some("0.02".parse().unwrap()),
+                gas_offset: Some(eth::Gas(U256::from(2))),
+                ..with(Tokens::Token(token(1)), None)
+            },
+            Override {
+                relative_slippage: Some("0.0001".parse().unwrap()),
+                ..with(Tokens::Pair(token(2), token(1)), None)
+            },
+        ])
+        .unwrap();
+        let defaults = defaults();
+        let resolve = |sell, buy, class| {
+            let parameters = overrides.resolve_for(sell, buy, class, &defaults);
+            (
+                parameters
+                    .slippage
+                    .relative(
+                        &eth::Asset {
+                            token: sell,
+                            amount: U256::from(1),
+                        },
+                        &crate::domain::auction::Tokens(Default::default()),
+                    )
+                    .as_factor()
+                    .clone(),
+                parameters.gas_offset.0,
+            )
+        };
+
+        // No override matches.
+        assert_eq!(
+            resolve(token(3), token(4), order::Class::Market),
+            ("0.01".parse().unwrap(), U256::from(100_000)),
+        );
+        // Class override.
+        assert_eq!(
+            resolve(token(3), token(4), order::Class::Limit),
+            ("0.01".parse().unwrap(), U256::from(1)),
+        );
+        // Token overrides take priority over class overrides.
+        assert_eq!(
+            resolve(token(3), token(1), order::Class::Limit),
+            ("0.02".parse().unwrap(), U256::from(2)),
+        );
+        // Pair overrides take priority over token overrides, unset parameters
+        // fall back to the next matching override.
+        assert_eq!(
+            resolve(token(1), token(2), order::Class::Market),
+            ("0.0001".parse().unwrap(), U256::from(2)),
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_overrides() {
+        let slippage = |tokens, class| Override {
+            relative_slippage: Some("0.01".parse().unwrap()),
+            ..with(tokens, class)
+        };
+
+        // Matches everything.
+        assert!(Overrides::new(vec![slippage(Tokens::Any, None)]).is_err());
+        // Does not override anything.
+        assert!(Overrides::new(vec![with(Tokens::Token(token(1)), None)]).is_err());
+        // Invalid pair.
+        assert!(Overrides::new(vec![slippage(Tokens::Pair(token(1), token(1)), None)]).is_err());
+        // Out of range slippage.
+        assert!(
+            Overrides::new(vec![Override {
+                relative_slippage: Some("2".parse().unwrap()),
+                ..with(Tokens::Token(token(1)), None)
+            }])
+            .is_err()
+        );
+        // Ambiguous overrides.
+        assert!(
+            Overrides::new(vec![
+                slippage(Tokens::Pair(token(1), token(2)), None),
+                slippage(Tokens::Pair(token(2), token(1)), None),
+            ])
+            .is_err()
+        );
+        assert!(
+            Overrides::new(vec![
+                slippage(Tokens::Pair(token(1), token(2)), None),
+                slippage(Tokens::Pair(token(2), token(1)), Some(order::Class::Limit)),
+            ])
+            .is_ok()
+        );
+    }
+}
@@ -1,12 +1,37 @@
 //! Slippage tolerance computation for DEX swaps.
 
 use {
-    crate::domain::{auction, dex::shared, eth},
+    crate::{
+        domain::{
+            auction,
+            dex::{self, shared},
+            eth,
+        },
+        infra,
+        util::conv,
+    },
     alloy::primitives::U256,
     bigdecimal::{BigDecimal, One, ToPrimitive, Zero},
-    std::cmp,
+    std::{
+        cmp,
+        collections::{HashMap, HashSet},
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    },
 };
 
+/// The minimum time between two quotes of a pair for their rates to be
+/// compared. Quotes within the same block only differ by their size, which
+/// says nothing about the volatility of the pair.
+const MIN_OBSERVATION_INTERVAL: Duration = Duration::from_secs(12);
+
+/// The time after which the observed volatility of a pair has halved if the
+/// pair doesn't get quoted again.
+const VOLATILITY_HALF_LIFE: Duration = Duration::from_secs(10 * 60);
+
+/// How long the volatility of a pair is remembered after its last quote.
+const VOLATILITY_MEMORY: Duration = Duration::from_secs(60 * 60);
+
 /// DEX swap slippage limits.
 #[derive(Clone, Debug)]
 pub struct SlippageLimits {
@@ -14,6 +39,8 @@ pub struct SlippageLimits {
     relative: BigDecimal,
     /// The maximum absolute slippage allowed for swaps.
     absolute: Option<eth::Ether>,
+    /// Optional model for adjusting the relative slippage per token pair.
+    dynamic: Option<Arc<DynamicSlippage>>,
 }
 
 impl SlippageLimits {
@@ -23,17 +50,271 @@ impl SlippageLimits {
             relative >= BigDecimal::zero() && relative <= BigDecimal::one(),
             "slippage relative tolerance must be in the range [0, 1]"
         );
-        Ok(Self { relative, absolute })
+        Ok(Self {
+            relative,
+            absolute,
+            dynamic: None,
+        })
+    }
+
+    /// Returns `self` with the relative slippage derived from the specified
+    /// dynamic slippage model instead of being fixed for all tokens.
+    pub fn with_dynamic(self, dynamic: DynamicSlippage) -> Self {
+        Self {
+            dynamic: Some(Arc::new(dynamic)),
+            ..self
+        }
+    }
+
+    /// Returns a copy of the limits with the specified values overridden. An
+    /// overridden relative slippage is used as is, without the dynamic
+    /// slippage model.
+    pub fn with_overrides(
+        &self,
+        relative: Option<BigDecimal>,
+        absolute: Option<eth::Ether>,
+    ) -> Self {
+        Self {
+            dynamic: match relative {
+                Some(_) => None,
+                None => self.dynamic.clone(),
+            },
+            relative: relative.unwrap_or_else(|| self.relative.clone()),
+            absolute: absolute.or(self.absolute),
+        }
+    }
+
+    /// Returns `self` with the pair volatility observed by `previous`, so
+    /// that replacing the limits at runtime doesn't reset the dynamic slippage
+    /// model.
+    pub fn with_observations_from(self, previous: &Self) -> Self {
+        if let (Some(dynamic), Some(previous)) = (&self.dynamic, &previous.dynamic) {
+            let observed = previous.volatility.lock().unwrap().clone();
+            *dynamic.volatility.lock().unwrap() = observed;
+        }
+        self
+    }
+
+    /// Records the rate of a swap quoted by the DEX API for estimating the
+    /// volatility of its token pair. This is a no-op unless dynamic slippage
+    /// is configured.
+    pub fn observe(&self, swap: &dex::Swap) {
+        if let Some(dynamic) = &self.dynamic {
+            dynamic.observe(&swap.input, &swap.output, Instant::now());
+        }
+    }
+
+    /// Returns the slippage for the amount of the specified order.
+    pub fn relative(&self, order: &dex::Order, tokens: &auction::Tokens) -> Slippage {
+        let asset = order.amount();
+        let absolute_as_relative = shared::absolute_to_relative(self.absolute, &asset, tokens);
+        let relative = match &self.dynamic {
+            Some(dynamic) => {
+                dynamic.relative(order.sell, order.buy, &self.relative, Instant::now())
+            }
+            None => self.relative.clone(),
+        };
+        let relative = cmp::min(relative, absolute_as_relative.unwrap_or(BigDecimal::one()));
+
+        // Record the slippage that actually gets applied, i.e. after the
+        // absolute slippage cap.
+        if let Some(dynamic) = &self.dynamic {
+            infra::metrics::slippage(dynamic.class(&order.sell, &order.buy), &relative);
+        }
+
+        Slippage::new(relative)
+    }
+}
+
+/// A slippage model that derives the relative slippage tolerance of a token
+/// pair from its class and its recently observed volatility.
+///
+/// Volatility is estimated as an exponentially weighted moving average of the
+/// relative change of the rate the DEX API quotes for a pair across blocks.
+/// Without new quotes the estimate decays, and it is forgotten after an hour.
+/// The resulting slippage is the base slippage for the pair class plus the
+/// volatility scaled by a configurable factor, capped to a maximum.
+#[derive(Debug)]
+pub struct DynamicSlippage {
+    /// Tokens that are considered stable (e.g. stablecoins).
+    stable_tokens: HashSet<eth::TokenAddress>,
+    /// The base relative slippage for stable tokens.
+    stable_relative: BigDecimal,
+    /// The factor by which the observed volatility increases the slippage.
+    volatility_factor: BigDecimal,
+    /// The weight of the most recent observation in the volatility average.
+    smoothing: f64,
+    /// The maximum relative slippage that the model can choose.
+    max_relative: BigDecimal,
+    /// Volatility estimates per token pair, keyed by the tokens in ascending
+    /// order.
+    volatility: Mutex<HashMap<(eth::TokenAddress, eth::TokenAddress), Volatility>>,
+}
+
+/// The class of a token pair for the purpose of determining its base
+/// slippage. Pairs of two stable tokens are stable.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TokenClass {
+    Stable,
+    Volatile,
+}
+
+impl TokenClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Volatile => "volatile",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Volatility {
+    /// The last quoted rate, in the second token of the pair per the first.
+    rate: BigDecimal,
+    /// When the rate was quoted.
+    observed: Instant,
+    /// The moving average of the relative rate change between quotes.
+    average: f64,
+}
+
+impl Volatility {
+    /// Returns the volatility estimate, decayed by the time since the pair
+    /// was last quoted.
+    fn at(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.observed);
+        self.average * 0.5_f64.powf(elapsed.as_secs_f64() / VOLATILITY_HALF_LIFE.as_secs_f64())
+    }
+}
+
+impl DynamicSlippage {
+    /// Creates a new dynamic slippage model.
+    pub fn new(
+        stable_tokens: impl IntoIterator<Item = eth::TokenAddress>,
+        stable_relative: BigDecimal,
+        volatility_factor: BigDecimal,
+        smoothing: f64,
+        max_relative: BigDecimal,
+    ) -> Result<Self, anyhow::Error> {
+        anyhow::ensure!(
+            stable_relative >= BigDecimal::zero() && stable_relative <= BigDecimal::one(),
+            "stable relative slippage must be in the range [0, 1]"
+        );
+        anyhow::ensure!(
+            max_relative >= BigDecimal::zero() && max_relative <= BigDecimal::one(),
+            "maximum relative slippage must be in the range [0, 1]"
+        );
+        anyhow::ensure!(
+            volatility_factor >= BigDecimal::zero(),
+            "volatility factor must not be negative"
+        );
+        anyhow::ensure!(
+            smoothing > 0.0 && smoothing <= 1.0,
+            "volatility smoothing factor must be in the range (0, 1]"
+        );
+        Ok(Self {
+            stable_tokens: stable_tokens.into_iter().collect(),
+            stable_relative,
+            volatility_factor,
+            smoothing,
+            max_relative,
+            volatility: Default::default(),
+        })
+    }
+
+    /// Returns the class of the specified token pair.
+    pub fn class(&self, a: &eth::TokenAddress, b: &eth::TokenAddress) -> TokenClass {
+        if self.stable_tokens.contains(a) && self.stable_tokens.contains(b) {
+            TokenClass::Stable
+        } else {
+            TokenClass::Volatile
+        }
+    }
+
+    /// Updates the volatility estimate of a token pair with a quoted swap.
+    fn observe(&self, input: &eth::Asset, output: &eth::Asset, now: Instant) {
+        if input.amount.is_zero() || output.amount.is_zero() {
+            return;
+        }
+        let (pair, rate) = if input.token < output.token {
+            (
+                (input.token, output.token),
+                conv::u256_to_bigdecimal(&output.amount) / conv::u256_to_bigdecimal(&input.amount),
+            )
+        } else {
+            (
+                (output.token, input.token),
+                conv::u256_to_bigdecimal(&input.amount) / conv::u256_to_bigdecimal(&output.amount),
+            )
+        };
+
+        let mut volatility = self.volatility.lock().unwrap();
+        volatility
+            .retain(|_, entry| now.saturating_duration_since(entry.observed) < VOLATILITY_MEMORY);
+        match volatility.get_mut(&pair) {
+            Some(entry) => {
+                if now.saturating_duration_since(entry.observed) < MIN_OBSERVATION_INTERVAL {
+                    return;
+                }
+                let change = ((&rate - &entry.rate) / &entry.rate)
+                    .abs()
+                    .to_f64()
+                    .unwrap_or_default();
+                entry.average = self.smoothing * change + (1.0 - self.smoothing) * entry.at(now);
+                entry.rate = rate;
+                entry.observed = now;
+            }
+            None => {
+                volatility.insert(
+                    pair,
+                    Volatility {
+                        rate,
+                        observed: now,
+                        average: 0.0,
+                    },
+                );
+            }
+        }
     }
 
-    /// Returns the slippage for the specified token amount.
-    pub fn relative(&self, asset: &eth::Asset, tokens: &auction::Tokens) -> Slippage {
-        let absolute_as_relative = shared::absolute_to_relative(self.absolute, asset, tokens);
+    /// Returns the relative slippage for the specified token pair. Volatile
+    /// pairs use `default` as their base slippage.
+    fn relative(
+        &self,
+        sell: eth::TokenAddress,
+        buy: eth::TokenAddress,
+        default: &BigDecimal,
+        now: Instant,
+    ) -> BigDecimal {
+        let class = self.class(&sell, &buy);
+        let base = match class {
+            TokenClass::Stable => &self.stable_relative,
+            TokenClass::Volatile => default,
+        };
+        let volatility = self
+            .volatility
+            .lock()
+            .unwrap()
+            .get(&(sell.min(buy), sell.max(buy)))
+            .map(|entry| entry.at(now))
+            .unwrap_or_default();
+        let volatility = BigDecimal::try_from(volatility).unwrap_or_default();
+
+        let relative = cmp::min(
+            (base + &self.volatility_factor * &volatility).round(6),
+            self.max_relative.clone(),
+        );
+
+        tracing::debug!(
+            ?sell,
+            ?buy,
+            class = class.as_str(),
+            %volatility,
+            %relative,
+            "chose dynamic slippage"
+        );
 
-        Slippage::new(cmp::min(
-            self.relative.clone(),
-            absolute_as_relative.unwrap_or(BigDecimal::one()),
-        ))
+        relative
     }
 }
 
@@ -86,11 +367,22 @@ mod tests {
     use {
         super::*,
         crate::{
-            domain::{auction, eth},
+            domain::{auction, eth, order},
             util::conv,
         },
     };
 
+    /// An order selling the specified asset.
+    fn sell(asset: &eth::Asset) -> dex::Order {
+        dex::Order {
+            sell: asset.token,
+            buy: eth::TokenAddress(eth::Address::ZERO),
+            side: order::Side::Sell,
+            amount: dex::Amount::new(asset.amount),
+            owner: eth::Address::ZERO,
+        }
+    }
+
     #[test]
     fn slippage_tolerance() {
         let token = |t: &str| eth::TokenAddress(t.parse().unwrap());
@@ -197,7 +489,7 @@ mod tests {
             let min = U256::from(min);
             let max = U256::from(max);
 
-            let computed = slippage.relative(&asset, &tokens);
+            let computed = slippage.relative(&sell(&asset), &tokens);
 
             assert_eq!(computed.round(9), relative);
             assert_eq!(computed.sub(asset.amount), min);
@@ -249,7 +541,199 @@ mod tests {
         };
 
         // This should not panic and should return the relative slippage (1%)
-        let computed = slippage.relative(&asset_with_zero_amount, &tokens);
+        let computed = slippage.relative(&sell(&asset_with_zero_amount), &tokens);
         assert_eq!(computed.as_factor(), &"0.01".parse::<BigDecimal>().unwrap());
     }
+
+    #[test]
+    fn dynamic_slippage_from_class_and_pair_volatility() {
+        let weth = eth::TokenAddress(
+            "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
+                .parse()
+                .unwrap(),
+        );
+        let usdc = eth::TokenAddress(
+            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+                .parse()
+                .unwrap(),
+        );
+        let dai = eth::TokenAddress(
+            "0x6B175474E89094C44Da98b954EedeAC495271d0F"
+                .parse()
+                .unwrap(),
+        );
+        let asset = |token, amount: u128| eth::Asset {
+            token,
+            amount: U256::from(amount),
+        };
+        let relative = |dynamic: &DynamicSlippage, sell, buy, now| {
+            dynamic.relative(sell, buy, &"0.01".parse().unwrap(), now)
+        };
+
+        let dynamic = DynamicSlippage::new(
+            [usdc, dai],
+            "0.001".parse().unwrap(),
+            "2".parse().unwrap(),
+            0.5,
+            "0.05".parse().unwrap(),
+        )
+        .unwrap();
+        let start = Instant::now();
+
+        // Without any observed volatility, the base slippage of the pair class
+        // is used. Pairs with a single stable token are volatile.
+        dynamic.observe(
+            &asset(usdc, 3_000_000_000),
+            &asset(weth, 1_000_000_000_000_000_000),
+            start,
+        );
+        dynamic.observe(
+            &asset(usdc, 1_000_000_000),
+            &asset(dai, 1_000_000_000_000_000_000_000),
+            start,
+        );
+        assert_eq!(
+            relative(&dynamic, usdc, weth, start),
+            "0.01".parse().unwrap()
+        );
+        assert_eq!(
+            relative(&dynamic, dai, usdc, start),
+            "0.001".parse().unwrap()
+        );
+
+        // ETH moves by 2% against USDC, which, with a smoothing factor of 0.5,
+        // results in a volatility of 1% for the pair. The stablecoin pair keeps
+        // its rate and doesn't pick up the volatility of ETH.
+        let later = start + MIN_OBSERVATION_INTERVAL;
+        dynamic.observe(
+            &asset(usdc, 3_000_000_000),
+            &asset(weth, 1_020_000_000_000_000_000),
+            later,
+        );
+        dynamic.observe(
+            &asset(dai, 1_000_000_000_000_000_000_000),
+            &asset(usdc, 1_000_000_000),
+            later,
+        );
+        assert_eq!(
+            relative(&dynamic, weth, usdc, later),
+            "0.03".parse().unwrap()
+        );
+        assert_eq!(
+            relative(&dynamic, usdc, dai, later),
+            "0.001".parse().unwrap()
+        );
+        assert_eq!(
+            relative(&dynamic, weth, dai, later),
+            "0.01".parse().unwrap()
+        );
+
+        // Quotes within the same block don't count as price moves.
+        dynamic.observe(
+            &asset(usdc, 3_000_000_000),
+            &asset(weth, 1_100_000_000_000_000_000),
+            later + Duration::from_secs(1),
+        );
+        assert_eq!(
+            relative(&dynamic, weth, usdc, later),
+            "0.03".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn dynamic_slippage_decays_without_quotes() {
+        let token = |byte| eth::TokenAddress(eth::Address::repeat_byte(byte));
+        let asset = |token, amount: u128| eth::Asset {
+            token,
+            amount: U256::from(amount),
+        };
+        let relative = |dynamic: &DynamicSlippage, now| {
+            dynamic.relative(token(1), token(2), &"0.01".parse().unwrap(), now)
+        };
+
+        let dynamic = DynamicSlippage::new(
+            [],
+            "0.001".parse().unwrap(),
+            "2".parse().unwrap(),
+            0.5,
+            "0.05".parse().unwrap(),
+        )
+        .unwrap();
+        let start = Instant::now();
+        dynamic.observe(&asset(token(1), 100), &asset(token(2), 100), start);
+        let moved = start + MIN_OBSERVATION_INTERVAL;
+        dynamic.observe(&asset(token(1), 100), &asset(token(2), 102), moved);
+        assert_eq!(relative(&dynamic, moved), "0.03".parse().unwrap());
+
+        // The volatility halves with every half-life without new quotes.
+        assert_eq!(
+            relative(&dynamic, moved + VOLATILITY_HALF_LIFE),
+            "0.02".parse().unwrap()
+        );
+
+        // Pairs that don't get quoted for a while are forgotten.
+        dynamic.observe(
+            &asset(token(3), 100),
+            &asset(token(4), 100),
+            moved + VOLATILITY_MEMORY,
+        );
+        assert_eq!(dynamic.volatility.lock().unwrap().len(), 1);
+        assert_eq!(
+            relative(&dynamic, moved + VOLATILITY_MEMORY),
+            "0.01".parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn overridden_relative_slippage_ignores_dynamic_slippage() {
+        let usdc = eth::TokenAddress(
+            "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+                .parse()
+                .unwrap(),
+        );
+        let dai = eth::TokenAddress(
+            "0x6B175474E89094C44Da98b954EedeAC495271d0F"
+                .parse()
+                .unwrap(),
+        );
+        let order = dex::Order {
+            sell: usdc,
+            buy: dai,
+            side: order::Side::Sell,
+            amount: dex::Amount::new(U256::from(1_000_000_000_u128)),
+            owner: eth::Address::ZERO,
+        };
+        let tokens = auction::Tokens(Default::default());
+
+        let slippage = SlippageLimits::new("0.01".parse().unwrap(), None)
+            .unwrap()
+            .with_dynamic(
+                DynamicSlippage::new(
+                    [usdc, dai],
+                    "0.001".parse().unwrap(),
+                    "2".parse().unwrap(),
+                    0.5,
+                    "0.05".parse().unwrap(),
+                )
+                .unwrap(),
+            );
+        assert_eq!(
+            slippage.relative(&order, &tokens),
+            Slippage::new("0.001".parse().unwrap())
+        );
+
+        // Overriding only the absolute slippage keeps the dynamic slippage.
+        let absolute = slippage.with_overrides(None, Some(eth::Ether(U256::from(1))));
+        assert_eq!(
+            absolute.relative(&order, &tokens),
+            Slippage::new("0.001".parse().unwrap())
+        );
+
+        // An overridden relative slippage applies to stable pairs as well.
+        let relative = slippage.with_overrides(Some("0.005".parse().unwrap()), None);
+        assert_eq!(
+            relative.relative(&order, &tokens),
+            Slippage::new("0.005".parse().unwrap())
+        );
+    }
 }
@@ -1,4 +1,9 @@
-use {serde::Deserialize, std::str::FromStr};
+use {
+    super::{TokenAddress, WrappedNativeToken},
+    alloy::primitives::address,
+    serde::{Deserialize, Serialize},
+    std::str::FromStr,
+};
 
 /// A supported Ethereum Chain ID.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -58,6 +63,24 @@ impl ChainId {
     pub fn value(self) -> u64 {
         self as u64
     }
+
+    /// Returns the canonical wrapped version of the chain's native token.
+    pub fn wrapped_native_token(self) -> WrappedNativeToken {
+        WrappedNativeToken(TokenAddress(match self {
+            ChainId::Mainnet => address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+            ChainId::Goerli => address!("B4FBF271143F4FBf7B91A5ded31805e42b2208d6"),
+            ChainId::Gnosis => address!("e91D153E0b41518A2Ce8Dd3D7944Fa863463a97d"),
+            ChainId::Base | ChainId::Optimism | ChainId::Ink => {
+                address!("4200000000000000000000000000000000000006")
+            }
+            ChainId::ArbitrumOne => address!("82aF49447D8a07e3bd95BD0d56f35241523fBab1"),
+            ChainId::Bnb => address!("bb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c"),
+            ChainId::Avalanche => address!("B31f66AA3C1e785363F0875A1B74E27b85FD66c7"),
+            ChainId::Polygon => address!("0d500B1d8E8eF31E21C99d1Db9A6444d3ADf1270"),
+            ChainId::Linea => address!("e5D7C2a44FfDDf6b295A15c148167daaAf5Cf34f"),
+            ChainId::Plasma => address!("6100E367285b01F48D07953803A2d8dCA5D19873"),
+        }))
+    }
 }
 
 impl<'de> Deserialize<'de> for ChainId {
@@ -88,12 +111,30 @@ impl<'de> Deserialize<'de> for ChainId {
             {
                 ChainId::new(v).map_err(E::custom)
             }
+
+            // TOML integers are signed.
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                let chain_id = u64::try_from(v).map_err(E::custom)?;
+                ChainId::new(chain_id).map_err(E::custom)
+            }
         }
 
         deserializer.deserialize_any(ChainIdVisitor)
     }
 }
 
+impl Serialize for ChainId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_u64(self.value())
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 #[error("unsupported chain")]
 pub struct UnsupportedChain;
@@ -195,6 +236,26 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_supported_chains_toml_integer() {
+        #[derive(serde::Deserialize)]
+        struct Config {
+            chain_id: ChainId,
+        }
+
+        assert_eq!(
+            toml::from_str::<Config>("chain_id = 1").unwrap().chain_id,
+            ChainId::Mainnet
+        );
+        assert_eq!(
+            toml::from_str::<Config>("chain_id = '100'")
+                .unwrap()
+                .chain_id,
+            ChainId::Gnosis
+        );
+        toml::from_str::<Config>("chain_id = -1").unwrap_err();
+    }
+
     #[test]
     fn test_unsupported_chains() {
         serde_json::from_str::<ChainId>("0").unwrap_err();
@@ -2,7 +2,7 @@ mod chain;
 
 pub use {
     self::chain::ChainId,
-    alloy::primitives::{Address, U256},
+    alloy::primitives::{Address, B256, U256},
 };
 
 /// A contract address.
@@ -21,6 +21,30 @@ impl From<Address> for TokenAddress {
     }
 }
 
+/// The marker address CoW Protocol uses for the chain's native token (e.g.
+/// Ether on Mainnet). It can only be used as the buy token of an order.
+pub const NATIVE_TOKEN: TokenAddress = TokenAddress(Address::repeat_byte(0xee));
+
+/// The wrapped native token of a chain (e.g. WETH on Mainnet). Swaps involving
+/// the native token get routed through it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct WrappedNativeToken(pub TokenAddress);
+
+impl WrappedNativeToken {
+    /// The selector of the `withdraw(uint256)` function.
+    const WITHDRAW: [u8; 4] = [0x2e, 0x1a, 0x7d, 0x4d];
+
+    /// Returns the interaction that unwraps `amount` of the wrapped native
+    /// token held by the caller into the native token.
+    pub fn withdraw(&self, amount: U256) -> Interaction {
+        Interaction {
+            target: self.0.0,
+            value: Ether::default(),
+            calldata: [&Self::WITHDRAW[..], &amount.to_be_bytes::<32>()].concat(),
+        }
+    }
+}
+
 /// An asset on the Ethereum blockchain. Represents a particular amount of a
 /// particular token.
 #[derive(Debug, Clone, Copy)]
@@ -51,9 +75,13 @@ impl std::ops::Add for Gas {
     }
 }
 
+/// A transaction hash.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TxHash(pub B256);
+
 /// An arbitrary ethereum interaction that is required for the settlement
 /// execution.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Interaction {
     pub target: Address,
     pub value: Ether,
@@ -3,6 +3,8 @@
 pub mod auction;
 pub mod dex;
 pub mod eth;
+pub mod notification;
 pub mod order;
+pub mod quote;
 pub mod solution;
 pub mod solver;
@@ -0,0 +1,77 @@
+//! Notifications about what happened to the solutions of this solver engine
+//! after they were submitted to the driver.
+
+use {
+    crate::domain::{auction, eth, solution},
+    std::collections::BTreeSet,
+};
+
+/// A notification about a solution that was submitted for an auction.
+#[derive(Debug)]
+pub struct Notification {
+    pub auction_id: auction::Id,
+    /// The solutions the notification refers to, if any. Solutions can be
+    /// merged by the driver, in which case the notification refers to all of
+    /// them.
+    pub solution_ids: Vec<solution::Id>,
+    pub kind: Kind,
+}
+
+/// What happened to a solution.
+#[derive(Debug)]
+pub enum Kind {
+    /// The solver engine didn't respond before the deadline.
+    Timeout,
+    /// The solution contained no trades.
+    EmptySolution,
+    /// The solution ID was used for more than one solution.
+    DuplicatedSolutionId,
+    /// The settlement transaction of the solution failed to simulate.
+    SimulationFailed { succeeded_once: bool },
+    /// The solution has invalid clearing prices.
+    InvalidClearingPrices,
+    /// The solution is missing a clearing price for a traded token.
+    MissingPrice(eth::TokenAddress),
+    /// The solution executes invalid amounts of an order.
+    InvalidExecutedAmount,
+    /// The solution uses the settlement contract buffers for tokens that are
+    /// not allowed to be internalized.
+    NonBufferableTokensUsed(BTreeSet<eth::TokenAddress>),
+    /// The solver account doesn't have enough Ether to submit the solution.
+    SolverAccountInsufficientBalance(eth::Ether),
+    /// The solution was settled on chain.
+    Settled(eth::TxHash),
+    /// The settlement transaction of the solution reverted on chain.
+    Reverted(eth::TxHash),
+    /// The driver failed to process the solution.
+    DriverError(String),
+    /// The solution was not submitted because the auction got cancelled.
+    Cancelled,
+    /// The solution failed to settle for any other reason.
+    Failed,
+    /// Any kind of notification this solver engine doesn't know about.
+    Unknown,
+}
+
+impl Kind {
+    /// Returns the label used for logging and metrics.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::EmptySolution => "empty_solution",
+            Self::DuplicatedSolutionId => "duplicated_solution_id",
+            Self::SimulationFailed { .. } => "simulation_failed",
+            Self::InvalidClearingPrices => "invalid_clearing_prices",
+            Self::MissingPrice(_) => "missing_price",
+            Self::InvalidExecutedAmount => "invalid_executed_amount",
+            Self::NonBufferableTokensUsed(_) => "non_bufferable_tokens_used",
+            Self::SolverAccountInsufficientBalance(_) => "solver_account_insufficient_balance",
+            Self::Settled(_) => "settled",
+            Self::Reverted(_) => "reverted",
+            Self::DriverError(_) => "driver_error",
+            Self::Cancelled => "cancelled",
+            Self::Failed => "failed",
+            Self::Unknown => "unknown",
+        }
+    }
+}
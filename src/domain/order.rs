@@ -14,6 +14,7 @@ pub struct Order {
     pub side: Side,
     pub class: Class,
     pub partially_fillable: bool,
+    pub signed: Signed,
 }
 
 impl Order {
@@ -28,6 +29,21 @@ impl Order {
     }
 }
 
+/// A fill-or-kill order for tests, with a UID of all ones and the signed
+/// amounts of its assets.
+#[cfg(test)]
+pub fn test_order(sell: eth::Asset, buy: eth::Asset, side: Side, class: Class) -> Order {
+    Order {
+        uid: Uid([1; 56]),
+        sell,
+        buy,
+        side,
+        class,
+        partially_fillable: false,
+        signed: Signed::synthetic(sell.amount, buy.amount),
+    }
+}
+
 /// UID of an order.
 #[derive(Clone, Copy, Eq, Hash, PartialEq)]
 pub struct Uid(pub [u8; 56]);
@@ -61,3 +77,73 @@ pub enum Class {
     Market,
     Limit,
 }
+
+/// The order data exactly as it was signed by the owner. This is only needed
+/// for encoding the order as a trade of a settlement.
+#[derive(Debug, Clone)]
+pub struct Signed {
+    /// The full sell amount of the order, regardless of how much of it was
+    /// already filled.
+    pub sell_amount: eth::U256,
+    /// The full buy amount of the order, regardless of how much of it was
+    /// already filled.
+    pub buy_amount: eth::U256,
+    pub receiver: Option<eth::Address>,
+    pub valid_to: u32,
+    pub app_data: [u8; 32],
+    pub sell_token_balance: SellTokenBalance,
+    pub buy_token_balance: BuyTokenBalance,
+    pub signing_scheme: SigningScheme,
+    pub signature: Vec<u8>,
+    /// Interactions that the settlement executes before transferring the
+    /// order's sell tokens in.
+    pub pre_interactions: Vec<eth::Interaction>,
+    /// Interactions that the settlement executes after transferring the
+    /// order's buy tokens out.
+    pub post_interactions: Vec<eth::Interaction>,
+}
+
+impl Signed {
+    /// The signed data of an order that nobody signed, such as the order a
+    /// quote is computed for: it is valid indefinitely, has no app data,
+    /// interactions or signature, and trades ERC20 balances.
+    pub fn synthetic(sell_amount: eth::U256, buy_amount: eth::U256) -> Self {
+        Self {
+            sell_amount,
+            buy_amount,
+            receiver: None,
+            valid_to: u32::MAX,
+            app_data: [0; 32],
+            sell_token_balance: SellTokenBalance::Erc20,
+            buy_token_balance: BuyTokenBalance::Erc20,
+            signing_scheme: SigningScheme::PreSign,
+            signature: Vec::new(),
+            pre_interactions: Vec::new(),
+            post_interactions: Vec::new(),
+        }
+    }
+}
+
+/// Where the sell tokens of an order are transferred from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SellTokenBalance {
+    Erc20,
+    External,
+    Internal,
+}
+
+/// Where the buy tokens of an order are transferred to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuyTokenBalance {
+    Erc20,
+    Internal,
+}
+
+/// The scheme that was used for signing an order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningScheme {
+    Eip712,
+    EthSign,
+    Eip1271,
+    PreSign,
+}
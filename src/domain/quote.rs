@@ -0,0 +1,55 @@
+//! Quoting a single swap outside of an auction.
+
+use crate::domain::{auction, eth, order, solution};
+
+/// A request for quoting a single swap.
+#[derive(Debug)]
+pub struct Request {
+    pub sell: eth::TokenAddress,
+    pub buy: eth::TokenAddress,
+    pub side: order::Side,
+    /// The sell amount for sell quotes and the buy amount for buy quotes.
+    pub amount: eth::U256,
+    pub owner: eth::Address,
+    /// Optional decimals and reference prices of the traded tokens, used by
+    /// the DEX APIs and the slippage and fee computations that need them.
+    pub tokens: auction::Tokens,
+    pub gas_price: auction::GasPrice,
+}
+
+/// A quoted swap.
+#[derive(Debug)]
+pub struct Quote {
+    pub sell: eth::Asset,
+    pub buy: eth::Asset,
+    pub gas: eth::Gas,
+    /// The calls executing the swap.
+    pub calls: Vec<eth::Interaction>,
+    /// The solution the swap would turn into for a market order at the
+    /// quoted price, if the swap can be turned into a solution.
+    pub solution: Option<solution::Solution>,
+}
+
+impl Request {
+    /// Returns a market order that is filled exactly by a swap with the
+    /// specified executed amounts.
+    pub fn order(&self, sell: eth::U256, buy: eth::U256) -> order::Order {
+        let mut uid = [0; 56];
+        uid[32..52].copy_from_slice(self.owner.as_slice());
+        order::Order {
+            uid: order::Uid(uid),
+            sell: eth::Asset {
+                token: self.sell,
+                amount: sell,
+            },
+            buy: eth::Asset {
+                token: self.buy,
+                amount: buy,
+            },
+            side: self.side,
+            class: order::Class::Market,
+            partially_fillable: false,
+            signed: order::Signed::synthetic(sell, buy),
+        }
+    }
+}
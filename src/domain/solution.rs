@@ -34,6 +34,14 @@ impl Solution {
         Self { id, ..self }
     }
 
+    /// Returns `self` with the specified gas estimate.
+    pub fn with_gas(self, gas: eth::Gas) -> Self {
+        Self {
+            gas: Some(gas),
+            ..self
+        }
+    }
+
     /// Returns `self` with eligible interactions internalized using the
     /// Settlement contract buffers.
     ///
@@ -287,6 +295,63 @@ impl Fulfillment {
             amount: self.fee.surplus()?,
         })
     }
+
+    /// Returns the surplus of the trade at the specified clearing prices. The
+    /// surplus is in the buy token for sell orders and in the sell token for
+    /// buy orders.
+    pub fn surplus(&self, prices: &ClearingPrices) -> Option<Surplus> {
+        let order = &self.order;
+        let sell_price = prices.0.get(&order.sell.token)?;
+        let buy_price = prices.0.get(&order.buy.token)?;
+        let fee = self.fee.surplus().unwrap_or_default();
+        // The surplus is `high - low`, and negative if the limit isn't met.
+        let (token, high, low) = match order.side {
+            order::Side::Sell => {
+                let bought = self
+                    .executed
+                    .checked_mul(*sell_price)?
+                    .checked_div(*buy_price)?;
+                let limit = order
+                    .buy
+                    .amount
+                    .checked_mul(self.executed.checked_add(fee)?)?
+                    .checked_div(order.sell.amount)?;
+                (order.buy.token, bought, limit)
+            }
+            order::Side::Buy => {
+                let sold = self
+                    .executed
+                    .checked_mul(*buy_price)?
+                    .checked_div(*sell_price)?
+                    .checked_add(fee)?;
+                let limit = order
+                    .sell
+                    .amount
+                    .checked_mul(self.executed)?
+                    .checked_div(order.buy.amount)?;
+                (order.sell.token, limit, sold)
+            }
+        };
+        Some(if high >= low {
+            Surplus::Positive(eth::Asset {
+                token,
+                amount: high - low,
+            })
+        } else {
+            Surplus::Negative(eth::Asset {
+                token,
+                amount: low - high,
+            })
+        })
+    }
+}
+
+/// The surplus of a trade. It is negative if the trade doesn't satisfy the
+/// limit price of the order at the clearing prices.
+#[derive(Clone, Copy, Debug)]
+pub enum Surplus {
+    Positive(eth::Asset),
+    Negative(eth::Asset),
 }
 
 /// The fee that is charged to a user for executing an order.
@@ -346,3 +411,98 @@ pub struct Allowance {
     pub spender: eth::Address,
     pub asset: eth::Asset,
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::domain::eth::Address};
+
+    fn token(byte: u8) -> eth::TokenAddress {
+        eth::TokenAddress(Address::repeat_byte(byte))
+    }
+
+    fn order(side: order::Side, class: order::Class, sell: u64, buy: u64) -> order::Order {
+        order::test_order(
+            eth::Asset {
+                token: token(1),
+                amount: U256::from(sell),
+            },
+            eth::Asset {
+                token: token(2),
+                amount: U256::from(buy),
+            },
+            side,
+            class,
+        )
+    }
+
+    fn prices(sell: u64, buy: u64) -> ClearingPrices {
+        ClearingPrices(HashMap::from([
+            (token(1), U256::from(sell)),
+            (token(2), U256::from(buy)),
+        ]))
+    }
+
+    fn surplus(
+        fulfillment: &Fulfillment,
+        prices: &ClearingPrices,
+    ) -> (bool, eth::TokenAddress, u64) {
+        match fulfillment.surplus(prices).unwrap() {
+            Surplus::Positive(asset) => (true, asset.token, asset.amount.to()),
+            Surplus::Negative(asset) => (false, asset.token, asset.amount.to()),
+        }
+    }
+
+    #[test]
+    fn sell_order_surplus() {
+        let market = Fulfillment::new(
+            order(order::Side::Sell, order::Class::Market, 100, 200),
+            U256::from(100),
+            Fee::Protocol,
+        )
+        .unwrap();
+        assert_eq!(surplus(&market, &prices(3, 1)), (true, token(2), 100));
+        assert_eq!(surplus(&market, &prices(1, 1)), (false, token(2), 100));
+
+        // The surplus fee counts towards the executed amount for the limit.
+        let limit = Fulfillment::new(
+            order(order::Side::Sell, order::Class::Limit, 100, 200),
+            U256::from(90),
+            Fee::Surplus(U256::from(10)),
+        )
+        .unwrap();
+        assert_eq!(surplus(&limit, &prices(3, 1)), (true, token(2), 70));
+    }
+
+    #[test]
+    fn buy_order_surplus() {
+        let market = Fulfillment::new(
+            order(order::Side::Buy, order::Class::Market, 100, 200),
+            U256::from(200),
+            Fee::Protocol,
+        )
+        .unwrap();
+        assert_eq!(surplus(&market, &prices(4, 1)), (true, token(1), 50));
+        assert_eq!(surplus(&market, &prices(1, 1)), (false, token(1), 100));
+
+        // The surplus fee gets sold on top of the executed amount.
+        let limit = Fulfillment::new(
+            order(order::Side::Buy, order::Class::Limit, 100, 200),
+            U256::from(200),
+            Fee::Surplus(U256::from(10)),
+        )
+        .unwrap();
+        assert_eq!(surplus(&limit, &prices(4, 1)), (true, token(1), 40));
+    }
+
+    #[test]
+    fn surplus_requires_clearing_prices() {
+        let fulfillment = Fulfillment::new(
+            order(order::Side::Sell, order::Class::Market, 100, 200),
+            U256::from(100),
+            Fee::Protocol,
+        )
+        .unwrap();
+        let prices = ClearingPrices(HashMap::from([(token(1), U256::from(1))]));
+        assert!(fulfillment.surplus(&prices).is_none());
+    }
+}
@@ -0,0 +1,342 @@
+use {
+    crate::domain::{auction, eth, notification, order, solution},
+    std::{
+        collections::{HashMap, VecDeque},
+        sync::Mutex,
+        time::{Duration, Instant},
+    },
+};
+
+/// The number of recent auctions for which the traded orders are remembered.
+/// Notifications about a solution arrive a few blocks after solving at most.
+const AUCTIONS: usize = 100;
+
+/// How long an order is backed off after its first revert. The backoff doubles
+/// with every further revert of the order.
+const BACKOFF: Duration = Duration::from_secs(60);
+
+/// The longest time an order gets backed off for.
+const MAX_BACKOFF: Duration = Duration::from_secs(60 * 60);
+
+/// The number of on-chain reverts of orders trading a token pair after which
+/// the pair is considered suspicious (e.g. because one of the tokens takes a
+/// fee on transfer).
+const SUSPICIOUS_REVERTS: u32 = 3;
+
+/// How long reverts of orders trading a token pair are remembered.
+const REVERT_MEMORY: Duration = Duration::from_secs(60 * 60);
+
+/// Feeds the outcomes of submitted solutions back into solving. Orders whose
+/// settlements reverted get backed off, and token pairs that are involved in
+/// repeated on-chain reverts are skipped as suspicious.
+///
+/// Reverts are counted per token pair rather than per token, so that commonly
+/// traded tokens don't become suspicious because of unrelated reverts. Pairs
+/// of trusted tokens and the wrapped native token never become suspicious.
+#[derive(Debug)]
+pub struct Feedback {
+    wrapped_native_token: eth::WrappedNativeToken,
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// The orders traded by the solutions of recent auctions.
+    auctions: VecDeque<(i64, HashMap<u64, Vec<Trade>>)>,
+    backoffs: HashMap<order::Uid, Backoff>,
+    reverts: HashMap<Pair, Reverts>,
+}
+
+/// An order traded by a solution.
+#[derive(Clone, Copy, Debug)]
+pub struct Trade {
+    pub uid: order::Uid,
+    pub sell: eth::TokenAddress,
+    pub buy: eth::TokenAddress,
+    /// Whether both tokens are trusted, in which case reverts of the order
+    /// never make the pair suspicious.
+    pub trusted: bool,
+}
+
+impl Trade {
+    fn pair(&self) -> Pair {
+        Pair::new(self.sell, self.buy)
+    }
+}
+
+/// A token pair, regardless of the direction it is traded in.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Pair(pub eth::TokenAddress, pub eth::TokenAddress);
+
+impl Pair {
+    fn new(a: eth::TokenAddress, b: eth::TokenAddress) -> Self {
+        Self(a.min(b), a.max(b))
+    }
+}
+
+#[derive(Debug)]
+struct Backoff {
+    reverts: u32,
+    until: Instant,
+}
+
+#[derive(Debug)]
+struct Reverts {
+    count: u32,
+    last: Instant,
+}
+
+impl Reverts {
+    fn is_suspicious(&self, now: Instant) -> bool {
+        self.count >= SUSPICIOUS_REVERTS && now.duration_since(self.last) < REVERT_MEMORY
+    }
+}
+
+impl Feedback {
+    pub fn new(wrapped_native_token: eth::WrappedNativeToken) -> Self {
+        Self {
+            wrapped_native_token,
+            state: Default::default(),
+        }
+    }
+
+    /// Remembers the orders traded by the solutions of an auction, so that
+    /// notifications about these solutions can be attributed to them.
+    pub fn solved(&self, auction: &auction::Auction, solutions: &[solution::Solution]) {
+        let auction::Id::Solve(id) = auction.id else {
+            return;
+        };
+        let trusted = |token: &eth::TokenAddress| {
+            *token == self.wrapped_native_token.0
+                || auction.tokens.get(token).is_some_and(|token| token.trusted)
+        };
+        let trades = solutions
+            .iter()
+            .map(|solution| {
+                let trades = solution
+                    .trades
+                    .iter()
+                    .map(|trade| match trade {
+                        solution::Trade::Fulfillment(fulfillment) => {
+                            let order = fulfillment.order();
+                            Trade {
+                                uid: order.uid,
+                                sell: order.sell.token,
+                                buy: order.buy.token,
+                                trusted: trusted(&order.sell.token) && trusted(&order.buy.token),
+                            }
+                        }
+                    })
+                    .collect();
+                (solution.id.0, trades)
+            })
+            .collect();
+
+        let mut state = self.state.lock().unwrap();
+        state.auctions.push_back((id, trades));
+        while state.auctions.len() > AUCTIONS {
+            state.auctions.pop_front();
+        }
+
+        let now = Instant::now();
+        state
+            .backoffs
+            .retain(|_, backoff| now < backoff.until + MAX_BACKOFF);
+        state
+            .reverts
+            .retain(|_, reverts| now.duration_since(reverts.last) < REVERT_MEMORY);
+    }
+
+    /// Returns the orders traded by the solutions a notification refers to.
+    /// Orders of solutions that are no longer remembered are not returned.
+    pub fn trades(&self, notification: &notification::Notification) -> Vec<Trade> {
+        let auction::Id::Solve(auction) = notification.auction_id else {
+            return Vec::new();
+        };
+        let state = self.state.lock().unwrap();
+        let Some((_, solutions)) = state.auctions.iter().find(|(id, _)| *id == auction) else {
+            return Vec::new();
+        };
+        notification
+            .solution_ids
+            .iter()
+            .filter_map(|id| solutions.get(&id.0))
+            .flatten()
+            .copied()
+            .collect()
+    }
+
+    /// Backs off the orders of a solution that failed to settle.
+    pub fn failed(&self, trades: &[Trade]) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        for trade in trades {
+            let backoff = state.backoffs.entry(trade.uid).or_insert(Backoff {
+                reverts: 0,
+                until: now,
+            });
+            backoff.reverts += 1;
+            let duration = BACKOFF
+                .saturating_mul(2_u32.saturating_pow(backoff.reverts - 1))
+                .min(MAX_BACKOFF);
+            backoff.until = now + duration;
+            tracing::debug!(order = %trade.uid, ?duration, "backing off reverted order");
+        }
+    }
+
+    /// Backs off the orders of a solution that reverted on chain and counts
+    /// the revert for their token pairs. Returns the pairs that became
+    /// suspicious.
+    pub fn reverted(&self, trades: &[Trade]) -> Vec<Pair> {
+        self.failed(trades);
+
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let mut suspicious = Vec::new();
+        for trade in trades.iter().filter(|trade| !trade.trusted) {
+            let pair = trade.pair();
+            let reverts = state.reverts.entry(pair).or_insert(Reverts {
+                count: 0,
+                last: now,
+            });
+            let was_suspicious = reverts.is_suspicious(now);
+            reverts.count += 1;
+            reverts.last = now;
+            if !was_suspicious && reverts.is_suspicious(now) {
+                suspicious.push(pair);
+            }
+        }
+        suspicious
+    }
+
+    /// Forgets the reverts of the orders and token pairs of a settled
+    /// solution.
+    pub fn settled(&self, trades: &[Trade]) {
+        let mut state = self.state.lock().unwrap();
+        for trade in trades {
+            state.backoffs.remove(&trade.uid);
+            state.reverts.remove(&trade.pair());
+        }
+    }
+
+    /// Returns whether an order should not be solved because it is backed off
+    /// or trades a suspicious token pair.
+    pub fn is_backed_off(&self, order: &order::Order) -> bool {
+        let now = Instant::now();
+        let state = self.state.lock().unwrap();
+        state
+            .backoffs
+            .get(&order.uid)
+            .is_some_and(|backoff| now < backoff.until)
+            || state
+                .reverts
+                .get(&Pair::new(order.sell.token, order.buy.token))
+                .is_some_and(|reverts| reverts.is_suspicious(now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::domain::eth::Address};
+
+    fn order(uid: u8, sell: u8, buy: u8) -> order::Order {
+        order::Order {
+            uid: order::Uid([uid; 56]),
+            ..order::test_order(
+                eth::Asset {
+                    token: eth::TokenAddress(Address::repeat_byte(sell)),
+                    amount: eth::U256::from(1),
+                },
+                eth::Asset {
+                    token: eth::TokenAddress(Address::repeat_byte(buy)),
+                    amount: eth::U256::from(1),
+                },
+                order::Side::Sell,
+                order::Class::Market,
+            )
+        }
+    }
+
+    fn trade(order: &order::Order) -> Trade {
+        Trade {
+            uid: order.uid,
+            sell: order.sell.token,
+            buy: order.buy.token,
+            trusted: false,
+        }
+    }
+
+    fn feedback() -> Feedback {
+        Feedback::new(eth::WrappedNativeToken(eth::TokenAddress(
+            Address::repeat_byte(0xee),
+        )))
+    }
+
+    #[test]
+    fn backs_off_reverted_orders_until_settled() {
+        let feedback = feedback();
+        let reverted = order(1, 1, 2);
+        let other = order(2, 3, 4);
+
+        assert!(feedback.reverted(&[trade(&reverted)]).is_empty());
+        assert!(feedback.is_backed_off(&reverted));
+        assert!(!feedback.is_backed_off(&other));
+
+        feedback.settled(&[trade(&reverted)]);
+        assert!(!feedback.is_backed_off(&reverted));
+    }
+
+    #[test]
+    fn flags_pairs_with_repeated_reverts() {
+        let feedback = feedback();
+        for uid in 1..SUSPICIOUS_REVERTS as u8 {
+            assert!(feedback.reverted(&[trade(&order(uid, 1, 2))]).is_empty());
+        }
+        let suspicious = feedback.reverted(&[trade(&order(42, 2, 1))]);
+        assert_eq!(
+            suspicious,
+            [Pair(
+                eth::TokenAddress(Address::repeat_byte(1)),
+                eth::TokenAddress(Address::repeat_byte(2))
+            )]
+        );
+        assert!(feedback.is_backed_off(&order(43, 2, 1)));
+        assert!(!feedback.is_backed_off(&order(44, 1, 3)));
+    }
+
+    #[test]
+    fn does_not_flag_common_tokens_for_unrelated_reverts() {
+        let feedback = feedback();
+        for uid in 1..=SUSPICIOUS_REVERTS as u8 {
+            assert!(
+                feedback
+                    .reverted(&[trade(&order(uid, 1, uid + 1))])
+                    .is_empty()
+            );
+        }
+        assert!(!feedback.is_backed_off(&order(42, 1, 42)));
+    }
+
+    #[test]
+    fn does_not_flag_trusted_pairs() {
+        let feedback = feedback();
+        for uid in 1..=SUSPICIOUS_REVERTS as u8 {
+            let trade = Trade {
+                trusted: true,
+                ..trade(&order(uid, 0xee, 1))
+            };
+            assert!(feedback.reverted(&[trade]).is_empty());
+        }
+        assert!(!feedback.is_backed_off(&order(42, 0xee, 1)));
+    }
+
+    #[test]
+    fn does_not_flag_pairs_for_failed_simulations() {
+        let feedback = feedback();
+        for uid in 1..=SUSPICIOUS_REVERTS as u8 {
+            feedback.failed(&[trade(&order(uid, 1, 2))]);
+            assert!(feedback.is_backed_off(&order(uid, 1, 2)));
+        }
+        assert!(!feedback.is_backed_off(&order(42, 1, 2)));
+    }
+}
@@ -26,8 +26,6 @@ pub struct Fills {
     smallest_fill: BigDecimal,
 }
 
-const ETH: eth::TokenAddress = eth::TokenAddress(eth::Address::repeat_byte(0xee));
-
 impl Fills {
     pub fn new(smallest_fill: eth::Ether) -> Self {
         Self {
@@ -53,7 +51,7 @@ impl Fills {
         };
 
         let smallest_fill = self.smallest_fill.clone()
-            * conv::ether_to_decimal(&tokens.reference_price(&ETH)?.0)
+            * conv::ether_to_decimal(&tokens.reference_price(&eth::NATIVE_TOKEN)?.0)
             / conv::ether_to_decimal(&tokens.reference_price(&token)?.0);
         let smallest_fill = conv::bigdecimal_to_u256(&smallest_fill)?;
 
@@ -130,7 +128,7 @@ impl Fills {
                 token: order.buy.token,
                 amount: buy_amount,
             },
-            ..*order
+            ..order.clone()
         }))
     }
 
@@ -159,6 +157,29 @@ impl Fills {
         });
     }
 
+    /// Returns the fill amounts that are tried next for all orders.
+    pub fn entries(&self) -> Vec<Entry> {
+        let now = Instant::now();
+        self.amounts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(uid, entry)| Entry {
+                uid: *uid,
+                next_amount: entry.next_amount,
+                total_amount: entry.total_amount,
+                last_requested: now.duration_since(entry.last_requested),
+            })
+            .collect()
+    }
+
+    /// Forgets the fill amount to try next for an order, so that the next
+    /// attempt starts from the full amount again. Returns whether the order
+    /// had an entry.
+    pub fn reset(&self, uid: order::Uid) -> bool {
+        self.amounts.lock().unwrap().remove(&uid).is_some()
+    }
+
     /// Removes entries that have not been requested for a long time. This
     /// allows us to remove orders that got settled by other solvers which
     /// we are not able to notice.
@@ -173,6 +194,16 @@ impl Fills {
     }
 }
 
+/// The fill amount that is tried next for an order.
+#[derive(Debug)]
+pub struct Entry {
+    pub uid: order::Uid,
+    pub next_amount: eth::U256,
+    pub total_amount: eth::U256,
+    /// How long ago the order was last tried.
+    pub last_requested: Duration,
+}
+
 #[derive(Debug)]
 struct CacheEntry {
     next_amount: eth::U256,
@@ -0,0 +1,266 @@
+use {
+    crate::{
+        domain::{auction, dex, eth, order},
+        infra::metrics::{self, Outcome},
+    },
+    std::{
+        collections::VecDeque,
+        fmt::Display,
+        sync::{Arc, Mutex},
+    },
+};
+
+/// The number of recent auctions for which the order outcomes are kept.
+const AUCTIONS: usize = 20;
+
+/// The order outcomes of recently solved auctions, for debugging why orders
+/// don't get solved.
+#[derive(Debug, Default)]
+pub struct History {
+    auctions: Mutex<VecDeque<Auction>>,
+}
+
+/// A solved auction.
+#[derive(Clone, Debug)]
+pub struct Auction {
+    pub id: i64,
+    pub started: chrono::DateTime<chrono::Utc>,
+    pub orders: Vec<Order>,
+}
+
+/// The outcome of solving an order.
+#[derive(Clone, Debug)]
+pub struct Order {
+    pub uid: order::Uid,
+    /// The outcome of solving the order, or `None` if the order was skipped.
+    pub outcome: Option<Outcome>,
+    /// The error that caused the outcome, if any.
+    pub error: Option<String>,
+    /// The slippage tolerance the swap was requested with, if one was.
+    pub slippage: Option<dex::Slippage>,
+    /// The swap the DEX API found, if any.
+    pub swap: Option<Swap>,
+    /// The gas of the solution, once it was estimated.
+    pub gas: Option<eth::Gas>,
+}
+
+/// The amounts of a swap found by the DEX API.
+#[derive(Clone, Debug)]
+pub struct Swap {
+    pub input: eth::Asset,
+    pub output: eth::Asset,
+    /// The gas estimate of the DEX API.
+    pub gas: eth::Gas,
+}
+
+impl Order {
+    fn new(uid: order::Uid) -> Self {
+        Self {
+            uid,
+            outcome: None,
+            error: None,
+            slippage: None,
+            swap: None,
+            gas: None,
+        }
+    }
+}
+
+/// Collects the order outcomes of an auction while it is being solved.
+#[derive(Clone, Debug, Default)]
+pub struct Log(Arc<Mutex<Vec<Order>>>);
+
+impl Log {
+    /// Takes the order outcomes collected so far.
+    pub fn take(&self) -> Vec<Order> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl History {
+    /// Adds the order outcomes of a solved auction. Quotes are not kept.
+    pub fn record(
+        &self,
+        id: auction::Id,
+        started: chrono::DateTime<chrono::Utc>,
+        orders: Vec<Order>,
+    ) {
+        let auction::Id::Solve(id) = id else {
+            return;
+        };
+        let mut auctions = self.auctions.lock().unwrap();
+        auctions.push_back(Auction {
+            id,
+            started,
+            orders,
+        });
+        while auctions.len() > AUCTIONS {
+            auctions.pop_front();
+        }
+    }
+
+    /// Returns the recently solved auctions, most recent first.
+    pub fn auctions(&self) -> Vec<Auction> {
+        self.auctions
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Records the outcome of solving an order in the metrics and the log of the
+/// auction. An order that gets dropped without an outcome ran out of time.
+pub struct OrderOutcome {
+    metrics: metrics::OrderOutcome,
+    order: Order,
+    log: Log,
+    done: bool,
+}
+
+impl OrderOutcome {
+    pub fn new(dex: &'static str, order: &order::Order, log: &Log) -> Self {
+        Self {
+            metrics: metrics::OrderOutcome::new(dex, order),
+            order: Order::new(order.uid),
+            log: log.clone(),
+            done: false,
+        }
+    }
+
+    /// Notes the slippage tolerance the swap gets requested with.
+    pub fn slippage(&mut self, slippage: &dex::Slippage) {
+        self.order.slippage = Some(slippage.clone());
+    }
+
+    /// Notes the swap that the DEX API found.
+    pub fn swap(&mut self, swap: &dex::Swap) {
+        self.order.swap = Some(Swap {
+            input: swap.input,
+            output: swap.output,
+            gas: swap.gas,
+        });
+    }
+
+    /// Notes the estimated gas of the solution.
+    pub fn gas(&mut self, gas: eth::Gas) {
+        self.order.gas = Some(gas);
+    }
+
+    pub fn record(&mut self, outcome: Outcome) {
+        self.metrics.record(outcome);
+        self.log(Some(outcome), None);
+    }
+
+    /// Records an outcome that was caused by an error.
+    pub fn fail(&mut self, outcome: Outcome, error: impl Display) {
+        self.metrics.record(outcome);
+        self.log(Some(outcome), Some(error.to_string()));
+    }
+
+    /// Marks an order that was skipped without trying to solve it.
+    pub fn skip(&mut self) {
+        self.metrics.skip();
+        self.log(None, None);
+    }
+
+    fn log(&mut self, outcome: Option<Outcome>, error: Option<String>) {
+        self.order.outcome = outcome;
+        self.order.error = error;
+        self.log.0.lock().unwrap().push(self.order.clone());
+        self.done = true;
+    }
+}
+
+impl Drop for OrderOutcome {
+    fn drop(&mut self) {
+        // The metrics record the deadline outcome themselves.
+        if !self.done {
+            self.order.outcome = Some(Outcome::Deadline);
+            self.log.0.lock().unwrap().push(self.order.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::domain::eth::Address};
+
+    fn order(uid: u8) -> order::Order {
+        order::Order {
+            uid: order::Uid([uid; 56]),
+            ..order::test_order(
+                eth::Asset {
+                    token: eth::TokenAddress(Address::repeat_byte(1)),
+                    amount: eth::U256::from(1),
+                },
+                eth::Asset {
+                    token: eth::TokenAddress(Address::repeat_byte(2)),
+                    amount: eth::U256::from(1),
+                },
+                order::Side::Sell,
+                order::Class::Limit,
+            )
+        }
+    }
+
+    #[test]
+    fn logs_the_outcome_of_every_order() {
+        let log = Log::default();
+
+        let mut unprofitable = OrderOutcome::new("test", &order(1), &log);
+        unprofitable.gas(eth::Gas(eth::U256::from(100_000)));
+        unprofitable.record(Outcome::FeeExceedsSurplus);
+
+        let mut failed = OrderOutcome::new("test", &order(2), &log);
+        failed.fail(Outcome::SimulationFailed, "execution reverted");
+
+        let mut skipped = OrderOutcome::new("test", &order(3), &log);
+        skipped.skip();
+
+        drop(OrderOutcome::new("test", &order(4), &log));
+
+        let orders = log.take();
+        let outcomes = orders
+            .iter()
+            .map(|order| (order.uid, order.outcome, order.error.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            outcomes,
+            [
+                (order(1).uid, Some(Outcome::FeeExceedsSurplus), None),
+                (
+                    order(2).uid,
+                    Some(Outcome::SimulationFailed),
+                    Some("execution reverted")
+                ),
+                (order(3).uid, None, None),
+                (order(4).uid, Some(Outcome::Deadline), None),
+            ]
+        );
+        assert_eq!(
+            orders[0].gas.map(|gas| gas.0),
+            Some(eth::U256::from(100_000))
+        );
+        assert!(log.take().is_empty());
+    }
+
+    #[test]
+    fn keeps_recent_solve_auctions() {
+        let history = History::default();
+        let started = chrono::Utc::now();
+        for id in 0..=AUCTIONS as i64 {
+            history.record(auction::Id::Solve(id), started, Vec::new());
+        }
+        history.record(auction::Id::Quote, started, Vec::new());
+
+        let ids = history
+            .auctions()
+            .iter()
+            .map(|auction| auction.id)
+            .collect::<Vec<_>>();
+        assert_eq!(ids, (1..=AUCTIONS as i64).rev().collect::<Vec<_>>());
+    }
+}
@@ -5,20 +5,39 @@ use {
     crate::{
         domain::{
             auction,
-            dex::{self, minimum_surplus::MinimumSurplusLimits, slippage::SlippageLimits},
+            dex::{
+                self,
+                overrides::{Overrides, Parameters},
+            },
             eth,
+            notification,
             order::{self, Order},
+            quote,
             solution,
-            solver::dex::fills::Fills,
+            solver::dex::{
+                feedback::Feedback,
+                fills::Fills,
+                history::{History, OrderOutcome},
+                native_prices::NativePrices,
+            },
         },
-        infra,
+        infra::{self, metrics::Outcome},
+        util,
     },
+    ethrpc::block_stream::CurrentBlockWatcher,
     futures::{FutureExt, StreamExt, future, stream},
-    std::num::NonZeroUsize,
+    std::{
+        num::NonZeroUsize,
+        sync::{Arc, RwLock},
+        time::{Duration, Instant, SystemTime},
+    },
     tracing::Instrument,
 };
 
-mod fills;
+mod feedback;
+pub mod fills;
+pub mod history;
+mod native_prices;
 
 pub struct Dex {
     /// The DEX API client.
@@ -27,62 +46,263 @@ pub struct Dex {
     /// A DEX swap gas simulator for computing limit order fees.
     simulator: infra::dex::Simulator,
 
-    /// The slippage configuration to use for the solver.
-    slippage: SlippageLimits,
-
-    /// The minimum surplus configuration to use for the solver.
-    minimum_surplus: MinimumSurplusLimits,
-
-    /// The number of concurrent requests to make.
-    concurrent_requests: NonZeroUsize,
+    /// The settings that can be changed at runtime. They get replaced as a
+    /// whole when the configuration is reloaded, auctions that are already
+    /// being solved keep using the settings they started with.
+    settings: RwLock<Arc<Settings>>,
 
     /// Helps to manage the strategy to fill orders (especially partially
     /// fillable orders).
     fills: Fills,
 
-    /// Handles 429 Too Many Requests error with a retry mechanism
-    rate_limiter: rate_limit::RateLimiter,
+    /// Backs off orders and tokens based on the notified outcomes of
+    /// submitted solutions.
+    feedback: Feedback,
 
-    /// Amount of gas that gets added to each swap to tweak the cost coverage of
-    /// the solver.
-    gas_offset: eth::Gas,
+    /// The order outcomes of recently solved auctions.
+    history: History,
+
+    /// The result of the last DEX API readiness probe and when it was made.
+    dex_probe: tokio::sync::Mutex<Option<(Instant, Result<(), String>)>>,
+
+    /// Native token prices estimated in the current block.
+    native_prices: NativePrices,
+
+    /// The amount of the wrapped native token that gets sold for estimating
+    /// native token prices.
+    native_price_reference_amount: eth::Ether,
+
+    /// Block stream for caching native token prices per block. Prices are not
+    /// cached without it.
+    block_stream: Option<CurrentBlockWatcher>,
 
     /// Whether to internalize the solution interactions using the Settlement
     /// contract buffer.
     internalize_interactions: bool,
+
+    /// Whether to simulate the complete settlement of a solution before
+    /// returning it.
+    simulate_settlement: bool,
+
+    /// The wrapped native token used for routing orders that buy the native
+    /// token.
+    wrapped_native_token: eth::WrappedNativeToken,
+
+    /// The chain the solver solves auctions for.
+    chain_id: eth::ChainId,
+
+    /// The name of the solver instance, if it is one of several named
+    /// instances.
+    name: Option<String>,
+
+    /// The labels of the metrics recorded for this solver.
+    instance: infra::metrics::Instance,
+}
+
+/// The state of the DEX API rate limiter and the block stream.
+#[derive(Debug)]
+pub struct Status {
+    /// The back-off state of the rate limiter.
+    pub rate_limiter: infra::dex::rate_limiter::State,
+    /// The current block, if the solver polls blocks.
+    pub block: Option<Block>,
+}
+
+/// The current block of the block stream.
+#[derive(Debug)]
+pub struct Block {
+    pub number: u64,
+    /// The time since the block was mined.
+    pub age: Duration,
+}
+
+/// The results of the checks whether the solver is ready to solve auctions.
+/// A failed check holds the reason it failed.
+#[derive(Debug)]
+pub struct Readiness {
+    /// Whether the node responds.
+    pub node: Result<(), String>,
+    /// Whether the current block is recent, if the solver polls blocks.
+    pub block_stream: Option<Result<(), String>>,
+    /// Whether the DEX API is reachable and accepts the credentials.
+    pub dex: Result<(), String>,
+}
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        self.node.is_ok()
+            && self.block_stream.as_ref().is_none_or(Result::is_ok)
+            && self.dex.is_ok()
+    }
 }
 
+/// The result of probing a single swap with the DEX API.
+#[derive(Debug)]
+pub struct Probe {
+    /// The order the swap was requested for.
+    pub order: dex::Order,
+    /// The slippage tolerance the swap was requested with.
+    pub slippage: dex::Slippage,
+    /// The swap the DEX API found.
+    pub swap: Result<dex::Swap, infra::dex::Error>,
+    /// The simulated gas of the swap including the unwraps, if one was found
+    /// that can be executed for the order. The inner `None` means that the
+    /// swap couldn't be simulated.
+    pub gas: Option<Result<Option<eth::Gas>, infra::dex::simulator::Error>>,
+    /// The checks an order with the probed limit amount goes through, if a
+    /// limit was specified and a swap found.
+    pub limit: Option<LimitChecks>,
+}
+
+/// Whether a swap passes the checks of an order with a limit price.
+#[derive(Debug)]
+pub struct LimitChecks {
+    /// Whether the swap satisfies the limit price.
+    pub limit_price: bool,
+    /// Whether the swap provides the minimum surplus over the limit price.
+    pub minimum_surplus: bool,
+}
+
+/// The runtime-tunable settings of the solver.
+struct Settings {
+    /// The default slippage, minimum surplus and gas offset configuration to
+    /// use for the solver.
+    parameters: Parameters,
+
+    /// Overrides of the default parameters for specific tokens, token pairs or
+    /// order classes.
+    overrides: Overrides,
+
+    /// The number of concurrent requests to make.
+    concurrent_requests: NonZeroUsize,
+
+    /// Handles 429 Too Many Requests error with a retry mechanism
+    rate_limiter: Arc<infra::dex::RateLimiter>,
+
+    /// The back-off configuration of the rate limiter.
+    back_off: infra::config::dex::BackOff,
+}
+
+impl Settings {
+    fn new(
+        tunables: infra::config::dex::Tunables,
+        rate_limiter: Arc<infra::dex::RateLimiter>,
+    ) -> Self {
+        Self {
+            parameters: Parameters {
+                slippage: tunables.slippage,
+                minimum_surplus: tunables.minimum_surplus,
+                gas_offset: tunables.gas_offset,
+            },
+            overrides: tunables.overrides,
+            concurrent_requests: tunables.concurrent_requests,
+            rate_limiter,
+            back_off: tunables.back_off,
+        }
+    }
+}
+
+/// How long each readiness check may take before it fails.
+const READINESS_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How long the result of a DEX API readiness probe is reused, so that
+/// frequent readiness checks don't use up the DEX API rate limit.
+const DEX_PROBE_TTL: Duration = Duration::from_secs(10);
+
+/// The age of the current block after which the block stream is considered
+/// stale.
+const MAX_BLOCK_AGE: Duration = Duration::from_secs(60);
+
 /// The amount of time we aim the solver to finish before the final deadline is
 /// reached.
 const DEADLINE_SLACK: chrono::Duration = chrono::Duration::milliseconds(500);
 
 impl Dex {
     pub fn new(dex: infra::dex::Dex, config: infra::config::dex::Config) -> Self {
-        let rate_limiter = rate_limit::RateLimiter::from_strategy(
-            config.rate_limiting_strategy,
-            "dex_api".to_string(),
-        );
+        let instance = infra::metrics::Instance::new(config.name.as_deref(), config.chain_id);
+        if config.block_stream.is_none() {
+            tracing::warn!(
+                chain = ?config.chain_id,
+                "native prices are not cached without a current-block-poll-interval"
+            );
+        }
         Self {
             dex,
-            simulator: infra::dex::Simulator::new(
-                &config.node_url,
-                config.contracts.settlement,
-                config.contracts.authenticator,
-            ),
-            slippage: config.slippage,
-            minimum_surplus: config.minimum_surplus,
-            concurrent_requests: config.concurrent_requests,
+            // Simulation batches run in background tasks, which need to record
+            // their metrics for this solver.
+            simulator: instance.in_scope(|| {
+                infra::dex::Simulator::new(
+                    &config.node_url,
+                    config.contracts.settlement,
+                    config.contracts.authenticator,
+                    config.simulation_batching,
+                )
+            }),
+            settings: RwLock::new(Arc::new(Settings::new(
+                config.tunables,
+                config.rate_limiter,
+            ))),
             fills: Fills::new(config.smallest_partial_fill),
-            rate_limiter,
-            gas_offset: config.gas_offset,
+            feedback: Feedback::new(config.chain_id.wrapped_native_token()),
+            history: History::default(),
+            dex_probe: Default::default(),
+            native_prices: NativePrices::default(),
+            native_price_reference_amount: config.native_price_reference_amount,
+            block_stream: config.block_stream,
             internalize_interactions: config.internalize_interactions,
+            simulate_settlement: config.simulate_settlement,
+            wrapped_native_token: config.chain_id.wrapped_native_token(),
+            chain_id: config.chain_id,
+            name: config.name,
+            instance,
+        }
+    }
+
+    pub fn chain_id(&self) -> eth::ChainId {
+        self.chain_id
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    pub fn instance(&self) -> &infra::metrics::Instance {
+        &self.instance
+    }
+
+    /// Replaces the runtime-tunable settings of the solver. Auctions that are
+    /// currently being solved finish with the previous settings.
+    pub fn reload(&self, reload: infra::config::dex::Reload) {
+        let mut settings = self.settings.write().unwrap();
+        let mut new = Settings::new(reload.tunables, reload.rate_limiter);
+        new.parameters.slippage = new
+            .parameters
+            .slippage
+            .with_observations_from(&settings.parameters.slippage);
+        // Keep backing off from a rate limited DEX API, unless the back-off
+        // configuration itself changed.
+        if new.back_off == settings.back_off {
+            new.rate_limiter = settings.rate_limiter.clone();
+        }
+        *settings = Arc::new(new);
+        if let Some(excluded_sources) = reload.excluded_sources {
+            self.dex.set_excluded_sources(excluded_sources);
         }
     }
 
-    pub async fn solve(&self, auction: auction::Auction) -> Vec<solution::Solution> {
+    /// Solves an auction and returns, next to the solutions, how every order
+    /// was handled.
+    pub async fn solve(
+        &self,
+        auction: auction::Auction,
+    ) -> (Vec<solution::Solution>, Vec<history::Order>) {
+        let settings = self.settings.read().unwrap().clone();
+        let started = chrono::Utc::now();
+        let log = history::Log::default();
+
         let mut solutions = Vec::new();
         let solve_orders = async {
-            let mut stream = self.solution_stream(&auction);
+            let mut stream = self.solution_stream(&auction, &settings, &log);
             while let Some(solution) = stream.next().await {
                 solutions.push(solution);
             }
@@ -99,23 +319,374 @@ impl Dex {
         }
 
         self.fills.collect_garbage();
+        self.feedback.solved(&auction, &solutions);
+        let orders = log.take();
+        self.history.record(auction.id, started, orders.clone());
+
+        (solutions, orders)
+    }
+
+    /// Logs and meters a notification for every order of the notified
+    /// solutions and feeds reverts and settlements back into solving.
+    pub fn notify(&self, notification: notification::Notification) {
+        let trades = self.feedback.trades(&notification);
+        if trades.is_empty() {
+            tracing::debug!(?notification, "received notification for unknown solution");
+            infra::metrics::notification(self.dex.name(), &notification.kind);
+        }
+        for trade in &trades {
+            tracing::debug!(order = %trade.uid, kind = ?notification.kind, "received notification");
+            infra::metrics::notification(self.dex.name(), &notification.kind);
+        }
+
+        match notification.kind {
+            notification::Kind::Reverted(_) => {
+                for pair in self.feedback.reverted(&trades) {
+                    tracing::warn!(
+                        ?pair,
+                        "marking token pair as suspicious after repeated reverts"
+                    );
+                    infra::metrics::suspicious_pair(self.dex.name());
+                }
+            }
+            // Failing simulations in the driver can have many reasons besides
+            // the traded tokens, so they only back off the orders.
+            notification::Kind::SimulationFailed { .. } => self.feedback.failed(&trades),
+            notification::Kind::Settled(_) => self.feedback.settled(&trades),
+            _ => (),
+        }
+    }
+
+    /// Returns the fill amounts that are tried next for orders.
+    pub fn fills(&self) -> Vec<fills::Entry> {
+        self.fills.entries()
+    }
+
+    /// Resets the fill amount that is tried next for an order.
+    pub fn reset_fill(&self, uid: order::Uid) -> bool {
+        self.fills.reset(uid)
+    }
+
+    /// Returns the order outcomes of recently solved auctions.
+    pub fn auctions(&self) -> Vec<history::Auction> {
+        self.history.auctions()
+    }
+
+    /// Returns the state of the rate limiter and the block stream.
+    pub fn status(&self) -> Status {
+        let rate_limiter = self.settings.read().unwrap().rate_limiter.state();
+        let block = self.block_stream.as_ref().map(|stream| {
+            let block = stream.borrow();
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs();
+            Block {
+                number: block.number,
+                age: Duration::from_secs(now.saturating_sub(block.timestamp)),
+            }
+        });
+        Status {
+            rate_limiter,
+            block,
+        }
+    }
+
+    /// Checks whether the node and the DEX API respond and the block stream is
+    /// up to date.
+    pub async fn readiness(&self) -> Readiness {
+        let (node, dex) = future::join(
+            check(async { self.simulator.block_number().await.map(|_| ()) }),
+            self.probe_dex(),
+        )
+        .await;
+        let block_stream = self.status().block.map(|block| {
+            if block.age <= MAX_BLOCK_AGE {
+                Ok(())
+            } else {
+                Err(format!(
+                    "block {} is {}s old",
+                    block.number,
+                    block.age.as_secs()
+                ))
+            }
+        });
+        Readiness {
+            node,
+            block_stream,
+            dex,
+        }
+    }
+
+    /// Checks whether the DEX API is reachable and accepts the credentials.
+    /// The result is reused for [`DEX_PROBE_TTL`] and probes go through the
+    /// rate limiter, so that readiness checks don't get the API key rate
+    /// limited.
+    async fn probe_dex(&self) -> Result<(), String> {
+        // Holding the lock while probing makes concurrent readiness checks
+        // wait for the same probe.
+        let mut probe = self.dex_probe.lock().await;
+        if let Some((at, result)) = &*probe
+            && at.elapsed() < DEX_PROBE_TTL
+        {
+            return result.clone();
+        }
+
+        let rate_limiter = self.settings.read().unwrap().rate_limiter.clone();
+        let result = check(async {
+            match rate_limiter
+                .execute_with_back_off(self.dex.probe(), |result| {
+                    matches!(
+                        result,
+                        Err(util::http::Error::Status(
+                            reqwest::StatusCode::TOO_MANY_REQUESTS,
+                            _
+                        ))
+                    )
+                })
+                .await
+            {
+                Ok(result) => result.map_err(|err| err.to_string()),
+                Err(rate_limit::Error::RateLimited) => Err("rate limited".to_string()),
+            }
+        })
+        .await;
+        *probe = Some((Instant::now(), result.clone()));
+        result
+    }
+
+    /// Quotes a single swap with the DEX API, using the configured slippage.
+    /// Quotes don't affect the state used for solving auctions.
+    pub async fn quote(&self, request: quote::Request) -> Result<quote::Quote, infra::dex::Error> {
+        let settings = self.settings.read().unwrap().clone();
+        let (sell, buy) = match request.side {
+            order::Side::Sell => (request.amount, eth::U256::ZERO),
+            order::Side::Buy => (eth::U256::ZERO, request.amount),
+        };
+        let order = request.order(sell, buy);
+        let dex_order =
+            dex::Order::new(&order).with_wrapped_native_token(self.wrapped_native_token);
+        let parameters = settings.overrides.resolve(&order, &settings.parameters);
+        let slippage = parameters.slippage.relative(&dex_order, &request.tokens);
+
+        let swap = self
+            .swap(
+                &dex_order,
+                &slippage,
+                &request.tokens,
+                &settings.rate_limiter,
+            )
+            .await?;
+
+        let order = request.order(swap.input.amount, swap.output.amount);
+        let quote = quote::Quote {
+            sell: order.sell,
+            buy: order.buy,
+            gas: swap.heuristic_gas(&order, self.wrapped_native_token),
+            calls: swap
+                .calls
+                .iter()
+                .map(|call| eth::Interaction {
+                    target: call.to,
+                    value: call.value,
+                    calldata: call.calldata.clone(),
+                })
+                .collect(),
+            solution: None,
+        };
+        let sell = request.tokens.reference_price(&order.sell.token);
+        let solution = swap
+            .into_solution(
+                order,
+                quote.gas,
+                request.gas_price,
+                sell,
+                parameters.gas_offset,
+                self.wrapped_native_token,
+            )
+            .map(|solution| {
+                if self.internalize_interactions {
+                    solution.with_buffers_internalizations(&request.tokens)
+                } else {
+                    solution
+                }
+            });
+        Ok(quote::Quote { solution, ..quote })
+    }
+
+    /// Requests a single swap from the DEX API and simulates it, for finding
+    /// out why a pair doesn't get solved. With a limit amount, which is the
+    /// minimum buy amount of sell orders and the maximum sell amount of buy
+    /// orders, the swap also goes through the checks of an order with that
+    /// limit. Probes don't affect the state used for solving auctions.
+    pub async fn probe(&self, request: quote::Request, limit: Option<eth::U256>) -> Probe {
+        let settings = self.settings.read().unwrap().clone();
+        let (sell, buy) = match request.side {
+            order::Side::Sell => (request.amount, limit.unwrap_or_default()),
+            order::Side::Buy => (limit.unwrap_or_default(), request.amount),
+        };
+        let order = request.order(sell, buy);
+        let dex_order =
+            dex::Order::new(&order).with_wrapped_native_token(self.wrapped_native_token);
+        let parameters = settings.overrides.resolve(&order, &settings.parameters);
+        let slippage = parameters.slippage.relative(&dex_order, &request.tokens);
 
-        solutions
+        let swap = self
+            .swap(
+                &dex_order,
+                &slippage,
+                &request.tokens,
+                &settings.rate_limiter,
+            )
+            .await;
+        let (gas, limit) = match &swap {
+            Ok(swap) => {
+                let wrapped = self.wrapped_native_token;
+                let gas = match swap.unwraps(&order, wrapped) {
+                    Some(unwraps) => Some(
+                        self.simulator
+                            .gas(request.owner, swap, &unwraps, wrapped)
+                            .await,
+                    ),
+                    None => None,
+                };
+                let limit = limit.map(|_| {
+                    let minimum_surplus = parameters
+                        .minimum_surplus
+                        .relative(&dex_order, &request.tokens);
+                    LimitChecks {
+                        limit_price: swap.satisfies(&order),
+                        minimum_surplus: swap
+                            .satisfies_with_minimum_surplus(&order, &minimum_surplus),
+                    }
+                });
+                (gas, limit)
+            }
+            Err(_) => (None, None),
+        };
+
+        Probe {
+            order: dex_order,
+            slippage,
+            swap,
+            gas,
+            limit,
+        }
+    }
+
+    /// Estimates the price of a token in the native token by selling the
+    /// reference amount of the wrapped native token for it. Prices are cached
+    /// for the current block if blocks are polled, and concurrent requests
+    /// for the same token share a single lookup.
+    pub async fn native_price(
+        &self,
+        token: eth::TokenAddress,
+        decimals: Option<u8>,
+    ) -> Result<auction::Price, infra::dex::Error> {
+        let wrapped = self.wrapped_native_token.0;
+        if token == wrapped || token == eth::NATIVE_TOKEN {
+            return Ok(auction::Price(eth::Ether(
+                eth::U256::from(10).pow(eth::U256::from(18)),
+            )));
+        }
+        let block = self
+            .block_stream
+            .as_ref()
+            .map(|stream| stream.borrow().number);
+        if let Some(price) = block.and_then(|block| self.native_prices.get(block, &token)) {
+            return Ok(price);
+        }
+        // Concurrent requests for the same token wait for a single lookup.
+        let lookup = self.native_prices.lookup(token);
+        let mut lookup = lookup.lock().await;
+        if let Some(price) = *lookup {
+            return Ok(price);
+        }
+
+        let settings = self.settings.read().unwrap().clone();
+        let order = dex::Order {
+            sell: wrapped,
+            buy: token,
+            side: order::Side::Sell,
+            amount: dex::Amount::new(self.native_price_reference_amount.0),
+            owner: eth::Address::ZERO,
+        };
+        let tokens = auction::Tokens(
+            [(wrapped, Some(18)), (token, decimals)]
+                .into_iter()
+                .map(|(token, decimals)| {
+                    let token_info = auction::Token {
+                        decimals,
+                        reference_price: None,
+                        available_balance: eth::U256::ZERO,
+                        trusted: false,
+                    };
+                    (token, token_info)
+                })
+                .collect(),
+        );
+        let slippage = settings.parameters.slippage.relative(&order, &tokens);
+        let swap = self
+            .swap(&order, &slippage, &tokens, &settings.rate_limiter)
+            .await?;
+
+        let price = swap
+            .input
+            .amount
+            .checked_mul(eth::U256::from(10).pow(eth::U256::from(18)))
+            .and_then(|amount| amount.checked_div(swap.output.amount))
+            .map(eth::Ether)
+            .map(auction::Price)
+            .ok_or(infra::dex::Error::NotFound)?;
+        if let Some(block) = block {
+            self.native_prices.insert(block, token, price);
+        }
+        *lookup = Some(price);
+        Ok(price)
+    }
+
+    /// Computes a swap with the DEX API, backing off when getting rate
+    /// limited.
+    async fn swap(
+        &self,
+        order: &dex::Order,
+        slippage: &dex::Slippage,
+        tokens: &auction::Tokens,
+        rate_limiter: &infra::dex::RateLimiter,
+    ) -> Result<dex::Swap, infra::dex::Error> {
+        rate_limiter
+            .execute_with_back_off(self.dex.swap(order, slippage, tokens), |result| {
+                matches!(result, Err(infra::dex::Error::RateLimited))
+            })
+            .await
+            .map_err(|err| match err {
+                rate_limit::Error::RateLimited => infra::dex::Error::RateLimited,
+            })
+            .and_then(|result| result)
     }
 
     fn solution_stream<'a>(
         &'a self,
         auction: &'a auction::Auction,
+        settings: &'a Settings,
+        log: &history::Log,
     ) -> impl stream::Stream<Item = solution::Solution> + 'a {
-        stream::iter(auction.orders.iter())
+        // Outcomes are created upfront so that orders which never get solved
+        // because of the deadline are recorded as well.
+        let outcomes = auction
+            .orders
+            .iter()
+            .map(|order| OrderOutcome::new(self.dex.name(), order, log))
+            .collect::<Vec<_>>();
+        stream::iter(auction.orders.iter().zip(outcomes))
             .enumerate()
-            .map(|(i, order)| {
+            .map(|(i, (order, outcome))| {
                 let span = tracing::info_span!("solve", order = %order.uid);
-                self.solve_order(order, &auction.tokens, auction.gas_price)
+                self.solve_order(order, outcome, &auction.tokens, auction.gas_price, settings)
                     .map(move |solution| solution.map(|s| s.with_id(solution::Id(i as u64))))
                     .instrument(span)
             })
-            .buffer_unordered(self.concurrent_requests.get())
+            .buffer_unordered(settings.concurrent_requests.get())
             .filter_map(future::ready)
     }
 
@@ -124,6 +695,9 @@ impl Dex {
         order: &Order,
         dex_order: &dex::Order,
         tokens: &auction::Tokens,
+        parameters: &Parameters,
+        settings: &Settings,
+        outcome: &mut OrderOutcome,
     ) -> Option<dex::Swap> {
         let dex_err_handler = |err: infra::dex::Error| {
             infra::metrics::solve_error(err.format_variant());
@@ -154,15 +728,17 @@ impl Dex {
             }
             err
         };
+        let slippage = parameters.slippage.relative(dex_order, tokens);
+        outcome.slippage(&slippage);
         let swap = async {
-            let slippage = self.slippage.relative(&dex_order.amount(), tokens);
             self.dex
                 .swap(dex_order, &slippage, tokens)
                 .await
                 .inspect(|_| infra::metrics::request_sent())
                 .map_err(dex_err_handler)
         };
-        self.rate_limiter
+        let result = settings
+            .rate_limiter
             .execute_with_back_off(swap, |result| {
                 matches!(result, Err(infra::dex::Error::RateLimited))
             })
@@ -170,61 +746,184 @@ impl Dex {
             .map_err(|err| match err {
                 rate_limit::Error::RateLimited => infra::dex::Error::RateLimited,
             })
-            .and_then(|result| result)
-            .ok()
-            .filter(|swap| {
-                if !swap.satisfies(order) {
-                    tracing::debug!("swap does not satisfy order");
-                    if order.partially_fillable {
-                        self.fills.reduce_next_try(order.uid);
-                    }
-                    return false;
-                }
+            .and_then(|result| result);
+        let swap = match result {
+            Ok(swap) => swap,
+            Err(err) => {
+                let kind = match err {
+                    infra::dex::Error::NotFound => Outcome::NotFound,
+                    infra::dex::Error::RateLimited => Outcome::RateLimited,
+                    infra::dex::Error::OrderNotSupported
+                    | infra::dex::Error::UnavailableForLegalReasons => Outcome::Unsupported,
+                    infra::dex::Error::Other(_) => Outcome::Error,
+                };
+                outcome.fail(kind, err);
+                return None;
+            }
+        };
 
-                // Check minimum surplus requirement
-                let minimum_surplus = self.minimum_surplus.relative(&dex_order.amount(), tokens);
-                let valid_surplus = swap.satisfies_with_minimum_surplus(order, &minimum_surplus);
-                if !valid_surplus {
-                    tracing::debug!("swap does not meet minimum surplus requirement");
-                    if order.partially_fillable {
-                        self.fills.reduce_next_try(order.uid);
-                    }
-                }
-                valid_surplus
-            })
+        outcome.swap(&swap);
+        settings.parameters.slippage.observe(&swap);
+
+        if !swap.satisfies(order) {
+            tracing::debug!("swap does not satisfy order");
+            if order.partially_fillable {
+                self.fills.reduce_next_try(order.uid);
+            }
+            outcome.record(Outcome::OutOfPrice);
+            return None;
+        }
+
+        // Check minimum surplus requirement
+        let minimum_surplus = parameters
+            .minimum_surplus
+            .relative(&dex_order.amount(), tokens);
+        if !swap.satisfies_with_minimum_surplus(order, &minimum_surplus) {
+            tracing::debug!("swap does not meet minimum surplus requirement");
+            if order.partially_fillable {
+                self.fills.reduce_next_try(order.uid);
+            }
+            outcome.record(Outcome::MinSurplusFailed);
+            return None;
+        }
+        Some(swap)
     }
 
     async fn solve_order(
         &self,
         order: &order::Order,
+        mut outcome: OrderOutcome,
         tokens: &auction::Tokens,
         gas_price: auction::GasPrice,
+        settings: &Settings,
     ) -> Option<solution::Solution> {
-        let dex_order = self.fills.dex_order(order, tokens)?;
-        let swap = self.try_solve(order, &dex_order, tokens).await?;
-        let sell = tokens.reference_price(&order.sell.token);
-        let Some(solution) = swap
-            .into_solution(
-                order.clone(),
-                gas_price,
-                sell,
-                &self.simulator,
-                self.gas_offset,
+        if self.feedback.is_backed_off(order) {
+            tracing::debug!("skipping order backed off after reverts");
+            outcome.record(Outcome::BackedOff);
+            return None;
+        }
+        let Some(dex_order) = self.fills.dex_order(order, tokens) else {
+            outcome.skip();
+            return None;
+        };
+        let dex_order = dex_order.with_wrapped_native_token(self.wrapped_native_token);
+        if dex_order.sell == dex_order.buy {
+            tracing::debug!("skipping order wrapping or unwrapping the native token");
+            outcome.skip();
+            return None;
+        }
+        let parameters = settings.overrides.resolve(order, &settings.parameters);
+        let swap = self
+            .try_solve(
+                order,
+                &dex_order,
+                tokens,
+                &parameters,
+                settings,
+                &mut outcome,
             )
+            .await?;
+        let simulated_gas = match swap
+            .simulated_gas(order, &self.simulator, self.wrapped_native_token)
             .await
-        else {
+        {
+            Ok(gas) => gas,
+            Err(err) => {
+                tracing::warn!(?err, "gas simulation failed");
+                outcome.fail(Outcome::SimulationFailed, err);
+                return None;
+            }
+        };
+        let heuristic_gas = swap.heuristic_gas(order, self.wrapped_native_token);
+        let gas = simulated_gas.unwrap_or(heuristic_gas);
+        outcome.gas(gas);
+        let sell = tokens.reference_price(&order.sell.token);
+        let Some(solution) = swap.into_solution(
+            order.clone(),
+            gas,
+            gas_price,
+            sell,
+            parameters.gas_offset,
+            self.wrapped_native_token,
+        ) else {
             tracing::debug!("no solution for swap");
+            outcome.record(Outcome::FeeExceedsSurplus);
             return None;
         };
 
+        let heuristic_gas = heuristic_gas + parameters.gas_offset;
+
         tracing::debug!("solved");
         // Maybe some liquidity appeared that enables a bigger fill.
         self.fills.increase_next_try(order.uid);
 
-        if self.internalize_interactions {
-            Some(solution.with_buffers_internalizations(tokens))
+        let solution = if self.internalize_interactions {
+            solution.with_buffers_internalizations(tokens)
         } else {
-            Some(solution)
-        }
+            solution
+        };
+
+        let solution = if self.simulate_settlement {
+            match self.simulator.settle(&solution).await {
+                Ok(gas) => {
+                    outcome.gas(gas);
+                    solution.with_gas(gas)
+                }
+                Err(err) => {
+                    tracing::warn!(?err, "settlement simulation failed; discarding solution");
+                    outcome.fail(Outcome::SimulationFailed, err);
+                    return None;
+                }
+            }
+        } else {
+            solution
+        };
+
+        outcome.record(Outcome::Solved);
+        // Without any simulation the solution gas is the heuristic itself.
+        let simulated = self.simulate_settlement || simulated_gas.is_some();
+        self.observe(&solution, tokens, simulated.then_some(heuristic_gas));
+        Some(solution)
+    }
+
+    /// Records the surplus, fees and gas of a single order solution.
+    fn observe(
+        &self,
+        solution: &solution::Solution,
+        tokens: &auction::Tokens,
+        heuristic_gas: Option<eth::Gas>,
+    ) {
+        let Some(solution::Trade::Fulfillment(fulfillment)) = solution.trades.first() else {
+            return;
+        };
+        let in_eth = |asset: eth::Asset| tokens.reference_price(&asset.token)?.in_eth(asset.amount);
+        let surplus = match fulfillment.surplus(&solution.prices) {
+            Some(solution::Surplus::Positive(surplus)) => {
+                in_eth(surplus).map(infra::metrics::Surplus::Positive)
+            }
+            Some(solution::Surplus::Negative(shortfall)) => {
+                tracing::debug!(?shortfall, "solution with negative surplus");
+                in_eth(shortfall).map(infra::metrics::Surplus::Negative)
+            }
+            None => None,
+        };
+        infra::metrics::solution(
+            self.dex.name(),
+            fulfillment.order().class,
+            surplus,
+            fulfillment.surplus_fee().and_then(in_eth),
+            heuristic_gas,
+            solution.gas,
+        );
+    }
+}
+
+/// Runs a readiness check, failing it if it takes too long.
+async fn check<E: std::fmt::Display>(
+    check: impl Future<Output = Result<(), E>>,
+) -> Result<(), String> {
+    match tokio::time::timeout(READINESS_TIMEOUT, check).await {
+        Ok(result) => result.map_err(|err| err.to_string()),
+        Err(_) => Err("timed out".to_string()),
     }
 }
@@ -0,0 +1,102 @@
+use {
+    crate::domain::{auction, eth},
+    std::{
+        collections::HashMap,
+        sync::{Arc, Mutex, Weak},
+    },
+};
+
+/// Caches native token prices for the duration of a block.
+#[derive(Debug, Default)]
+pub struct NativePrices {
+    cache: Mutex<Cache>,
+    lookups: Mutex<HashMap<eth::TokenAddress, Weak<Lookup>>>,
+}
+
+/// A lookup of a token price that concurrent requests share. The lock is held
+/// while looking up the price, which is then stored for the requests that
+/// waited for it.
+pub type Lookup = tokio::sync::Mutex<Option<auction::Price>>;
+
+#[derive(Debug, Default)]
+struct Cache {
+    block: u64,
+    prices: HashMap<eth::TokenAddress, auction::Price>,
+}
+
+impl NativePrices {
+    /// Returns the cached price of a token if it was estimated in the
+    /// specified block.
+    pub fn get(&self, block: u64, token: &eth::TokenAddress) -> Option<auction::Price> {
+        let cache = self.cache.lock().unwrap();
+        if cache.block != block {
+            return None;
+        }
+        cache.prices.get(token).copied()
+    }
+
+    /// Returns the lookup of a token price that is in flight, or a new one.
+    /// Lookups are forgotten once no request holds them anymore.
+    pub fn lookup(&self, token: eth::TokenAddress) -> Arc<Lookup> {
+        let mut lookups = self.lookups.lock().unwrap();
+        lookups.retain(|_, lookup| lookup.strong_count() > 0);
+        if let Some(lookup) = lookups.get(&token).and_then(Weak::upgrade) {
+            return lookup;
+        }
+        let lookup = Arc::new(Lookup::default());
+        lookups.insert(token, Arc::downgrade(&lookup));
+        lookup
+    }
+
+    /// Caches the price of a token estimated in the specified block. Prices of
+    /// older blocks get evicted.
+    pub fn insert(&self, block: u64, token: eth::TokenAddress, price: auction::Price) {
+        let mut cache = self.cache.lock().unwrap();
+        if cache.block < block {
+            cache.block = block;
+            cache.prices.clear();
+        }
+        if cache.block == block {
+            cache.prices.insert(token, price);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caches_prices_per_block() {
+        let prices = NativePrices::default();
+        let token = eth::TokenAddress(eth::Address::repeat_byte(1));
+        let price = auction::Price(eth::Ether(eth::U256::from(42)));
+
+        prices.insert(1, token, price);
+        assert_eq!(prices.get(1, &token).unwrap().0, price.0);
+        assert!(prices.get(2, &token).is_none());
+
+        prices.insert(2, token, price);
+        assert!(prices.get(1, &token).is_none());
+        // Prices estimated in an older block don't replace newer ones.
+        prices.insert(1, token, price);
+        assert!(prices.get(1, &token).is_none());
+        assert!(prices.get(2, &token).is_some());
+    }
+
+    #[test]
+    fn shares_in_flight_lookups() {
+        let prices = NativePrices::default();
+        let token = eth::TokenAddress(eth::Address::repeat_byte(1));
+        let other = eth::TokenAddress(eth::Address::repeat_byte(2));
+
+        let lookup = prices.lookup(token);
+        assert!(Arc::ptr_eq(&lookup, &prices.lookup(token)));
+        assert!(!Arc::ptr_eq(&lookup, &prices.lookup(other)));
+
+        // Finished lookups aren't reused.
+        *lookup.try_lock().unwrap() = Some(auction::Price(eth::Ether(eth::U256::from(42))));
+        drop(lookup);
+        assert!(prices.lookup(token).try_lock().unwrap().is_none());
+    }
+}
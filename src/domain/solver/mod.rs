@@ -1,6 +1,6 @@
 use crate::{
-    domain::{auction, solution},
-    infra::metrics,
+    domain::{auction, eth, notification, order, quote, solution},
+    infra::{self, config, metrics},
 };
 
 pub mod dex;
@@ -16,12 +16,120 @@ impl Solver {
     /// returning multiple solutions to later merge multiple non-overlapping
     /// solutions to get one big more gas efficient solution.
     pub async fn solve(&self, auction: auction::Auction) -> Vec<solution::Solution> {
+        self.solve_explained(auction).await.0
+    }
+
+    /// Solves a given auction like [`Solver::solve`] and also returns how
+    /// every order was handled.
+    pub async fn solve_explained(
+        &self,
+        auction: auction::Auction,
+    ) -> (Vec<solution::Solution>, Vec<dex::history::Order>) {
         metrics::solve(&auction);
         let deadline = auction.deadline.clone();
-        let solutions = match self {
+        let (solutions, orders) = match self {
             Solver::Dex(solver) => solver.solve(auction).await,
         };
         metrics::solved(&deadline, &solutions);
-        solutions
+        (solutions, orders)
+    }
+
+    /// Quotes a single swap. Quotes are not counted as auctions.
+    pub async fn quote(&self, request: quote::Request) -> Result<quote::Quote, infra::dex::Error> {
+        match self {
+            Solver::Dex(solver) => solver.quote(request).await,
+        }
+    }
+
+    /// Requests and simulates a single swap for debugging, see
+    /// [`dex::Dex::probe`].
+    pub async fn probe(&self, request: quote::Request, limit: Option<eth::U256>) -> dex::Probe {
+        match self {
+            Solver::Dex(solver) => solver.probe(request, limit).await,
+        }
+    }
+
+    /// Estimates the price of a token in the native token.
+    pub async fn native_price(
+        &self,
+        token: eth::TokenAddress,
+        decimals: Option<u8>,
+    ) -> Result<auction::Price, infra::dex::Error> {
+        match self {
+            Solver::Dex(solver) => solver.native_price(token, decimals).await,
+        }
+    }
+
+    /// Returns the fill amounts that are tried next for orders.
+    pub fn fills(&self) -> Vec<dex::fills::Entry> {
+        match self {
+            Solver::Dex(solver) => solver.fills(),
+        }
+    }
+
+    /// Resets the fill amount that is tried next for an order. Returns whether
+    /// the order had a fill amount.
+    pub fn reset_fill(&self, uid: order::Uid) -> bool {
+        match self {
+            Solver::Dex(solver) => solver.reset_fill(uid),
+        }
+    }
+
+    /// Returns the order outcomes of recently solved auctions.
+    pub fn auctions(&self) -> Vec<dex::history::Auction> {
+        match self {
+            Solver::Dex(solver) => solver.auctions(),
+        }
+    }
+
+    /// Returns the state of the rate limiter and the block stream.
+    pub fn status(&self) -> dex::Status {
+        match self {
+            Solver::Dex(solver) => solver.status(),
+        }
+    }
+
+    /// Checks whether the solver is ready to solve auctions.
+    pub async fn readiness(&self) -> dex::Readiness {
+        match self {
+            Solver::Dex(solver) => solver.readiness().await,
+        }
+    }
+
+    /// The chain the solver solves auctions for.
+    pub fn chain_id(&self) -> eth::ChainId {
+        match self {
+            Solver::Dex(solver) => solver.chain_id(),
+        }
+    }
+
+    /// The name of the solver instance, if it is one of several named
+    /// instances.
+    pub fn name(&self) -> Option<&str> {
+        match self {
+            Solver::Dex(solver) => solver.name(),
+        }
+    }
+
+    /// The labels of the metrics recorded for the solver.
+    pub fn instance(&self) -> &metrics::Instance {
+        match self {
+            Solver::Dex(solver) => solver.instance(),
+        }
+    }
+
+    /// Handles a notification about what happened to a solution of this
+    /// solver.
+    pub fn notify(&self, notification: notification::Notification) {
+        match self {
+            Solver::Dex(solver) => solver.notify(notification),
+        }
+    }
+
+    /// Applies a reloaded configuration to the solver.
+    pub fn reload(&self, reload: config::dex::Reload) {
+        match self {
+            Solver::Dex(solver) => solver.reload(reload),
+        }
     }
 }
@@ -1,8 +1,17 @@
 //! CLI arguments for the `solvers` binary.
 
 use {
+    alloy::primitives::{Address, U256},
     clap::{Parser, Subcommand},
-    std::{net::SocketAddr, path::PathBuf},
+    std::{
+        convert::Infallible,
+        fmt::{self, Debug, Formatter},
+        net::SocketAddr,
+        num::NonZeroUsize,
+        path::{Path, PathBuf},
+        str::FromStr,
+        time::Duration,
+    },
 };
 
 /// Run a solver engine
@@ -25,15 +34,256 @@ pub struct Args {
     #[arg(long, env, default_value = "127.0.0.1:7872")]
     pub addr: SocketAddr,
 
+    /// Record the DEX API and node traffic of every auction into replayable
+    /// fixtures in this directory.
+    #[arg(long, env)]
+    pub record: Option<PathBuf>,
+
+    /// Require requests to the solver endpoints to send this token as a
+    /// bearer token.
+    #[arg(long, env)]
+    pub api_token: Option<Secret>,
+
+    /// Require requests to the solver endpoints to send the Unix timestamp in
+    /// seconds in the `X-Timestamp` header and the hex-encoded HMAC-SHA256
+    /// signature of `<timestamp>.<body>` with this secret in the
+    /// `X-Signature` header. Requests with a timestamp more than 5 minutes
+    /// off get rejected, as do requests repeating the signature of an
+    /// accepted request. If `--api-token` is also set, requests need to send
+    /// either of them.
+    #[arg(long, env)]
+    pub api_hmac_secret: Option<Secret>,
+
+    /// Only accept requests to the solver endpoints from these comma-separated
+    /// networks, e.g. `10.0.0.0/8,127.0.0.1/32`.
+    #[arg(long, env, value_delimiter = ',')]
+    pub api_allowlist: Vec<ipnet::IpNet>,
+
+    /// The maximum size of request bodies in bytes. Unlimited if not set.
+    #[arg(long, env)]
+    pub max_body_size: Option<usize>,
+
+    /// Serve the API over HTTPS with the certificate chain in this PEM file.
+    #[arg(long, env, requires = "tls_key")]
+    pub tls_cert: Option<PathBuf>,
+
+    /// The private key in PEM format for `--tls-cert`.
+    #[arg(long, env, requires = "tls_cert")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Decompress gzip request bodies and compress responses for clients that
+    /// accept it. Decompressed bodies are limited to `--max-body-size`, or
+    /// 256 MiB if not set.
+    #[arg(long, env, default_value = "false")]
+    pub compression: bool,
+
+    /// The maximum time to handle a request. `/solve` requests stop solving
+    /// shortly before the timeout or the auction deadline, whichever comes
+    /// first, and return the solutions found until then.
+    #[arg(long, env, value_parser = humantime_serde::re::humantime::parse_duration)]
+    pub request_timeout: Option<Duration>,
+
+    /// The maximum number of `/solve` requests to handle at once. Further
+    /// requests get rejected with `503 Service Unavailable`.
+    #[arg(long, env)]
+    pub max_concurrent_solves: Option<NonZeroUsize>,
+
+    /// Validate `/solve` requests and responses against the OpenAPI
+    /// specification and log every violation. This is meant for test
+    /// environments, since it slows down requests.
+    #[arg(long, env, default_value = "false")]
+    pub validate_openapi: bool,
+
+    /// Serve the `/debug` API for inspecting the internal state of the solver
+    /// engine. Requests need to send the token in this file as a bearer
+    /// token. The token is read from a file so that it doesn't show up in the
+    /// process list.
+    #[arg(long, env)]
+    pub debug_token_file: Option<PathBuf>,
+
+    /// How long to keep serving requests after receiving a shutdown signal
+    /// before shutting down. `/readyz` reports not being ready in the
+    /// meantime, so that load balancers stop sending new requests. The
+    /// default leaves time for at least one readiness probe with the usual
+    /// probe period of 10 seconds.
+    #[arg(long, env, default_value = "15s", value_parser = humantime_serde::re::humantime::parse_duration)]
+    pub shutdown_delay: Duration,
+
     #[command(subcommand)]
     pub command: Command,
 }
 
+/// The command to run.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    #[command(flatten)]
+    Solver(Solver),
+    /// validate a solver configuration file without starting the solver and
+    /// print the resolved configuration
+    CheckConfig {
+        #[command(subcommand)]
+        solver: Solver,
+    },
+    /// serve several named solver instances, each under its own path
+    Instances {
+        /// A TOML file with the name, solver and configuration file of every
+        /// instance.
+        #[clap(long, env)]
+        config: PathBuf,
+    },
+    /// solve a single auction without starting the HTTP server and print the
+    /// solutions
+    Solve(Solve),
+    /// quote a single swap and show the raw DEX API response, the parsed swap
+    /// and its simulation
+    Quote(Quote),
+    /// solve recorded auctions with recorded DEX API and node responses
+    Replay(Replay),
+    /// serve a mock DEX API for running the solver engine locally
+    MockDex(MockDex),
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Solve {
+    /// A JSON file with the auction, in the format of `/solve` requests.
+    #[clap(long)]
+    pub auction: PathBuf,
+
+    /// Also print how every order was handled: the slippage the swap was
+    /// requested with, the amounts the DEX API quoted and why a swap was
+    /// rejected.
+    #[clap(long)]
+    pub explain: bool,
+
+    /// The time to solve the auction for. The deadline of the auction is
+    /// replaced, since it is usually in the past for recorded auctions.
+    #[clap(long, default_value = "10s", value_parser = humantime_serde::re::humantime::parse_duration)]
+    pub time_limit: Duration,
+
+    #[command(subcommand)]
+    pub solver: Solver,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Quote {
+    /// The token to sell.
+    #[clap(long)]
+    pub sell_token: Address,
+
+    /// The token to buy.
+    #[clap(long)]
+    pub buy_token: Address,
+
+    /// Whether the amount is the amount to sell or to buy.
+    #[clap(long, value_enum, default_value = "sell")]
+    pub side: Side,
+
+    /// The amount to sell or buy, in token atoms.
+    #[clap(long)]
+    pub amount: U256,
+
+    /// The minimum buy amount of sell quotes or the maximum sell amount of buy
+    /// quotes. The swap gets checked against it like against the limit price
+    /// of an order.
+    #[clap(long)]
+    pub limit: Option<U256>,
+
+    /// The owner of the order, which the swap gets simulated for.
+    #[clap(long, default_value_t = Address::ZERO)]
+    pub owner: Address,
+
+    /// The decimals of the sell token, which some DEX APIs need.
+    #[clap(long)]
+    pub sell_token_decimals: Option<u8>,
+
+    /// The decimals of the buy token, which some DEX APIs need.
+    #[clap(long)]
+    pub buy_token_decimals: Option<u8>,
+
+    #[command(subcommand)]
+    pub solver: Solver,
+}
+
+/// The side of a quoted swap.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+#[value(rename_all = "lowercase")]
+pub enum Side {
+    Sell,
+    Buy,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct Replay {
+    /// A JSONL file with one auction per line, in the format of `/solve`
+    /// requests.
+    #[clap(long)]
+    pub auctions: PathBuf,
+
+    /// The directory with the recorded responses, one `<auction-id>.json`
+    /// file per auction.
+    #[clap(long)]
+    pub fixtures: PathBuf,
+
+    /// Where to write the solutions, one JSON line per auction.
+    #[clap(long)]
+    pub output: PathBuf,
+
+    /// The solutions of a previous run to compare the solutions against.
+    #[clap(long)]
+    pub previous: Option<PathBuf>,
+
+    /// The time to solve each auction for. Recorded deadlines are replaced,
+    /// since they are in the past.
+    #[clap(long, default_value = "10s", value_parser = humantime_serde::re::humantime::parse_duration)]
+    pub time_limit: Duration,
+
+    #[command(subcommand)]
+    pub solver: Solver,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct MockDex {
+    /// The DEX API to mock.
+    #[clap(value_enum)]
+    pub dex: MockedDex,
+
+    /// The socket address to serve the mock DEX API on.
+    #[clap(long, default_value = "127.0.0.1:7873")]
+    pub addr: SocketAddr,
+
+    /// A directory with recorded fixtures to answer requests from. Requests
+    /// without a recorded response get a canned response.
+    #[clap(long)]
+    pub fixtures: Option<PathBuf>,
+
+    /// How long to wait before answering each request.
+    #[clap(long, default_value = "0s", value_parser = humantime_serde::re::humantime::parse_duration)]
+    pub latency: Duration,
+
+    /// Answer every n-th request with `429 Too Many Requests`.
+    #[clap(long)]
+    pub rate_limit_every: Option<NonZeroUsize>,
+
+    /// Answer every n-th request with `500 Internal Server Error`.
+    #[clap(long)]
+    pub error_every: Option<NonZeroUsize>,
+}
+
+/// The DEX APIs that can be mocked.
+#[derive(clap::ValueEnum, Clone, Copy, Debug)]
+#[value(rename_all = "lowercase")]
+pub enum MockedDex {
+    Balancer,
+    ZeroEx,
+    OneInch,
+    ParaSwap,
+}
+
 /// The solver engine to run. The config field is a path to the solver
 /// configuration file. This file should be in TOML format.
 #[derive(Subcommand, Debug)]
 #[clap(rename_all = "lowercase")]
-pub enum Command {
+pub enum Solver {
     /// solve individual orders using Balancer API
     Balancer {
         #[clap(long, env)]
@@ -55,3 +305,52 @@ pub enum Command {
         config: PathBuf,
     },
 }
+
+impl Solver {
+    /// Returns the path of the solver configuration file.
+    pub fn config(&self) -> &Path {
+        match self {
+            Solver::Balancer { config }
+            | Solver::ZeroEx { config }
+            | Solver::OneInch { config }
+            | Solver::ParaSwap { config } => config,
+        }
+    }
+
+    /// Returns the same solver with a different configuration file.
+    pub fn with_config(&self, config: PathBuf) -> Self {
+        match self {
+            Solver::Balancer { .. } => Solver::Balancer { config },
+            Solver::ZeroEx { .. } => Solver::ZeroEx { config },
+            Solver::OneInch { .. } => Solver::OneInch { config },
+            Solver::ParaSwap { .. } => Solver::ParaSwap { config },
+        }
+    }
+}
+
+/// A secret command line argument that doesn't get logged.
+#[derive(Clone)]
+pub struct Secret(pub String);
+
+impl Secret {
+    /// Reads a secret from a file, ignoring surrounding whitespace such as a
+    /// trailing newline.
+    pub async fn read(path: &Path) -> std::io::Result<Self> {
+        let secret = tokio::fs::read_to_string(path).await?;
+        Ok(Self(secret.trim().to_owned()))
+    }
+}
+
+impl FromStr for Secret {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self(s.to_owned()))
+    }
+}
+
+impl Debug for Secret {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
@@ -4,14 +4,15 @@ use {
         infra::{self, config::dex::file, dex},
     },
     alloy::primitives::Address,
+    anyhow::Context,
     contracts::{BalancerQueries, BalancerV2Vault, BalancerV3BatchRouter},
-    serde::Deserialize,
+    serde::{Deserialize, Serialize},
     serde_with::serde_as,
     std::path::Path,
 };
 
 #[serde_as]
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 struct Config {
     /// The URL of the Balancer SOR API.
@@ -43,7 +44,7 @@ struct Config {
     enabled_api_versions: Option<Vec<ApiVersion>>,
 }
 
-#[derive(Debug, Eq, PartialEq, Deserialize)]
+#[derive(Debug, Eq, PartialEq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 pub enum ApiVersion {
     V2,
@@ -56,39 +57,84 @@ impl ApiVersion {
     }
 }
 
-/// Load the driver configuration from a TOML file.
+impl Config {
+    /// Resolves the contract addresses of the enabled API versions that were
+    /// not configured explicitly from their known deployments.
+    fn resolve_contracts(&mut self) -> Result<(), anyhow::Error> {
+        let chain_id = self.chain_id.value();
+        let enabled_api_versions = self
+            .enabled_api_versions
+            .get_or_insert_with(ApiVersion::all);
+        if enabled_api_versions.contains(&ApiVersion::V2) {
+            self.vault = self
+                .vault
+                .or_else(|| BalancerV2Vault::deployment_address(&chain_id));
+            if self.queries.is_none() {
+                self.queries = Some(
+                    BalancerQueries::deployment_address(&chain_id)
+                        .context("Balancer Queries contract not found for chain")?,
+                );
+            }
+        }
+        if enabled_api_versions.contains(&ApiVersion::V3) {
+            self.v3_batch_router = self
+                .v3_batch_router
+                .or_else(|| BalancerV3BatchRouter::deployment_address(&chain_id));
+        }
+        Ok(())
+    }
+}
+
+/// Load the driver configuration of every chain from a TOML file.
 ///
 /// # Panics
 ///
 /// This method panics if the config is invalid or on I/O errors.
-pub async fn load(path: &Path) -> super::Config {
-    let (base, config) = file::load::<Config>(path).await;
-    let contracts = infra::contracts::Contracts::for_chain(config.chain_id);
-    let enabled_api_versions = config.enabled_api_versions.unwrap_or_else(ApiVersion::all);
-    let vault_contract = enabled_api_versions
-        .contains(&ApiVersion::V2)
-        .then(|| BalancerV2Vault::deployment_address(&config.chain_id.value()))
-        .flatten();
-    let batch_router = enabled_api_versions
-        .contains(&ApiVersion::V3)
-        .then(|| BalancerV3BatchRouter::deployment_address(&config.chain_id.value()))
-        .flatten();
-    let queries_contract = enabled_api_versions.contains(&ApiVersion::V2).then(|| {
-        BalancerQueries::deployment_address(&(config.chain_id as u64))
-            .expect("Balancer Queries contract not found for chain")
-    });
+pub async fn load(path: &Path, rate_limiters: &file::RateLimiters) -> Vec<super::Config> {
+    file::load::<Config>(path, rate_limiters)
+        .await
+        .into_iter()
+        .map(|(base, mut config)| {
+            let contracts = infra::contracts::Contracts::for_chain(config.chain_id);
+            config
+                .resolve_contracts()
+                .unwrap_or_else(|err| panic!("{err:#}"));
 
-    super::Config {
-        sor: dex::balancer::Config {
-            endpoint: config.endpoint,
-            vault: config.vault.or(vault_contract),
-            v3_batch_router: config.v3_batch_router.or(batch_router),
-            queries: config.queries.or(queries_contract),
-            permit2: config.permit2.unwrap_or(contracts.permit2),
-            settlement: base.contracts.settlement,
-            block_stream: base.block_stream.clone(),
-            chain_id: config.chain_id,
-        },
-        base,
-    }
+            super::Config {
+                sor: dex::balancer::Config {
+                    endpoint: config.endpoint,
+                    vault: config.vault,
+                    v3_batch_router: config.v3_batch_router,
+                    queries: config.queries,
+                    permit2: config.permit2.unwrap_or(contracts.permit2),
+                    settlement: base.contracts.settlement,
+                    block_stream: base.block_stream.clone(),
+                    chain_id: config.chain_id,
+                },
+                base,
+            }
+        })
+        .collect()
+}
+
+/// Creates a reloader for the runtime-tunable parts of the Balancer solver
+/// configuration.
+///
+/// # Panics
+///
+/// This method panics if the config is invalid or on I/O errors.
+pub async fn reloader(path: &Path, rate_limiters: file::RateLimiters) -> file::Reloader {
+    file::Reloader::new(path, rate_limiters, &[], |_| Ok(None)).await
+}
+
+/// Validates the Balancer solver configuration file without accessing the
+/// network, returning the resolved configuration with secrets redacted.
+pub async fn check(path: &Path) -> Result<toml::Table, file::Problems> {
+    file::check::<Config>(path, |config, problems| {
+        config
+            .permit2
+            .get_or_insert(infra::contracts::Contracts::for_chain(config.chain_id).permit2);
+        problems.check("dex.queries", config.resolve_contracts());
+    })
+    .await
 }
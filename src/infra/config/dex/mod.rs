@@ -4,14 +4,22 @@ pub mod oneinch;
 pub mod paraswap;
 pub mod zeroex;
 
+pub use file::{Problem, Problems, RateLimiters, Reload, ReloadError, Reloader};
 use {
-    crate::domain::{
-        dex::{minimum_surplus::MinimumSurplusLimits, slippage::SlippageLimits},
-        eth,
+    crate::{
+        domain::{
+            dex::{
+                minimum_surplus::MinimumSurplusLimits,
+                overrides::Overrides,
+                slippage::SlippageLimits,
+            },
+            eth,
+        },
+        infra::dex::{RateLimiter, simulator},
     },
     alloy::primitives::Address,
     ethrpc::block_stream::CurrentBlockWatcher,
-    std::num::NonZeroUsize,
+    std::{num::NonZeroUsize, sync::Arc, time::Duration},
 };
 
 #[derive(Clone)]
@@ -22,14 +30,43 @@ pub struct Contracts {
 
 #[derive(Clone)]
 pub struct Config {
+    /// The name of the solver instance, if it is one of several named
+    /// instances served by the process.
+    pub name: Option<String>,
     pub node_url: reqwest::Url,
+    pub chain_id: eth::ChainId,
     pub contracts: Contracts,
+    pub tunables: Tunables,
+    /// The DEX API rate limiter, shared by the chains that use the same API
+    /// key.
+    pub rate_limiter: Arc<RateLimiter>,
+    pub smallest_partial_fill: eth::Ether,
+    pub native_price_reference_amount: eth::Ether,
+    pub block_stream: Option<CurrentBlockWatcher>,
+    pub internalize_interactions: bool,
+    pub simulate_settlement: bool,
+    pub simulation_batching: Option<simulator::Batching>,
+}
+
+/// The parts of the solver configuration that can be changed at runtime
+/// without restarting the solver.
+#[derive(Clone)]
+pub struct Tunables {
     pub slippage: SlippageLimits,
     pub minimum_surplus: MinimumSurplusLimits,
     pub concurrent_requests: NonZeroUsize,
-    pub smallest_partial_fill: eth::Ether,
     pub rate_limiting_strategy: configs::rate_limit::Strategy,
+    /// The configuration the rate limiting strategy was created from.
+    pub back_off: BackOff,
     pub gas_offset: eth::Gas,
-    pub block_stream: Option<CurrentBlockWatcher>,
-    pub internalize_interactions: bool,
+    pub overrides: Overrides,
+}
+
+/// The back-off configuration of a DEX API rate limiter. Rate limiters keep
+/// their back-off state as long as this configuration doesn't change.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BackOff {
+    pub growth_factor: f64,
+    pub min: Duration,
+    pub max: Duration,
 }
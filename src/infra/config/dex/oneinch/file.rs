@@ -3,13 +3,13 @@ use {
         domain::eth,
         infra::{config::dex::file, contracts, dex::oneinch},
     },
-    serde::Deserialize,
+    serde::{Deserialize, Serialize},
     serde_with::serde_as,
     std::path::Path,
 };
 
 #[serde_as]
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 struct Config {
     /// Chain ID used to automatically determine the address of the settlement
@@ -39,34 +39,71 @@ struct Config {
     complexity_level: Option<u32>,
 }
 
-/// Load the 1inch solver configuration from a TOML file.
+/// Load the 1inch solver configuration of every chain from a TOML file.
 ///
 /// # Panics
 ///
 /// This method panics if the config is invalid or on I/O errors.
-pub async fn load(path: &Path) -> super::Config {
-    let (base, config) = file::load::<Config>(path).await;
+pub async fn load(path: &Path, rate_limiters: &file::RateLimiters) -> Vec<super::Config> {
+    file::load::<Config>(path, rate_limiters)
+        .await
+        .into_iter()
+        .map(|(base, config)| {
+            let settlement = contracts::Contracts::for_chain(config.chain_id).settlement;
 
-    let settlement = contracts::Contracts::for_chain(config.chain_id).settlement;
+            super::Config {
+                oneinch: oneinch::Config {
+                    settlement: eth::ContractAddress(settlement),
+                    endpoint: config.endpoint,
+                    liquidity: match (config.include_liquidity, config.exclude_liquidity) {
+                        (Some(include_liquidity), None) => {
+                            oneinch::Liquidity::Only(include_liquidity)
+                        }
+                        (None, Some(exclude_liquidity)) => {
+                            oneinch::Liquidity::Exclude(exclude_liquidity)
+                        }
+                        (None, None) => oneinch::Liquidity::Any,
+                        (Some(_), Some(_)) => {
+                            panic!("cannot specify both include-liquidity and exclude-liquidity")
+                        }
+                    },
+                    referrer: config.referrer,
+                    main_route_parts: config.main_route_parts,
+                    connector_tokens: config.connector_tokens,
+                    complexity_level: config.complexity_level,
+                    block_stream: base.block_stream.clone(),
+                },
+                base,
+            }
+        })
+        .collect()
+}
+
+/// Creates a reloader for the runtime-tunable parts of the 1inch solver
+/// configuration.
+///
+/// # Panics
+///
+/// This method panics if the config is invalid or on I/O errors.
+pub async fn reloader(path: &Path, rate_limiters: file::RateLimiters) -> file::Reloader {
+    // Excluded liquidity is resolved into the list of allowed protocols on
+    // startup, so changing it requires a restart.
+    file::Reloader::new(path, rate_limiters, &[], |_| Ok(None)).await
+}
 
-    super::Config {
-        oneinch: oneinch::Config {
-            settlement: eth::ContractAddress(settlement),
-            endpoint: config.endpoint,
-            liquidity: match (config.include_liquidity, config.exclude_liquidity) {
-                (Some(include_liquidity), None) => oneinch::Liquidity::Only(include_liquidity),
-                (None, Some(exclude_liquidity)) => oneinch::Liquidity::Exclude(exclude_liquidity),
-                (None, None) => oneinch::Liquidity::Any,
-                (Some(_), Some(_)) => {
-                    panic!("cannot specify both include-liquidity and exclude-liquidity")
-                }
-            },
-            referrer: config.referrer,
-            main_route_parts: config.main_route_parts,
-            connector_tokens: config.connector_tokens,
-            complexity_level: config.complexity_level,
-            block_stream: base.block_stream.clone(),
-        },
-        base,
-    }
+/// Validates the 1inch solver configuration file without accessing the
+/// network, returning the resolved configuration with secrets redacted.
+pub async fn check(path: &Path) -> Result<toml::Table, file::Problems> {
+    file::check::<Config>(path, |config, problems| {
+        config
+            .endpoint
+            .get_or_insert_with(|| oneinch::DEFAULT_URL.parse().unwrap());
+        if config.include_liquidity.is_some() && config.exclude_liquidity.is_some() {
+            problems.push(
+                "dex.exclude-liquidity",
+                "cannot specify both include-liquidity and exclude-liquidity",
+            );
+        }
+    })
+    .await
 }
@@ -3,13 +3,13 @@ use {
         domain::eth::{self, ChainId},
         infra::{config::dex::file, dex::paraswap},
     },
-    serde::Deserialize,
+    serde::{Deserialize, Serialize},
     serde_with::serde_as,
     std::path::Path,
 };
 
 #[serde_as]
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 struct Config {
     /// The base URL for the ParaSwap API.
@@ -38,27 +38,59 @@ struct Config {
     pub chain_id: u64,
 }
 
-/// Load the ParaSwap solver configuration from a TOML file.
+/// Load the ParaSwap solver configuration of every chain from a TOML file.
 ///
 /// # Panics
 ///
 /// This method panics if the config is invalid or on I/O errors.
-pub async fn load(path: &Path) -> super::Config {
-    let (base, config) = file::load::<Config>(path).await;
+pub async fn load(path: &Path, rate_limiters: &file::RateLimiters) -> Vec<super::Config> {
+    file::load::<Config>(path, rate_limiters)
+        .await
+        .into_iter()
+        .map(|(base, config)| super::Config {
+            paraswap: paraswap::Config {
+                endpoint: config
+                    .endpoint
+                    .unwrap_or_else(|| paraswap::DEFAULT_URL.parse().unwrap()),
+                exclude_dexs: config.exclude_dexs,
+                ignore_bad_usd_price: config.ignore_bad_usd_price,
+                address: config.address,
+                api_key: config.api_key,
+                partner: config.partner,
+                chain_id: ChainId::new(config.chain_id).unwrap(),
+                block_stream: base.block_stream.clone(),
+            },
+            base,
+        })
+        .collect()
+}
+
+/// Creates a reloader for the runtime-tunable parts of the ParaSwap solver
+/// configuration.
+///
+/// # Panics
+///
+/// This method panics if the config is invalid or on I/O errors.
+pub async fn reloader(path: &Path, rate_limiters: file::RateLimiters) -> file::Reloader {
+    file::Reloader::new(path, rate_limiters, &["exclude-dexs"], |dex| {
+        Ok(Some(dex.try_into::<Config>()?.exclude_dexs))
+    })
+    .await
+}
 
-    super::Config {
-        paraswap: paraswap::Config {
-            endpoint: config
-                .endpoint
-                .unwrap_or_else(|| paraswap::DEFAULT_URL.parse().unwrap()),
-            exclude_dexs: config.exclude_dexs,
-            ignore_bad_usd_price: config.ignore_bad_usd_price,
-            address: config.address,
-            api_key: config.api_key,
-            partner: config.partner,
-            chain_id: ChainId::new(config.chain_id).unwrap(),
-            block_stream: base.block_stream.clone(),
-        },
-        base,
-    }
+/// Validates the ParaSwap solver configuration file without accessing the
+/// network, returning the resolved configuration with secrets redacted.
+pub async fn check(path: &Path) -> Result<toml::Table, file::Problems> {
+    file::check::<Config>(path, |config, problems| {
+        config
+            .endpoint
+            .get_or_insert_with(|| paraswap::DEFAULT_URL.parse().unwrap());
+        if let Err(err) = ChainId::new(config.chain_id) {
+            problems.push("dex.chain-id", err);
+        }
+        if let Err(err) = reqwest::header::HeaderValue::from_str(&config.api_key) {
+            problems.push("dex.api-key", err);
+        }
+    })
+    .await
 }
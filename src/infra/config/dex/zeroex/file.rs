@@ -3,13 +3,13 @@ use {
         domain::eth,
         infra::{config::dex::file, contracts, dex::zeroex},
     },
-    serde::Deserialize,
+    serde::{Deserialize, Serialize},
     serde_with::serde_as,
     std::path::Path,
 };
 
 #[serde_as]
-#[derive(Deserialize)]
+#[derive(Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case", deny_unknown_fields)]
 struct Config {
     /// Chain ID used to automatically determine the address of the settlement
@@ -31,29 +31,57 @@ struct Config {
     excluded_sources: Vec<String>,
 }
 
-fn default_endpoint() -> reqwest::Url {
+pub fn default_endpoint() -> reqwest::Url {
     "https://api.0x.org/swap/allowance-holder/".parse().unwrap()
 }
 
-/// Load the 0x solver configuration from a TOML file.
+/// Load the 0x solver configuration of every chain from a TOML file.
 ///
 /// # Panics
 ///
 /// This method panics if the config is invalid or on I/O errors.
-pub async fn load(path: &Path) -> super::Config {
-    let (base, config) = file::load::<Config>(path).await;
-
-    let settlement = contracts::Contracts::for_chain(config.chain_id).settlement;
-
-    super::Config {
-        zeroex: zeroex::Config {
-            chain_id: config.chain_id,
-            endpoint: config.endpoint,
-            api_key: config.api_key,
-            excluded_sources: config.excluded_sources,
-            settlement: eth::ContractAddress(settlement),
-            block_stream: base.block_stream.clone(),
-        },
-        base,
-    }
+pub async fn load(path: &Path, rate_limiters: &file::RateLimiters) -> Vec<super::Config> {
+    file::load::<Config>(path, rate_limiters)
+        .await
+        .into_iter()
+        .map(|(base, config)| {
+            let settlement = contracts::Contracts::for_chain(config.chain_id).settlement;
+
+            super::Config {
+                zeroex: zeroex::Config {
+                    chain_id: config.chain_id,
+                    endpoint: config.endpoint,
+                    api_key: config.api_key,
+                    excluded_sources: config.excluded_sources,
+                    settlement: eth::ContractAddress(settlement),
+                    block_stream: base.block_stream.clone(),
+                },
+                base,
+            }
+        })
+        .collect()
+}
+
+/// Creates a reloader for the runtime-tunable parts of the 0x solver
+/// configuration.
+///
+/// # Panics
+///
+/// This method panics if the config is invalid or on I/O errors.
+pub async fn reloader(path: &Path, rate_limiters: file::RateLimiters) -> file::Reloader {
+    file::Reloader::new(path, rate_limiters, &["excluded-sources"], |dex| {
+        Ok(Some(dex.try_into::<Config>()?.excluded_sources))
+    })
+    .await
+}
+
+/// Validates the 0x solver configuration file without accessing the network,
+/// returning the resolved configuration with secrets redacted.
+pub async fn check(path: &Path) -> Result<toml::Table, file::Problems> {
+    file::check::<Config>(path, |config, problems| {
+        if let Err(err) = reqwest::header::HeaderValue::from_str(&config.api_key) {
+            problems.push("dex.api-key", err);
+        }
+    })
+    .await
 }
@@ -0,0 +1,167 @@
+//! Configuration of several named solver instances that get served by a
+//! single process.
+
+use {
+    crate::infra::{cli, config::unwrap_or_log},
+    serde::Deserialize,
+    std::path::{Path, PathBuf},
+    tokio::fs,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct Config {
+    instances: Vec<InstanceConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct InstanceConfig {
+    /// The name of the instance, which it gets served under.
+    name: String,
+
+    /// The DEX API the instance solves with.
+    solver: Kind,
+
+    /// The path of the solver configuration file, relative to this file.
+    config: PathBuf,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+    Balancer,
+    ZeroEx,
+    OneInch,
+    ParaSwap,
+}
+
+/// A named solver instance.
+#[derive(Debug)]
+pub struct Instance {
+    pub name: String,
+    pub solver: cli::Solver,
+}
+
+/// Paths of the solver engine API that can't be used as instance names.
+const RESERVED_NAMES: &[&str] = &[
+    "debug",
+    "healthz",
+    "metrics",
+    "native_price",
+    "notify",
+    "quote",
+    "readyz",
+    "solve",
+];
+
+/// Loads the solver instances from a TOML file.
+///
+/// # Panics
+///
+/// This method panics if the config is invalid or on I/O errors.
+pub async fn load(path: &Path) -> Vec<Instance> {
+    let data = fs::read_to_string(path)
+        .await
+        .unwrap_or_else(|e| panic!("I/O error while reading {path:?}: {e:?}"));
+    let config = unwrap_or_log(toml::de::from_str::<Config>(&data), &path);
+    let dir = path.parent().unwrap_or(Path::new(""));
+
+    assert!(
+        !config.instances.is_empty(),
+        "invalid instances configuration: at least one instance must be configured"
+    );
+    let mut instances = Vec::<Instance>::new();
+    for instance in config.instances {
+        if let Err(err) = validate_name(&instance.name) {
+            panic!("invalid instance name {:?}: {err}", instance.name);
+        }
+        assert!(
+            instances.iter().all(|other| other.name != instance.name),
+            "invalid instances configuration: instance {:?} is configured more than once",
+            instance.name,
+        );
+
+        let config = dir.join(instance.config);
+        instances.push(Instance {
+            name: instance.name,
+            solver: match instance.solver {
+                Kind::Balancer => cli::Solver::Balancer { config },
+                Kind::ZeroEx => cli::Solver::ZeroEx { config },
+                Kind::OneInch => cli::Solver::OneInch { config },
+                Kind::ParaSwap => cli::Solver::ParaSwap { config },
+            },
+        });
+    }
+    instances
+}
+
+/// Checks that an instance name can be used as a path segment without
+/// clashing with chain IDs or other endpoints.
+fn validate_name(name: &str) -> Result<(), &'static str> {
+    if name.is_empty() {
+        return Err("must not be empty");
+    }
+    if !name
+        .chars()
+        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        return Err("must only contain lowercase letters, digits, dashes and underscores");
+    }
+    if name.chars().all(|c| c.is_ascii_digit()) {
+        return Err("must not be a number, which would clash with chain IDs");
+    }
+    if RESERVED_NAMES.contains(&name) {
+        return Err("is reserved for an endpoint");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_names() {
+        assert!(validate_name("balancer-tight").is_ok());
+        assert!(validate_name("zeroex_2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("Balancer").is_err());
+        assert!(validate_name("balancer/tight").is_err());
+        assert!(validate_name("100").is_err());
+        assert!(validate_name("metrics").is_err());
+    }
+
+    #[tokio::test]
+    async fn resolves_configs_relative_to_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("instances.toml");
+        std::fs::write(
+            &path,
+            r#"
+            [[instances]]
+            name = "balancer-tight"
+            solver = "balancer"
+            config = "balancer-tight.toml"
+
+            [[instances]]
+            name = "zeroex"
+            solver = "zeroex"
+            config = "/etc/solvers/zeroex.toml"
+            "#,
+        )
+        .unwrap();
+
+        let instances = load(&path).await;
+        assert_eq!(instances.len(), 2);
+        assert_eq!(instances[0].name, "balancer-tight");
+        assert!(matches!(
+            &instances[0].solver,
+            cli::Solver::Balancer { config } if *config == dir.path().join("balancer-tight.toml")
+        ));
+        assert!(matches!(
+            &instances[1].solver,
+            cli::Solver::ZeroEx { config } if config == Path::new("/etc/solvers/zeroex.toml")
+        ));
+    }
+}
@@ -1,6 +1,46 @@
-use std::fmt::Debug;
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        LazyLock,
+        Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
 pub mod dex;
+pub mod instances;
+
+/// Configurations that are read from memory instead of from a file, by the
+/// path they are read from. Derived configurations contain the secrets of the
+/// configuration they are derived from, so they are never written to disk.
+static IN_MEMORY: LazyLock<Mutex<HashMap<PathBuf, String>>> = LazyLock::new(Default::default);
+static NEXT_IN_MEMORY: AtomicU64 = AtomicU64::new(0);
+
+/// Stores a configuration in memory and returns the path to read it from.
+/// The path doesn't exist on disk.
+pub fn in_memory(name: &str, data: String) -> PathBuf {
+    let id = NEXT_IN_MEMORY.fetch_add(1, Ordering::Relaxed);
+    let path = PathBuf::from(format!("<in-memory {name} #{id}>"));
+    IN_MEMORY.lock().unwrap().insert(path.clone(), data);
+    path
+}
+
+/// Removes a configuration that is stored in memory.
+pub fn forget(path: &Path) {
+    IN_MEMORY.lock().unwrap().remove(path);
+}
+
+/// Reads a configuration file, or the configuration stored in memory for
+/// the path.
+async fn read(path: &Path) -> io::Result<String> {
+    if let Some(data) = IN_MEMORY.lock().unwrap().get(path) {
+        return Ok(data.clone());
+    }
+    tokio::fs::read_to_string(path).await
+}
 
 /// Unwraps result or logs a `TOML` parsing error.
 fn unwrap_or_log<T, E, P>(result: Result<T, E>, path: &P) -> T
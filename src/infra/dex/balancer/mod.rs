@@ -6,7 +6,7 @@ use {
             eth::{self, TokenAddress},
             order::{self, Side},
         },
-        infra::{config::dex::balancer::file::ApiVersion, dex::balancer::dto::Chain},
+        infra::{config::dex::balancer::file::ApiVersion, dex::balancer::dto::Chain, metrics},
         util,
     },
     alloy::primitives::{Address, Bytes, FixedBytes, I256, U256},
@@ -299,7 +299,13 @@ impl Sor {
         Ok((kind, swaps, funds))
     }
 
+    /// Checks that the API is reachable.
+    pub async fn probe(&self) -> Result<(), util::http::Error> {
+        self.client.probe(self.endpoint.clone()).await
+    }
+
     async fn quote(&self, query: &dto::Query<'_>) -> Result<dto::Quote, Error> {
+        let _timer = metrics::dex_request("balancer", "sor");
         let response = util::http::roundtrip!(
             <dto::GetSwapPathsResponse, util::serialize::Never>;
             self.client
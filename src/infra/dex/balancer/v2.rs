@@ -3,7 +3,7 @@
 //! bindings, defining structs with named fields instead of using tuples.
 
 use {
-    crate::domain::dex,
+    crate::domain::{dex, eth},
     alloy::{
         primitives::{Address, I256, U256},
         sol_types::SolCall,
@@ -61,6 +61,7 @@ impl Vault {
         vec![dex::Call {
             to: self.address(),
             calldata,
+            value: eth::Ether::default(),
         }]
     }
 }
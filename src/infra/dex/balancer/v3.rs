@@ -3,7 +3,7 @@
 //! bindings, defining structs with named fields instead of using tuples.
 
 use {
-    crate::domain::dex,
+    crate::domain::{dex, eth},
     alloy::{
         primitives::{Address, Bytes, U160, U256, aliases::U48},
         sol_types::SolCall,
@@ -53,7 +53,11 @@ impl Permit2 {
         }
         .abi_encode();
 
-        dex::Call { to, calldata }
+        dex::Call {
+            to,
+            calldata,
+            value: eth::Ether::default(),
+        }
     }
 }
 
@@ -94,6 +98,7 @@ impl Router {
             dex::Call {
                 to: self.address(),
                 calldata,
+                value: eth::Ether::default(),
             },
         ]
     }
@@ -167,6 +172,7 @@ impl Router {
             dex::Call {
                 to: self.address(),
                 calldata,
+                value: eth::Ether::default(),
             },
         ]
     }
@@ -1,5 +1,9 @@
 use {
-    crate::domain::{auction, dex},
+    crate::{
+        domain::{auction, dex},
+        infra::{fixture, metrics},
+        util,
+    },
     ethrpc::block_stream::CurrentBlockWatcher,
     reqwest::RequestBuilder,
 };
@@ -7,10 +11,11 @@ use {
 pub mod balancer;
 pub mod oneinch;
 pub mod paraswap;
+pub mod rate_limiter;
 pub mod simulator;
 pub mod zeroex;
 
-pub use self::simulator::Simulator;
+pub use self::{rate_limiter::RateLimiter, simulator::Simulator};
 
 /// A supported external DEX/DEX aggregator API.
 pub enum Dex {
@@ -39,6 +44,38 @@ impl Dex {
         };
         Ok(swap)
     }
+
+    /// The name of the DEX API, for instrumentation purposes.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Dex::Balancer(_) => "balancer",
+            Dex::OneInch(_) => "oneinch",
+            Dex::ZeroEx(_) => "zeroex",
+            Dex::ParaSwap(_) => "paraswap",
+        }
+    }
+
+    /// Checks that the DEX API is reachable and accepts the configured
+    /// credentials with a lightweight request.
+    pub async fn probe(&self) -> Result<(), util::http::Error> {
+        let _timer = metrics::dex_request(self.name(), "probe");
+        match self {
+            Dex::Balancer(balancer) => balancer.probe().await,
+            Dex::OneInch(oneinch) => oneinch.probe().await,
+            Dex::ZeroEx(zeroex) => zeroex.probe().await,
+            Dex::ParaSwap(paraswap) => paraswap.probe().await,
+        }
+    }
+
+    /// Replaces the excluded liquidity sources of the DEX APIs that support
+    /// changing them at runtime.
+    pub fn set_excluded_sources(&self, excluded_sources: Vec<String>) {
+        match self {
+            Dex::ZeroEx(zeroex) => zeroex.set_excluded_sources(excluded_sources),
+            Dex::ParaSwap(paraswap) => paraswap.set_exclude_dexs(excluded_sources),
+            Dex::Balancer(_) | Dex::OneInch(_) => {}
+        }
+    }
 }
 
 /// A categorized error that occurred building a swap with an external DEX/DEX
@@ -77,13 +114,37 @@ impl Client {
 
     /// Prepares a request builder which already has additional headers set.
     pub fn request(&self, method: reqwest::Method, url: reqwest::Url) -> RequestBuilder {
-        let request = self.client.request(method, url);
+        let mut request = self.client.request(method, url);
         if let Some(stream) = &self.block_stream {
             // Set this header to easily support caching in an egress proxy.
-            request.header("X-CURRENT-BLOCK-HASH", stream.borrow().hash.to_string())
-        } else {
-            request
+            request = request.header("X-CURRENT-BLOCK-HASH", stream.borrow().hash.to_string());
+        }
+        if let Some(recording) = fixture::recorder::current() {
+            // Lets the recording proxy attribute the request to its auction.
+            request = request.header(fixture::recorder::HEADER, recording.to_string());
+        }
+        request
+    }
+
+    /// Sends a request without parameters to an API endpoint. Responses that
+    /// only reject the missing parameters mean that the API is reachable and
+    /// accepts the credentials, so only authentication, rate limiting and
+    /// server errors are returned.
+    pub async fn probe(&self, url: reqwest::Url) -> Result<(), util::http::Error> {
+        let response = self.request(reqwest::Method::GET, url).send().await?;
+        let status = response.status();
+        if status.is_server_error()
+            || matches!(
+                status,
+                reqwest::StatusCode::UNAUTHORIZED
+                    | reqwest::StatusCode::FORBIDDEN
+                    | reqwest::StatusCode::TOO_MANY_REQUESTS
+            )
+        {
+            let body = response.text().await.unwrap_or_default();
+            return Err(util::http::Error::Status(status, body));
         }
+        Ok(())
     }
 }
 
@@ -167,6 +167,11 @@ pub struct Tx {
     #[serde_as(as = "serialize::Hex")]
     pub data: Vec<u8>,
 
+    /// The amount of native token that needs to be sent with the swap.
+    #[serde(default)]
+    #[serde_as(as = "serialize::U256")]
+    pub value: U256,
+
     /// The estimated gas limit required for executing the transaction.
     pub gas: u64,
 }
@@ -1,6 +1,7 @@
 use {
     crate::{
         domain::{dex, eth},
+        infra::metrics,
         util,
     },
     alloy::primitives::U256,
@@ -93,6 +94,7 @@ impl OneInch {
             Liquidity::Any => None,
             Liquidity::Only(protocols) => Some(protocols),
             Liquidity::Exclude(excluded) => {
+                let _timer = metrics::dex_request("oneinch", "liquidity-sources");
                 let liquidity = util::http::roundtrip!(
                     <dto::Liquidity, dto::Error>;
                     client.request(reqwest::Method::GET, util::url::join(&endpoint, "liquidity-sources"))
@@ -119,6 +121,7 @@ impl OneInch {
             ..Default::default()
         };
 
+        let timer = metrics::dex_request("oneinch", "approve/spender");
         let spender = eth::ContractAddress(
             util::http::roundtrip!(
                 <dto::Spender, dto::Error>;
@@ -127,6 +130,7 @@ impl OneInch {
             .await?
             .address,
         );
+        drop(timer);
 
         Ok(Self {
             client,
@@ -157,6 +161,7 @@ impl OneInch {
             calls: vec![dex::Call {
                 to: swap.tx.to,
                 calldata: swap.tx.data,
+                value: eth::Ether(swap.tx.value),
             }],
             input: eth::Asset {
                 token: order.sell,
@@ -174,7 +179,15 @@ impl OneInch {
         })
     }
 
+    /// Checks that the API is reachable.
+    pub async fn probe(&self) -> Result<(), util::http::Error> {
+        self.client
+            .probe(util::url::join(&self.endpoint, "approve/spender"))
+            .await
+    }
+
     async fn quote(&self, query: &dto::Query) -> Result<dto::Swap, Error> {
+        let _timer = metrics::dex_request("oneinch", "swap");
         let swap = util::http::roundtrip!(
             <dto::Swap, dto::Error>;
             self.client
@@ -68,6 +68,7 @@ pub struct SwapQuery {
 impl SwapQuery {
     pub fn new(
         config: &super::Config,
+        exclude_dexs: Vec<String>,
         order: &dex::Order,
         tokens: &auction::Tokens,
         slippage: &dex::Slippage,
@@ -86,7 +87,7 @@ impl SwapQuery {
                 order::Side::Sell => Side::Sell,
             },
             amount: order.amount.get(),
-            exclude_dexs: config.exclude_dexs.clone(),
+            exclude_dexs,
             ignore_bad_usd_price: config.ignore_bad_usd_price,
             network: config.chain_id.network_id().to_string(),
             partner: config.partner.clone(),
@@ -141,6 +142,11 @@ pub struct TxParams {
 
     #[serde_as(as = "serialize::Hex")]
     pub data: Vec<u8>,
+
+    /// The amount of native token that needs to be sent with the swap.
+    #[serde(default)]
+    #[serde_as(as = "serialize::U256")]
+    pub value: U256,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1,9 +1,11 @@
 use {
     crate::{
         domain::{auction, dex, eth},
+        infra::metrics,
         util,
     },
     ethrpc::block_stream::CurrentBlockWatcher,
+    std::sync::RwLock,
 };
 
 mod dto;
@@ -14,6 +16,8 @@ pub const DEFAULT_URL: &str = "https://apiv5.paraswap.io";
 pub struct ParaSwap {
     client: super::Client,
     config: Config,
+    /// The DEXs to exclude, which can be changed at runtime.
+    exclude_dexs: RwLock<Vec<String>>,
 }
 
 #[derive(Debug)]
@@ -60,10 +64,16 @@ impl ParaSwap {
 
         Self {
             client: super::Client::new(client, config.block_stream.clone()),
+            exclude_dexs: RwLock::new(config.exclude_dexs.clone()),
             config,
         }
     }
 
+    /// Replaces the list of excluded DEXs for subsequent swaps.
+    pub fn set_exclude_dexs(&self, exclude_dexs: Vec<String>) {
+        *self.exclude_dexs.write().unwrap() = exclude_dexs;
+    }
+
     /// Make a request to the `/swap` endpoint.
     pub async fn swap(
         &self,
@@ -71,17 +81,21 @@ impl ParaSwap {
         slippage: &dex::Slippage,
         tokens: &auction::Tokens,
     ) -> Result<dex::Swap, Error> {
-        let query = dto::SwapQuery::new(&self.config, order, tokens, slippage)?;
+        let exclude_dexs = self.exclude_dexs.read().unwrap().clone();
+        let query = dto::SwapQuery::new(&self.config, exclude_dexs, order, tokens, slippage)?;
+        let timer = metrics::dex_request("paraswap", "swap");
         let swap = util::http::roundtrip!(
             <dto::Swap, dto::Error>;
             self.client.request(reqwest::Method::GET, util::url::join(&self.config.endpoint, "swap"))
                 .query(&query)
         )
         .await?;
+        drop(timer);
         Ok(dex::Swap {
             calls: vec![dex::Call {
                 to: swap.tx_params.to,
                 calldata: swap.tx_params.data,
+                value: eth::Ether(swap.tx_params.value),
             }],
             input: eth::Asset {
                 token: order.sell,
@@ -98,6 +112,13 @@ impl ParaSwap {
             gas: eth::Gas(swap.price_route.gas_cost),
         })
     }
+
+    /// Checks that the API is reachable and accepts the API key.
+    pub async fn probe(&self) -> Result<(), util::http::Error> {
+        self.client
+            .probe(util::url::join(&self.config.endpoint, "swap"))
+            .await
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
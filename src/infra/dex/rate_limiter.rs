@@ -0,0 +1,143 @@
+//! A DEX API rate limiter that keeps track of its back-off state.
+
+use {
+    crate::infra::config::dex::BackOff,
+    std::{future::Future, sync::Mutex},
+};
+
+/// Backs off from a rate limited DEX API like [`rate_limit::RateLimiter`],
+/// whose back-off state can't be inspected, and mirrors that state so that it
+/// can be shown by the debug API.
+pub struct RateLimiter {
+    inner: rate_limit::RateLimiter,
+    back_off: BackOff,
+    state: Mutex<State>,
+}
+
+/// The back-off state of a rate limiter.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct State {
+    /// The number of requests in a row that the DEX API rate limited.
+    pub consecutive_failures: u32,
+    /// Until when requests get dropped without being sent to the DEX API, if
+    /// the last request got rate limited.
+    pub backoff_until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl RateLimiter {
+    pub fn new(strategy: configs::rate_limit::Strategy, back_off: BackOff) -> Self {
+        Self {
+            inner: rate_limit::RateLimiter::from_strategy(strategy, "dex_api".to_string()),
+            back_off,
+            state: Default::default(),
+        }
+    }
+
+    /// The back-off configuration the rate limiter was created with.
+    pub fn back_off(&self) -> BackOff {
+        self.back_off
+    }
+
+    /// Executes the task unless requests are currently being dropped, and
+    /// backs off if the result of the task requires it.
+    pub async fn execute_with_back_off<T>(
+        &self,
+        task: impl Future<Output = T>,
+        requires_back_off: impl Fn(&T) -> bool,
+    ) -> Result<T, rate_limit::Error> {
+        let result = self
+            .inner
+            .execute_with_back_off(task, &requires_back_off)
+            .await;
+        if let Ok(result) = &result {
+            let mut state = self.state.lock().unwrap();
+            if requires_back_off(result) {
+                state.consecutive_failures += 1;
+                let back_off = self.back_off.after(state.consecutive_failures);
+                state.backoff_until = chrono::Duration::from_std(back_off)
+                    .ok()
+                    .map(|back_off| chrono::Utc::now() + back_off);
+            } else {
+                *state = State::default();
+            }
+        }
+        result
+    }
+
+    /// The current back-off state.
+    pub fn state(&self) -> State {
+        let mut state = *self.state.lock().unwrap();
+        if state
+            .backoff_until
+            .is_some_and(|until| until <= chrono::Utc::now())
+        {
+            state.backoff_until = None;
+        }
+        state
+    }
+}
+
+impl BackOff {
+    /// The time to back off for after the specified number of rate limited
+    /// requests in a row: the minimum back-off, growing by the growth factor
+    /// with every further rate limited request up to the maximum.
+    fn after(&self, consecutive_failures: u32) -> std::time::Duration {
+        let factor = self.growth_factor.powi(
+            consecutive_failures
+                .saturating_sub(1)
+                .try_into()
+                .unwrap_or(i32::MAX),
+        );
+        std::time::Duration::try_from_secs_f64(self.min.as_secs_f64() * factor)
+            .unwrap_or(self.max)
+            .min(self.max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::time::Duration};
+
+    #[test]
+    fn back_off_grows_up_to_the_maximum() {
+        let back_off = BackOff {
+            growth_factor: 2.,
+            min: Duration::from_secs(1),
+            max: Duration::from_secs(5),
+        };
+        assert_eq!(back_off.after(1), Duration::from_secs(1));
+        assert_eq!(back_off.after(2), Duration::from_secs(2));
+        assert_eq!(back_off.after(3), Duration::from_secs(4));
+        assert_eq!(back_off.after(4), Duration::from_secs(5));
+        assert_eq!(back_off.after(u32::MAX), Duration::from_secs(5));
+    }
+
+    #[tokio::test]
+    async fn tracks_back_off_state() {
+        let back_off = BackOff {
+            growth_factor: 2.,
+            min: Duration::from_secs(60),
+            max: Duration::from_secs(600),
+        };
+        let rate_limiter = RateLimiter::new(
+            configs::rate_limit::Strategy::try_new(2., back_off.min, back_off.max).unwrap(),
+            back_off,
+        );
+
+        let result = rate_limiter
+            .execute_with_back_off(async { 429 }, |status| *status == 429)
+            .await;
+        assert!(matches!(result, Ok(429)));
+        let state = rate_limiter.state();
+        assert_eq!(state.consecutive_failures, 1);
+        assert!(state.backoff_until.unwrap() > chrono::Utc::now());
+
+        // Requests get dropped while backing off, which doesn't count as
+        // another rate limited request.
+        let result = rate_limiter
+            .execute_with_back_off(async { 200 }, |status| *status == 429)
+            .await;
+        assert!(matches!(result, Err(rate_limit::Error::RateLimited)));
+        assert_eq!(rate_limiter.state().consecutive_failures, 1);
+    }
+}
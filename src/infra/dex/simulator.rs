@@ -1,20 +1,35 @@
 use {
     crate::{
-        domain::{dex, eth},
-        infra::blockchain,
+        domain::{dex, eth, solution},
+        infra::{
+            blockchain,
+            metrics,
+            settlement::{self, Settlement},
+        },
     },
     alloy::{
-        primitives::{Address, U256},
-        providers::DynProvider,
-        rpc::types::state::{AccountOverride, StateOverridesBuilder},
+        primitives::{Address, Bytes, U256},
+        providers::{DynProvider, Provider},
+        rpc::types::{
+            BlockId,
+            TransactionRequest,
+            state::{AccountOverride, StateOverridesBuilder},
+        },
+        sol_types::SolCall,
+        transports::TransportError,
     },
-    contracts::support::{
-        AnyoneAuthenticator,
-        Swapper::{
-            self,
-            Swapper::{Allowance, Asset, Interaction},
+    contracts::{
+        GPv2Settlement,
+        support::{
+            AnyoneAuthenticator,
+            Swapper::{
+                self,
+                Swapper::{Allowance, Asset, Interaction},
+            },
         },
     },
+    std::{num::NonZeroUsize, sync::Arc, time::Duration},
+    tokio::sync::{mpsc, oneshot},
 };
 
 /// A DEX swap simulator.
@@ -23,88 +38,283 @@ pub struct Simulator {
     web3: DynProvider,
     settlement: Address,
     authenticator: Address,
+    batcher: Option<Batcher>,
+}
+
+/// Parameters for batching gas simulations together.
+#[derive(Debug, Clone, Copy)]
+pub struct Batching {
+    /// How long to wait for more simulations after receiving the first one of
+    /// a batch.
+    pub window: Duration,
+    /// The maximum number of simulations executed in a single batch.
+    pub max_size: NonZeroUsize,
 }
 
 impl Simulator {
     /// Create a new simulator for computing DEX swap gas usage.
-    pub fn new(url: &reqwest::Url, settlement: Address, authenticator: Address) -> Self {
+    ///
+    /// If `batching` is configured, the `eth_call`s of gas simulations get
+    /// sent in JSON-RPC batch requests instead of one request each.
+    pub fn new(
+        url: &reqwest::Url,
+        settlement: Address,
+        authenticator: Address,
+        batching: Option<Batching>,
+    ) -> Self {
+        let web3 = blockchain::rpc(url).provider;
+        let batcher =
+            batching.map(|batching| Batcher::spawn(web3.clone(), authenticator, batching));
         Self {
-            web3: blockchain::rpc(url).provider,
+            web3,
             settlement,
             authenticator,
+            batcher,
         }
     }
 
+    /// Returns the number of the latest block, to check that the node is
+    /// reachable.
+    pub async fn block_number(&self) -> Result<u64, Error> {
+        Ok(self.web3.get_block_number().await?)
+    }
+
     /// Simulate the gas needed by a single order DEX swap.
     ///
     /// This will return a `None` if the gas simulation is unavailable.
-    pub async fn gas(&self, owner: Address, swap: &dex::Swap) -> Result<eth::Gas, Error> {
+    pub async fn gas(
+        &self,
+        owner: Address,
+        swap: &dex::Swap,
+        unwraps: &dex::Unwraps,
+        wrapped: eth::WrappedNativeToken,
+    ) -> Result<Option<eth::Gas>, Error> {
         if owner == self.settlement {
             // we can't have both the settlement and swapper contracts at the same address
             return Err(Error::SettlementContractIsOwner);
         }
 
-        let swapper = Swapper::Instance::new(owner, self.web3.clone());
-        let overrides = StateOverridesBuilder::with_capacity(2)
-            // Setup up our trader code that actually executes the settlement
-            .append(
-                *swapper.address(),
-                AccountOverride {
-                    code: Some(Swapper::Swapper::DEPLOYED_BYTECODE.clone()),
-                    ..Default::default()
-                },
-            )
-            // Override the CoW protocol solver authenticator with one that
-            // allows any address to solve
-            .append(
-                self.authenticator,
-                AccountOverride {
-                    code: Some(AnyoneAuthenticator::AnyoneAuthenticator::DEPLOYED_BYTECODE.clone()),
-                    ..Default::default()
-                },
-            );
+        let _timer = metrics::simulation("gas");
+        self.simulate_gas(owner, swap, unwraps, wrapped)
+            .await
+            .inspect_err(|err| metrics::simulation_failed("gas", err.format_variant()))
+    }
 
+    async fn simulate_gas(
+        &self,
+        owner: Address,
+        swap: &dex::Swap,
+        unwraps: &dex::Unwraps,
+        wrapped: eth::WrappedNativeToken,
+    ) -> Result<Option<eth::Gas>, Error> {
+        let swapper = Swapper::Instance::new(owner, self.web3.clone());
         let swapper_calls_arg = swap
-            .calls
-            .iter()
-            .map(|call| Interaction {
-                target: call.to,
-                value: U256::ZERO,
-                callData: alloy::primitives::Bytes::copy_from_slice(&call.calldata),
+            .interactions(unwraps, wrapped)
+            .into_iter()
+            .map(|interaction| Interaction {
+                target: interaction.target,
+                value: interaction.value.0,
+                callData: interaction.calldata.into(),
             })
             .collect();
         let sell = Asset {
             token: swap.input.token.0,
             amount: swap.input.amount,
         };
+        // The trader receives the native token if the swap output gets
+        // unwrapped.
         let buy = Asset {
-            token: swap.output.token.0,
+            token: match unwraps.output {
+                Some(_) => eth::NATIVE_TOKEN.0,
+                None => swap.output.token.0,
+            },
             amount: swap.output.amount,
         };
         let allowance = Allowance {
             spender: swap.allowance.spender,
             amount: swap.allowance.amount.get(),
         };
-        let gas = swapper
-            .swap(self.settlement, sell, buy, allowance, swapper_calls_arg)
-            .call()
-            .overrides(overrides)
-            .await?;
+        let call = swapper.swap(self.settlement, sell, buy, allowance, swapper_calls_arg);
+
+        let gas = match &self.batcher {
+            Some(batcher) => batcher.simulate(owner, call.calldata().clone()).await?,
+            None => {
+                let overrides = StateOverridesBuilder::with_capacity(2)
+                    // Setup up our trader code that actually executes the settlement
+                    .append(owner, swapper_override())
+                    // Override the CoW protocol solver authenticator with one that
+                    // allows any address to solve
+                    .append(self.authenticator, authenticator_override());
+                call.call().overrides(overrides).await?
+            }
+        };
 
         // `gas == 0` means that the simulation is not possible. See
-        // `Swapper.sol` contract for more details. In this case, use the
-        // heuristic gas amount from the swap.
+        // `Swapper.sol` contract for more details. In this case, the caller
+        // uses the heuristic gas amount from the swap.
         Ok(if gas.is_zero() {
             tracing::info!(
                 gas = ?swap.gas,
                 "could not simulate dex swap to get gas used; fall back to gas estimate provided \
                  by dex API"
             );
-            swap.gas
+            None
         } else {
-            eth::Gas(gas)
+            Some(eth::Gas(gas))
         })
     }
+
+    /// Simulate the complete settlement of a solution, including its clearing
+    /// prices, trades, allowances and all non-internalized interactions.
+    ///
+    /// Returns the gas used by the settlement or an error if it would revert.
+    pub async fn settle(&self, solution: &solution::Solution) -> Result<eth::Gas, Error> {
+        let _timer = metrics::simulation("settlement");
+        self.simulate_settlement(solution)
+            .await
+            .inspect_err(|err| metrics::simulation_failed("settlement", err.format_variant()))
+    }
+
+    async fn simulate_settlement(&self, solution: &solution::Solution) -> Result<eth::Gas, Error> {
+        let settlement = Settlement::encode(solution)?;
+
+        let overrides = StateOverridesBuilder::with_capacity(1)
+            .append(self.authenticator, authenticator_override());
+
+        let gas = GPv2Settlement::Instance::new(self.settlement, self.web3.clone())
+            .settle(
+                settlement.tokens,
+                settlement.clearing_prices,
+                settlement.trades,
+                settlement.interactions,
+            )
+            .state(overrides)
+            .estimate_gas()
+            .await?;
+
+        Ok(eth::Gas(U256::from(gas)))
+    }
+}
+
+/// Account override that replaces the code of an order owner with the
+/// `Swapper` contract.
+fn swapper_override() -> AccountOverride {
+    AccountOverride {
+        code: Some(Swapper::Swapper::DEPLOYED_BYTECODE.clone()),
+        ..Default::default()
+    }
+}
+
+/// Account override that replaces the CoW protocol solver authenticator with
+/// one that allows any address to solve.
+fn authenticator_override() -> AccountOverride {
+    AccountOverride {
+        code: Some(AnyoneAuthenticator::AnyoneAuthenticator::DEPLOYED_BYTECODE.clone()),
+        ..Default::default()
+    }
+}
+
+/// Collects gas simulations over a short window and sends their `eth_call`s
+/// together in a single JSON-RPC batch request.
+#[derive(Debug, Clone)]
+struct Batcher(mpsc::UnboundedSender<Request>);
+
+struct Request {
+    owner: Address,
+    calldata: Bytes,
+    result: oneshot::Sender<Result<U256, Error>>,
+}
+
+impl Batcher {
+    fn spawn(web3: DynProvider, authenticator: Address, batching: Batching) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        // Batches are simulated in their own tasks, so they need to record
+        // their metrics for the solver that spawned them.
+        tokio::spawn(metrics::Instance::current().scope(Self::run(
+            web3,
+            authenticator,
+            batching,
+            receiver,
+        )));
+        Self(sender)
+    }
+
+    /// Queues the `Swapper.swap` call for the next batch and waits for its
+    /// result.
+    async fn simulate(&self, owner: Address, calldata: Bytes) -> Result<U256, Error> {
+        let (result, receiver) = oneshot::channel();
+        self.0
+            .send(Request {
+                owner,
+                calldata,
+                result,
+            })
+            .map_err(|_| Error::BatcherStopped)?;
+        receiver.await.map_err(|_| Error::BatcherStopped)?
+    }
+
+    async fn run(
+        web3: DynProvider,
+        authenticator: Address,
+        batching: Batching,
+        mut requests: mpsc::UnboundedReceiver<Request>,
+    ) {
+        while let Some(request) = requests.recv().await {
+            let mut batch = vec![request];
+            let deadline = tokio::time::Instant::now() + batching.window;
+            while batch.len() < batching.max_size.get() {
+                match tokio::time::timeout_at(deadline, requests.recv()).await {
+                    Ok(Some(request)) => batch.push(request),
+                    Ok(None) | Err(_) => break,
+                }
+            }
+            tokio::spawn(metrics::Instance::current().scope(Self::execute(
+                web3.clone(),
+                authenticator,
+                batch,
+            )));
+        }
+    }
+
+    /// Every simulation is a separate `eth_call` with its own state
+    /// overrides, exactly like an unbatched simulation, so that simulations
+    /// can't affect each other's balances, allowances or gas.
+    async fn execute(web3: DynProvider, authenticator: Address, requests: Vec<Request>) {
+        let mut batch = web3.client().new_batch();
+        let mut calls = Vec::with_capacity(requests.len());
+        for request in requests {
+            let call = TransactionRequest::default()
+                .to(request.owner)
+                .input(request.calldata.clone().into());
+            let overrides = StateOverridesBuilder::with_capacity(2)
+                .append(request.owner, swapper_override())
+                .append(authenticator, authenticator_override())
+                .build();
+            match batch.add_call::<_, Bytes>("eth_call", &(call, BlockId::latest(), overrides)) {
+                Ok(waiter) => calls.push((request, waiter)),
+                Err(err) => {
+                    let _ = request.result.send(Err(Error::Node(err)));
+                }
+            }
+        }
+
+        if let Err(err) = batch.send().await {
+            let err = Arc::new(err);
+            for (request, _) in calls {
+                let _ = request.result.send(Err(Error::Batch(err.clone())));
+            }
+            return;
+        }
+        for (request, waiter) in calls {
+            let result = match waiter.await {
+                Ok(output) => {
+                    Swapper::Swapper::swapCall::abi_decode_returns(&output).map_err(Error::Decoding)
+                }
+                Err(err) => Err(Error::Node(err)),
+            };
+            let _ = request.result.send(result);
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -114,4 +324,34 @@ pub enum Error {
 
     #[error("can't simulate gas for an order for which the settlement contract is the owner")]
     SettlementContractIsOwner,
+
+    #[error("unable to encode settlement: {0}")]
+    Encoding(#[from] settlement::Error),
+
+    #[error("batched simulation error: {0}")]
+    Batch(Arc<TransportError>),
+
+    #[error("unable to decode simulation result: {0}")]
+    Decoding(alloy::sol_types::Error),
+
+    #[error("simulation batcher stopped")]
+    BatcherStopped,
+
+    #[error("node error: {0}")]
+    Node(#[from] alloy::transports::TransportError),
+}
+
+impl Error {
+    /// for instrumentization purposes
+    pub fn format_variant(&self) -> &'static str {
+        match self {
+            Self::ContractCall(_) => "ContractCall",
+            Self::SettlementContractIsOwner => "SettlementContractIsOwner",
+            Self::Encoding(_) => "Encoding",
+            Self::Batch(_) => "Batch",
+            Self::Decoding(_) => "Decoding",
+            Self::BatcherStopped => "BatcherStopped",
+            Self::Node(_) => "Node",
+        }
+    }
 }
@@ -129,6 +129,11 @@ pub struct QuoteTransaction {
     #[serde_as(as = "serialize::Hex")]
     pub data: Vec<u8>,
 
+    /// The amount of native token that needs to be sent with the swap.
+    #[serde(default)]
+    #[serde_as(as = "serialize::U256")]
+    pub value: U256,
+
     /// The estimate for the amount of gas that will actually be used in the
     /// transaction.
     #[serde_as(as = "Option<serialize::U256>")]
@@ -1,12 +1,16 @@
 use {
     crate::{
         domain::{dex, eth},
+        infra::metrics,
         util,
     },
     alloy::primitives::{Address, address},
     ethrpc::block_stream::CurrentBlockWatcher,
     hyper::StatusCode,
-    std::sync::atomic::{self, AtomicU64},
+    std::sync::{
+        RwLock,
+        atomic::{self, AtomicU64},
+    },
     tracing::Instrument,
 };
 
@@ -16,7 +20,7 @@ mod dto;
 pub struct ZeroEx {
     client: super::Client,
     endpoint: reqwest::Url,
-    defaults: dto::Query,
+    defaults: RwLock<dto::Query>,
 }
 
 /// https://0x.org/docs/introduction/0x-cheat-sheet#0x-contracts
@@ -71,16 +75,26 @@ impl ZeroEx {
         Ok(Self {
             client,
             endpoint: config.endpoint,
-            defaults,
+            defaults: RwLock::new(defaults),
         })
     }
 
+    /// Replaces the list of excluded liquidity sources for subsequent swaps.
+    pub fn set_excluded_sources(&self, excluded_sources: Vec<String>) {
+        self.defaults.write().unwrap().excluded_sources = excluded_sources;
+    }
+
     pub async fn swap(
         &self,
         order: &dex::Order,
         slippage: &dex::Slippage,
     ) -> Result<dex::Swap, Error> {
-        let query = self.defaults.clone().try_with_domain(order, slippage)?;
+        let query = self
+            .defaults
+            .read()
+            .unwrap()
+            .clone()
+            .try_with_domain(order, slippage)?;
         let quote = {
             // Set up a tracing span to make debugging of API requests easier.
             // Historically, debugging API requests to external DEXs was a bit
@@ -96,6 +110,7 @@ impl ZeroEx {
             calls: vec![dex::Call {
                 to: quote.transaction.to,
                 calldata: quote.transaction.data,
+                value: eth::Ether(quote.transaction.value),
             }],
             input: eth::Asset {
                 token: order.sell,
@@ -117,7 +132,15 @@ impl ZeroEx {
         })
     }
 
+    /// Checks that the API is reachable and accepts the API key.
+    pub async fn probe(&self) -> Result<(), util::http::Error> {
+        self.client
+            .probe(util::url::join(&self.endpoint, "quote"))
+            .await
+    }
+
     async fn quote(&self, query: &dto::Query) -> Result<dto::ValidQuote, Error> {
+        let _timer = metrics::dex_request("zeroex", "quote");
         let quote = Into::<Option<dto::ValidQuote>>::into(
             util::http::roundtrip!(
                 <dto::Quote, dto::Error>;
@@ -0,0 +1,152 @@
+//! Recorded HTTP traffic of the solver engine, used for replaying auctions
+//! deterministically.
+//!
+//! A fixture contains the requests made to the DEX API and the node while
+//! solving a single auction, together with the responses that were received.
+//! Fixtures are stored as one JSON file per auction, named after the auction
+//! ID, with an additional `init.json` fixture for the requests made while the
+//! solver starts up.
+
+use {
+    crate::infra::{cli, config, dex},
+    anyhow::{Context, Result},
+    serde::{Deserialize, Serialize},
+    std::path::{Path, PathBuf},
+};
+
+pub mod recorder;
+pub mod server;
+
+pub use self::{
+    recorder::Recorder,
+    server::{Exchanges, Server},
+};
+
+/// The name of the fixture with the requests made on startup.
+pub const INIT: &str = "init";
+
+/// Recorded HTTP traffic of a single auction.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fixture {
+    /// Requests to the DEX API.
+    #[serde(default)]
+    pub dex: Vec<Exchange>,
+    /// JSON-RPC requests to the node.
+    #[serde(default)]
+    pub node: Vec<Exchange>,
+}
+
+/// A request and the response that was received for it.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Exchange {
+    pub method: Method,
+    /// The request path relative to the API root, including the query
+    /// string and without a leading `/`.
+    pub path: String,
+    /// The JSON request body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<serde_json::Value>,
+    /// The HTTP status of the response.
+    #[serde(default = "default_status")]
+    pub status: u16,
+    /// The JSON response body.
+    pub response: serde_json::Value,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Method {
+    Get,
+    Post,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+impl Fixture {
+    /// Returns the path of the fixture with the specified name.
+    pub fn path(dir: &Path, name: &str) -> PathBuf {
+        dir.join(format!("{name}.json"))
+    }
+
+    /// Loads the fixture with the specified name. Returns an empty fixture if
+    /// no such fixture was recorded.
+    pub async fn load(dir: &Path, name: &str) -> Result<Self, anyhow::Error> {
+        let path = Self::path(dir, name);
+        let data = match tokio::fs::read(&path).await {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(serde_json::from_slice(&data)?)
+    }
+}
+
+/// The DEX API endpoint and node URL of a solver.
+#[derive(Clone, Debug)]
+pub struct Endpoints {
+    pub dex: reqwest::Url,
+    pub node: reqwest::Url,
+}
+
+/// Creates an in-memory copy of the solver configuration with its DEX API and
+/// node requests sent to the specified servers instead. Returns the path to
+/// load the copy from and the original endpoints. The copy can be dropped
+/// with [`config::forget`] once it has been loaded.
+///
+/// The DEX API server receives requests with the same path as the original
+/// endpoint, so that recorded paths don't depend on where they were recorded.
+pub async fn redirect(
+    solver: &cli::Solver,
+    servers: &Endpoints,
+    poll_blocks: bool,
+) -> Result<(PathBuf, Endpoints)> {
+    let path = solver.config();
+    let data = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("failed to read {}", path.display()))?;
+    let mut config: toml::Table = toml::from_str(&data).context("invalid configuration")?;
+    anyhow::ensure!(
+        !config.contains_key("chains"),
+        "recording and replaying only support single-chain configurations"
+    );
+
+    let node = config
+        .get("node-url")
+        .and_then(toml::Value::as_str)
+        .context("missing node URL")?
+        .parse()
+        .context("invalid node URL")?;
+    config.insert("node-url".to_owned(), servers.node.to_string().into());
+    if !poll_blocks {
+        config.remove("current-block-poll-interval");
+    }
+
+    let section = config
+        .get_mut("dex")
+        .and_then(toml::Value::as_table_mut)
+        .context("missing [dex] configuration")?;
+    let dex: reqwest::Url = match section.get("endpoint").and_then(toml::Value::as_str) {
+        Some(endpoint) => endpoint.parse().context("invalid DEX endpoint")?,
+        None => default_endpoint(solver).context("missing DEX endpoint")?,
+    };
+    let mut redirected = servers.dex.clone();
+    redirected.set_path(dex.path());
+    section.insert("endpoint".to_owned(), redirected.to_string().into());
+
+    // The configuration contains the API keys, so it is kept in memory.
+    let path = config::in_memory("redirected configuration", toml::to_string(&config)?);
+    Ok((path, Endpoints { dex, node }))
+}
+
+fn default_endpoint(solver: &cli::Solver) -> Option<reqwest::Url> {
+    match solver {
+        cli::Solver::ZeroEx { .. } => Some(config::dex::zeroex::file::default_endpoint()),
+        cli::Solver::OneInch { .. } => dex::oneinch::DEFAULT_URL.parse().ok(),
+        cli::Solver::ParaSwap { .. } => dex::paraswap::DEFAULT_URL.parse().ok(),
+        cli::Solver::Balancer { .. } => None,
+    }
+}
@@ -0,0 +1,379 @@
+//! Recording the DEX API and node traffic of a running solver engine.
+
+use {
+    super::{Endpoints, Exchange, Fixture, Method},
+    crate::infra::{cli, config},
+    anyhow::{Context, Result},
+    axum::response::IntoResponse,
+    std::{
+        collections::HashMap,
+        future::Future,
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+    },
+    tokio::{io::AsyncWriteExt, net::TcpListener, task::JoinHandle},
+};
+
+/// The name of the file that recorded auctions are appended to, in the format
+/// expected by `solvers replay --auctions`.
+pub const AUCTIONS: &str = "auctions.jsonl";
+
+/// Query parameters that are stripped from recorded request paths. Names are
+/// compared case-insensitively and ignoring `-` and `_`.
+const SECRET_PARAMETERS: &[&str] = &["apikey", "key", "token", "accesstoken", "secret"];
+
+/// The header that DEX API requests carry the ID of the recording of the
+/// auction they are made for in. The proxy removes it before forwarding.
+pub const HEADER: &str = "x-solvers-recording";
+
+tokio::task_local! {
+    static RECORDING: u64;
+}
+
+/// Returns the ID of the recording of the current task, if any.
+pub fn current() -> Option<u64> {
+    RECORDING.try_with(|id| *id).ok()
+}
+
+/// Records DEX API and node traffic into fixtures.
+///
+/// The solver gets configured to send its requests to local proxies, which
+/// forward them to the original endpoints. DEX API requests made while solving
+/// an auction carry the ID of its recording in the [`HEADER`] header and get
+/// recorded into the fixture of that auction only. Node requests can't carry
+/// the header, so they get recorded into the fixtures of all auctions that are
+/// being solved at the time. Request headers are never recorded, so API keys
+/// sent in headers don't end up in fixtures.
+pub struct Recorder {
+    dir: PathBuf,
+    /// The solver command, configured to send its requests through the
+    /// proxies.
+    pub solver: cli::Solver,
+    recordings: Arc<Mutex<Recordings>>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+#[derive(Default)]
+struct Recordings {
+    next: u64,
+    active: HashMap<u64, Fixture>,
+}
+
+/// Whether a proxy forwards requests to the DEX API or the node.
+#[derive(Clone, Copy)]
+enum Target {
+    Dex,
+    Node,
+}
+
+#[derive(Clone)]
+struct Proxy {
+    target: Target,
+    /// The URL to forward requests to. DEX API requests are forwarded with
+    /// their path, node requests always go to this exact URL.
+    upstream: reqwest::Url,
+    client: reqwest::Client,
+    recordings: Arc<Mutex<Recordings>>,
+}
+
+impl Recorder {
+    /// Starts recording the traffic of the specified solver into fixtures in
+    /// the specified directory.
+    pub async fn start(dir: &Path, solver: &cli::Solver) -> Result<Self> {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .with_context(|| format!("failed to create {}", dir.display()))?;
+
+        let recordings = Arc::new(Mutex::new(Recordings::default()));
+        let dex = TcpListener::bind("127.0.0.1:0").await?;
+        let node = TcpListener::bind("127.0.0.1:0").await?;
+        let servers = Endpoints {
+            dex: format!("http://{}/", dex.local_addr()?).parse()?,
+            node: format!("http://{}/", node.local_addr()?).parse()?,
+        };
+        let (config, upstream) = super::redirect(solver, &servers, true).await?;
+
+        let proxy = |target, upstream| Proxy {
+            target,
+            upstream,
+            client: reqwest::Client::new(),
+            recordings: recordings.clone(),
+        };
+        let mut dex_upstream = upstream.dex;
+        dex_upstream.set_path("");
+        let handles = vec![
+            serve(dex, proxy(Target::Dex, dex_upstream)),
+            serve(node, proxy(Target::Node, upstream.node)),
+        ];
+
+        Ok(Self {
+            dir: dir.to_owned(),
+            solver: solver.with_config(config),
+            recordings,
+            handles,
+        })
+    }
+
+    /// Records the traffic of the solver initialization into the `init`
+    /// fixture. The redirected configuration is no longer needed afterwards.
+    pub async fn init<T>(&self, init: impl Future<Output = T>) -> T {
+        let recording = self.record();
+        let result = recording.scope(init).await;
+        recording.finish(super::INIT, None).await;
+        config::forget(self.solver.config());
+        result
+    }
+
+    /// Starts recording the traffic for an auction. Only requests made within
+    /// [`Recording::scope`] are attributed to the recording, except for node
+    /// requests, which recordings of concurrently solved auctions all include.
+    pub fn record(&self) -> Recording {
+        let mut recordings = self.recordings.lock().unwrap();
+        let id = recordings.next;
+        recordings.next += 1;
+        recordings.active.insert(id, Fixture::default());
+        Recording {
+            id,
+            dir: self.dir.clone(),
+            recordings: self.recordings.clone(),
+        }
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        for handle in &self.handles {
+            handle.abort();
+        }
+    }
+}
+
+/// The traffic recorded for a single auction.
+pub struct Recording {
+    id: u64,
+    dir: PathBuf,
+    recordings: Arc<Mutex<Recordings>>,
+}
+
+impl Recording {
+    /// Attributes the DEX API requests of a future to this recording.
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        RECORDING.scope(self.id, future).await
+    }
+
+    /// Stops recording and writes the fixture with the specified name. If the
+    /// raw auction is specified, it gets appended to the recorded auctions.
+    pub async fn finish(self, name: &str, auction: Option<&[u8]>) {
+        let Some(fixture) = self.recordings.lock().unwrap().active.remove(&self.id) else {
+            return;
+        };
+        if let Err(err) = self.write(name, &fixture, auction).await {
+            tracing::warn!(?err, %name, "failed to write fixture");
+        }
+    }
+
+    /// Stops recording and returns the recorded traffic instead of writing
+    /// it to a fixture.
+    pub fn take(self) -> Fixture {
+        self.recordings
+            .lock()
+            .unwrap()
+            .active
+            .remove(&self.id)
+            .unwrap_or_default()
+    }
+
+    async fn write(&self, name: &str, fixture: &Fixture, auction: Option<&[u8]>) -> Result<()> {
+        let path = Fixture::path(&self.dir, name);
+        tokio::fs::write(&path, serde_json::to_vec_pretty(fixture)?).await?;
+        if let Some(auction) = auction {
+            // Re-encode the auction to make sure it fits on a single line.
+            let mut line =
+                serde_json::to_vec(&serde_json::from_slice::<serde_json::Value>(auction)?)?;
+            line.push(b'\n');
+            tokio::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(self.dir.join(AUCTIONS))
+                .await?
+                .write_all(&line)
+                .await?;
+        }
+        tracing::debug!(path = %path.display(), "recorded fixture");
+        Ok(())
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        self.recordings.lock().unwrap().active.remove(&self.id);
+    }
+}
+
+fn serve(listener: TcpListener, proxy: Proxy) -> JoinHandle<()> {
+    let app = axum::Router::new().fallback(forward).with_state(proxy);
+    tokio::spawn(async move {
+        if let Err(err) = axum::serve(listener, app).await {
+            tracing::error!(?err, "recording proxy stopped");
+        }
+    })
+}
+
+async fn forward(
+    axum::extract::State(proxy): axum::extract::State<Proxy>,
+    method: axum::http::Method,
+    uri: axum::http::Uri,
+    headers: axum::http::HeaderMap,
+    body: axum::body::Bytes,
+) -> axum::response::Response {
+    let path = uri
+        .path_and_query()
+        .map(|path| path.as_str().trim_start_matches('/'))
+        .unwrap_or_default();
+    let url = match proxy.target {
+        Target::Dex => match proxy.upstream.join(path) {
+            Ok(url) => url,
+            Err(_) => return axum::http::StatusCode::BAD_REQUEST.into_response(),
+        },
+        Target::Node => proxy.upstream.clone(),
+    };
+
+    let recording = headers
+        .get(HEADER)
+        .and_then(|id| id.to_str().ok()?.parse().ok());
+    let mut request = proxy.client.request(method.clone(), url);
+    for (name, value) in &headers {
+        if ![
+            axum::http::header::HOST,
+            axum::http::header::CONTENT_LENGTH,
+            axum::http::header::CONNECTION,
+        ]
+        .contains(name)
+            && *name != HEADER
+        {
+            request = request.header(name, value);
+        }
+    }
+    let response = match request.body(body.clone()).send().await {
+        Ok(response) => response,
+        Err(err) => {
+            tracing::warn!(?err, "failed to forward recorded request");
+            return axum::http::StatusCode::BAD_GATEWAY.into_response();
+        }
+    };
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get(axum::http::header::CONTENT_TYPE)
+        .cloned();
+    let response = match response.bytes().await {
+        Ok(response) => response,
+        Err(err) => {
+            tracing::warn!(?err, "failed to read recorded response");
+            return axum::http::StatusCode::BAD_GATEWAY.into_response();
+        }
+    };
+
+    proxy.record(recording, &method, path, &body, status, &response);
+
+    let mut forwarded = (status, response).into_response();
+    if let Some(content_type) = content_type {
+        forwarded
+            .headers_mut()
+            .insert(axum::http::header::CONTENT_TYPE, content_type);
+    }
+    forwarded
+}
+
+impl Proxy {
+    /// Adds the exchange to the specified recording, or to all active
+    /// recordings for requests that aren't attributed to one. Only exchanges
+    /// with JSON bodies are recorded, since fixtures can't represent anything
+    /// else.
+    fn record(
+        &self,
+        recording: Option<u64>,
+        method: &axum::http::Method,
+        path: &str,
+        request: &[u8],
+        status: reqwest::StatusCode,
+        response: &[u8],
+    ) {
+        let method = match *method {
+            axum::http::Method::GET => Method::Get,
+            axum::http::Method::POST => Method::Post,
+            _ => return,
+        };
+        let request = if request.is_empty() {
+            None
+        } else {
+            match serde_json::from_slice(request) {
+                Ok(request) => Some(request),
+                Err(_) => return,
+            }
+        };
+        let Ok(response) = serde_json::from_slice(response) else {
+            return;
+        };
+        let exchange = Exchange {
+            method,
+            path: match self.target {
+                Target::Dex => without_secrets(path),
+                // Node URLs are redirected as a whole, so paths carry no
+                // information.
+                Target::Node => String::new(),
+            },
+            request,
+            status: status.as_u16(),
+            response,
+        };
+
+        let mut recordings = self.recordings.lock().unwrap();
+        let fixtures: Vec<&mut Fixture> = match recording {
+            Some(id) => recordings.active.get_mut(&id).into_iter().collect(),
+            None => recordings.active.values_mut().collect(),
+        };
+        for fixture in fixtures {
+            match self.target {
+                Target::Dex => fixture.dex.push(exchange.clone()),
+                Target::Node => fixture.node.push(exchange.clone()),
+            }
+        }
+    }
+}
+
+/// Removes query parameters that look like credentials from a request path.
+fn without_secrets(path: &str) -> String {
+    let Some((path, query)) = path.split_once('?') else {
+        return path.to_owned();
+    };
+    let query = query
+        .split('&')
+        .filter(|parameter| {
+            let name = parameter.split('=').next().unwrap_or_default();
+            let name = name.replace(['-', '_'], "").to_lowercase();
+            !SECRET_PARAMETERS.contains(&name.as_str())
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    if query.is_empty() {
+        path.to_owned()
+    } else {
+        format!("{path}?{query}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_secrets_from_paths() {
+        assert_eq!(
+            without_secrets("swap/quote?sellToken=0x01&apiKey=secret&amount=1"),
+            "swap/quote?sellToken=0x01&amount=1",
+        );
+        assert_eq!(without_secrets("swap/quote?api_key=secret"), "swap/quote");
+        assert_eq!(without_secrets("swap/quote"), "swap/quote");
+    }
+}
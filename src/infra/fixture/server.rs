@@ -0,0 +1,287 @@
+//! An HTTP server that answers requests from recorded fixtures.
+
+use {
+    super::{Exchange, Method},
+    axum::response::IntoResponse,
+    std::{
+        collections::HashMap,
+        io,
+        sync::{Arc, Mutex},
+    },
+    tokio::{net::TcpListener, task::JoinHandle},
+};
+
+/// A local HTTP server answering requests with recorded responses.
+///
+/// Requests are matched against the recorded exchanges by method, path and
+/// JSON body, so the order in which concurrent requests arrive doesn't matter.
+/// JSON-RPC request IDs are ignored for matching and the IDs in the responses
+/// are replaced with the ones of the actual request.
+pub struct Server {
+    /// The URL of the server.
+    pub url: reqwest::Url,
+    state: Arc<Mutex<Exchanges>>,
+    handle: JoinHandle<()>,
+}
+
+/// Statistics about how well the served exchanges matched the requests.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Stats {
+    /// The number of requests for which no exchange was recorded.
+    pub missing: usize,
+    /// The number of recorded exchanges that were never requested.
+    pub unused: usize,
+}
+
+/// Recorded exchanges that requests are answered from.
+#[derive(Default)]
+pub struct Exchanges {
+    /// Whether to ignore the request path when matching requests. This is
+    /// used for node requests, where the recorded path is specific to the
+    /// node provider.
+    ignore_path: bool,
+    exchanges: Vec<Exchange>,
+    used: Vec<bool>,
+    missing: usize,
+}
+
+impl Server {
+    /// Starts a new fixture server on a random local port.
+    pub async fn start(ignore_path: bool) -> Result<Self, io::Error> {
+        let state = Arc::new(Mutex::new(Exchanges::new(Vec::new(), ignore_path)));
+        let app = axum::Router::new()
+            .fallback(handle)
+            .with_state(state.clone());
+
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/", listener.local_addr()?)
+            .parse()
+            .unwrap();
+        let handle = tokio::spawn(async move {
+            if let Err(err) = axum::serve(listener, app).await {
+                tracing::error!(?err, "fixture server stopped");
+            }
+        });
+
+        Ok(Self { url, state, handle })
+    }
+
+    /// Replaces the exchanges that requests are answered from, returning the
+    /// statistics of the previously served exchanges.
+    pub fn serve(&self, exchanges: Vec<Exchange>) -> Stats {
+        let mut state = self.state.lock().unwrap();
+        let stats = state.stats();
+        *state = Exchanges::new(exchanges, state.ignore_path);
+        stats
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+impl Exchanges {
+    pub fn new(exchanges: Vec<Exchange>, ignore_path: bool) -> Self {
+        Self {
+            ignore_path,
+            used: vec![false; exchanges.len()],
+            exchanges,
+            missing: 0,
+        }
+    }
+
+    fn stats(&self) -> Stats {
+        Stats {
+            missing: self.missing,
+            unused: self.used.iter().filter(|used| !**used).count(),
+        }
+    }
+
+    /// Finds the response for a request. Every exchange is used at most once,
+    /// unless the same request is made more often than it was recorded, in
+    /// which case the last matching exchange is reused.
+    pub fn respond(
+        &mut self,
+        method: Method,
+        path: &str,
+        request: Option<&serde_json::Value>,
+    ) -> Option<(u16, serde_json::Value)> {
+        let normalized = request.map(without_ids);
+        let matching = self
+            .exchanges
+            .iter()
+            .enumerate()
+            .filter(|(_, exchange)| {
+                exchange.method == method
+                    && (self.ignore_path || exchange.path == path)
+                    && exchange.request.as_ref().map(without_ids) == normalized
+            })
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        let i = matching
+            .iter()
+            .copied()
+            .find(|i| !self.used[*i])
+            .or(matching.last().copied())?;
+        self.used[i] = true;
+
+        let exchange = &self.exchanges[i];
+        let mut response = exchange.response.clone();
+        if let (Some(recorded), Some(actual)) = (&exchange.request, request) {
+            replace_ids(&mut response, &request_ids(recorded, actual));
+        }
+        Some((exchange.status, response))
+    }
+}
+
+async fn handle(
+    axum::extract::State(state): axum::extract::State<Arc<Mutex<Exchanges>>>,
+    method: axum::http::Method,
+    uri: axum::http::Uri,
+    body: axum::body::Bytes,
+) -> axum::response::Response {
+    let method = match method {
+        axum::http::Method::GET => Method::Get,
+        axum::http::Method::POST => Method::Post,
+        _ => return axum::http::StatusCode::METHOD_NOT_ALLOWED.into_response(),
+    };
+    let path = uri
+        .path_and_query()
+        .map(|path| path.as_str().trim_start_matches('/'))
+        .unwrap_or_default();
+    let request = if body.is_empty() {
+        None
+    } else {
+        match serde_json::from_slice(&body) {
+            Ok(request) => Some(request),
+            Err(_) => return axum::http::StatusCode::BAD_REQUEST.into_response(),
+        }
+    };
+
+    let mut state = state.lock().unwrap();
+    match state.respond(method, path, request.as_ref()) {
+        Some((status, response)) => (
+            axum::http::StatusCode::from_u16(status)
+                .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR),
+            axum::response::Json(response),
+        )
+            .into_response(),
+        None => {
+            tracing::warn!(?method, %path, ?request, "no recorded response for request");
+            state.missing += 1;
+            (
+                axum::http::StatusCode::NOT_FOUND,
+                axum::response::Json(serde_json::json!({
+                    "error": "no recorded response for request",
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Returns a JSON-RPC request (or batch of requests) without the request IDs.
+fn without_ids(request: &serde_json::Value) -> serde_json::Value {
+    match request {
+        serde_json::Value::Object(object) => {
+            let mut object = object.clone();
+            object.remove("id");
+            serde_json::Value::Object(object)
+        }
+        serde_json::Value::Array(requests) => requests.iter().map(without_ids).collect(),
+        other => other.clone(),
+    }
+}
+
+/// Maps the JSON-RPC request IDs of a recorded request to the IDs of the
+/// equivalent actual request.
+fn request_ids(
+    recorded: &serde_json::Value,
+    actual: &serde_json::Value,
+) -> HashMap<String, serde_json::Value> {
+    match (recorded, actual) {
+        (serde_json::Value::Object(recorded), serde_json::Value::Object(actual)) => {
+            match (recorded.get("id"), actual.get("id")) {
+                (Some(recorded), Some(actual)) => {
+                    HashMap::from([(recorded.to_string(), actual.clone())])
+                }
+                _ => HashMap::new(),
+            }
+        }
+        (serde_json::Value::Array(recorded), serde_json::Value::Array(actual)) => recorded
+            .iter()
+            .zip(actual)
+            .flat_map(|(recorded, actual)| request_ids(recorded, actual))
+            .collect(),
+        _ => HashMap::new(),
+    }
+}
+
+/// Replaces the IDs of a JSON-RPC response (or batch of responses).
+fn replace_ids(response: &mut serde_json::Value, ids: &HashMap<String, serde_json::Value>) {
+    match response {
+        serde_json::Value::Object(object) => {
+            if let Some(id) = object.get_mut("id")
+                && let Some(actual) = ids.get(&id.to_string())
+            {
+                *id = actual.clone();
+            }
+        }
+        serde_json::Value::Array(responses) => {
+            for response in responses {
+                replace_ids(response, ids);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn matches_requests_ignoring_json_rpc_ids() {
+        let call = |id| json!({"id": id, "jsonrpc": "2.0", "method": "eth_call", "params": []});
+        let mut state = Exchanges::new(
+            vec![
+                Exchange {
+                    method: Method::Post,
+                    path: "v3/key".to_owned(),
+                    request: Some(json!([call(1), call(2)])),
+                    status: 200,
+                    response: json!([{"id": 2, "result": "0x02"}, {"id": 1, "result": "0x01"}]),
+                },
+                Exchange {
+                    method: Method::Post,
+                    path: "v3/key".to_owned(),
+                    request: Some(call(3)),
+                    status: 200,
+                    response: json!({"id": 3, "result": "0x03"}),
+                },
+            ],
+            true,
+        );
+
+        assert_eq!(
+            state.respond(Method::Post, "", Some(&call(7))),
+            Some((200, json!({"id": 7, "result": "0x03"}))),
+        );
+        assert_eq!(
+            state.respond(Method::Post, "", Some(&json!([call(8), call(9)]))),
+            Some((
+                200,
+                json!([{"id": 9, "result": "0x02"}, {"id": 8, "result": "0x01"}])
+            )),
+        );
+        // Repeated requests reuse the last matching response.
+        assert_eq!(
+            state.respond(Method::Post, "", Some(&call(10))),
+            Some((200, json!({"id": 10, "result": "0x03"}))),
+        );
+        assert_eq!(state.respond(Method::Get, "", None), None);
+        assert_eq!(state.stats(), Stats::default());
+    }
+}
@@ -1,51 +1,437 @@
-use crate::domain::{auction, solution};
+use {
+    crate::{
+        domain::{auction, dex::slippage, eth, notification, order, solution},
+        util::conv::u256_to_bigdecimal,
+    },
+    bigdecimal::{BigDecimal, ToPrimitive},
+};
 
 /// Metrics for the solver engine.
 #[derive(Debug, Clone, prometheus_metric_storage::MetricStorage)]
 #[metric(subsystem = "solver_engine")]
 struct Metrics {
     /// The amount of time this solver engine has for solving.
-    #[metric(buckets(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15))]
-    time_limit: prometheus::Histogram,
+    #[metric(
+        labels("solver", "chain"),
+        buckets(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15)
+    )]
+    time_limit: prometheus::HistogramVec,
 
     /// The amount of time this solver engine has left when it finished solving.
-    #[metric(buckets(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15))]
-    remaining_time: prometheus::Histogram,
+    #[metric(
+        labels("solver", "chain"),
+        buckets(0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15)
+    )]
+    remaining_time: prometheus::HistogramVec,
 
     /// Total number of requests that got sent to the DEX API.
-    solve_requests: prometheus::IntCounter,
+    #[metric(labels("solver", "chain"))]
+    solve_requests: prometheus::IntCounterVec,
 
     /// Errors that occurred during solving.
-    #[metric(labels("reason"))]
+    #[metric(labels("solver", "chain", "reason"))]
     solve_errors: prometheus::IntCounterVec,
 
     /// The number of solutions that were found.
-    solutions: prometheus::IntCounter,
+    #[metric(labels("solver", "chain"))]
+    solutions: prometheus::IntCounterVec,
+
+    /// The relative slippage in basis points applied to swaps when the
+    /// dynamic slippage model is configured, after the absolute slippage cap.
+    #[metric(
+        labels("solver", "chain", "class"),
+        buckets(1, 5, 10, 25, 50, 100, 200, 300, 500, 1000)
+    )]
+    slippage_bps: prometheus::HistogramVec,
+
+    /// The time it took to receive a response from the DEX API.
+    #[metric(
+        labels("solver", "chain", "dex", "endpoint"),
+        buckets(0.05, 0.1, 0.25, 0.5, 1, 2, 3, 5, 10)
+    )]
+    dex_request_seconds: prometheus::HistogramVec,
+
+    /// The outcome of solving an order.
+    #[metric(labels("solver", "chain", "dex", "side", "class", "outcome"))]
+    order_outcomes: prometheus::IntCounterVec,
+
+    /// The time it took to simulate a swap or settlement.
+    #[metric(
+        labels("solver", "chain", "kind"),
+        buckets(0.01, 0.05, 0.1, 0.25, 0.5, 1, 2, 5)
+    )]
+    simulation_seconds: prometheus::HistogramVec,
+
+    /// Swap and settlement simulations that failed.
+    #[metric(labels("solver", "chain", "kind", "reason"))]
+    simulation_failures: prometheus::IntCounterVec,
+
+    /// The surplus of the user in ETH.
+    #[metric(
+        labels("solver", "chain", "dex", "class"),
+        buckets(0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1)
+    )]
+    solution_surplus_eth: prometheus::HistogramVec,
+
+    /// The shortfall of solutions not meeting the limit price of the user at
+    /// the clearing prices in ETH.
+    #[metric(
+        labels("solver", "chain", "dex", "class"),
+        buckets(0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1)
+    )]
+    solution_negative_surplus_eth: prometheus::HistogramVec,
+
+    /// The surplus fee charged for limit orders in ETH.
+    #[metric(
+        labels("solver", "chain", "dex", "class"),
+        buckets(0.0001, 0.0005, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1)
+    )]
+    solution_surplus_fee_eth: prometheus::HistogramVec,
+
+    /// The gas estimate of a solution.
+    #[metric(
+        labels("solver", "chain", "dex", "class"),
+        buckets(50000, 100000, 150000, 200000, 300000, 500000, 750000, 1000000)
+    )]
+    solution_gas: prometheus::HistogramVec,
+
+    /// The ratio of the heuristic gas estimate (the DEX API estimate plus the
+    /// gas offset) to the simulated gas of a solution.
+    #[metric(
+        labels("solver", "chain", "dex", "class"),
+        buckets(0.25, 0.5, 0.75, 0.9, 1, 1.1, 1.25, 1.5, 2, 4)
+    )]
+    solution_gas_ratio: prometheus::HistogramVec,
+
+    /// Notifications about submitted solutions, counted once per traded order.
+    #[metric(labels("solver", "chain", "dex", "kind"))]
+    notifications: prometheus::IntCounterVec,
+
+    /// Token pairs that got marked as suspicious after repeated reverts.
+    #[metric(labels("solver", "chain", "dex"))]
+    suspicious_pairs: prometheus::IntCounterVec,
+
+    /// Requests to the solver API that got rejected before being handled.
+    #[metric(labels("solver", "chain", "reason"))]
+    api_rejections: prometheus::IntCounterVec,
+}
+
+/// The labels of the solver instance that metrics get recorded for. Metrics
+/// recorded outside of [`Instance::scope`] have empty instance labels.
+#[derive(Clone, Debug, Default)]
+pub struct Instance {
+    /// The name of the solver instance, empty for unnamed solvers.
+    pub solver: String,
+    /// The chain ID of the solver.
+    pub chain: String,
+}
+
+tokio::task_local! {
+    static INSTANCE: Instance;
+}
+
+impl Instance {
+    pub fn new(name: Option<&str>, chain_id: eth::ChainId) -> Self {
+        Self {
+            solver: name.unwrap_or_default().to_owned(),
+            chain: chain_id.value().to_string(),
+        }
+    }
+
+    /// Returns the instance of the current task.
+    pub fn current() -> Self {
+        INSTANCE.try_with(Clone::clone).unwrap_or_default()
+    }
+
+    /// Records the metrics of a future for this instance.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        INSTANCE.scope(self, future).await
+    }
+
+    /// Records the metrics of a function, including the tasks it spawns that
+    /// propagate the [`Instance::current`] instance, for this instance.
+    pub fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        INSTANCE.sync_scope(self.clone(), f)
+    }
+}
+
+/// The outcome of solving an order.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// A solution was found.
+    Solved,
+    /// The DEX API didn't find a swap.
+    NotFound,
+    /// The swap doesn't satisfy the order's limit price.
+    OutOfPrice,
+    /// The swap doesn't provide the minimum surplus.
+    MinSurplusFailed,
+    /// The surplus of the swap doesn't cover the network fee of the solution,
+    /// or no solution could be built from the swap.
+    FeeExceedsSurplus,
+    /// The swap or the settlement failed to simulate.
+    SimulationFailed,
+    /// The DEX API rate limited the request.
+    RateLimited,
+    /// The order wasn't solved before the deadline.
+    Deadline,
+    /// The DEX API doesn't support the order.
+    Unsupported,
+    /// The DEX API failed with an unexpected error.
+    Error,
+    /// The order was backed off after reverting.
+    BackedOff,
+}
+
+impl Outcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Solved => "solved",
+            Self::NotFound => "not_found",
+            Self::OutOfPrice => "out_of_price",
+            Self::MinSurplusFailed => "min_surplus_failed",
+            Self::FeeExceedsSurplus => "fee_exceeds_surplus",
+            Self::SimulationFailed => "simulation_failed",
+            Self::RateLimited => "rate_limited",
+            Self::Deadline => "deadline",
+            Self::Unsupported => "unsupported",
+            Self::Error => "error",
+            Self::BackedOff => "backed_off",
+        }
+    }
+}
+
+/// Records the outcome of solving an order. An order that gets dropped
+/// without an outcome ran out of time, so its outcome is
+/// [`Outcome::Deadline`].
+pub struct OrderOutcome {
+    instance: Instance,
+    labels: [&'static str; 3],
+    done: bool,
+}
+
+impl OrderOutcome {
+    pub fn new(dex: &'static str, order: &order::Order) -> Self {
+        let side = match order.side {
+            order::Side::Buy => "buy",
+            order::Side::Sell => "sell",
+        };
+        Self {
+            instance: Instance::current(),
+            labels: [dex, side, class(order.class)],
+            done: false,
+        }
+    }
+
+    pub fn record(&mut self, outcome: Outcome) {
+        let [dex, side, class] = self.labels;
+        get()
+            .order_outcomes
+            .with_label_values(&[
+                &self.instance.solver,
+                &self.instance.chain,
+                dex,
+                side,
+                class,
+                outcome.as_str(),
+            ])
+            .inc();
+        self.done = true;
+    }
+
+    /// Marks an order that was skipped without trying to solve it.
+    pub fn skip(&mut self) {
+        self.done = true;
+    }
+}
+
+impl Drop for OrderOutcome {
+    fn drop(&mut self) {
+        if !self.done {
+            self.record(Outcome::Deadline);
+        }
+    }
 }
 
 pub fn solve(auction: &auction::Auction) {
-    get().time_limit.observe(
-        auction
-            .deadline
-            .remaining()
-            .unwrap_or_default()
-            .as_secs_f64(),
-    );
+    let instance = Instance::current();
+    get()
+        .time_limit
+        .with_label_values(&[&instance.solver, &instance.chain])
+        .observe(
+            auction
+                .deadline
+                .remaining()
+                .unwrap_or_default()
+                .as_secs_f64(),
+        );
 }
 
 pub fn solved(deadline: &auction::Deadline, solutions: &[solution::Solution]) {
+    let instance = Instance::current();
+    let labels = [instance.solver.as_str(), instance.chain.as_str()];
     get()
         .remaining_time
+        .with_label_values(&labels)
         .observe(deadline.remaining().unwrap_or_default().as_secs_f64());
-    get().solutions.inc_by(solutions.len() as u64);
+    get()
+        .solutions
+        .with_label_values(&labels)
+        .inc_by(solutions.len() as u64);
+}
+
+pub fn notification(dex: &str, kind: &notification::Kind) {
+    let instance = Instance::current();
+    get()
+        .notifications
+        .with_label_values(&[&instance.solver, &instance.chain, dex, kind.as_str()])
+        .inc();
+}
+
+pub fn suspicious_pair(dex: &str) {
+    let instance = Instance::current();
+    get()
+        .suspicious_pairs
+        .with_label_values(&[&instance.solver, &instance.chain, dex])
+        .inc();
 }
 
 pub fn solve_error(reason: &str) {
-    get().solve_errors.with_label_values(&[reason]).inc();
+    let instance = Instance::current();
+    get()
+        .solve_errors
+        .with_label_values(&[&instance.solver, &instance.chain, reason])
+        .inc();
+}
+
+/// Records a request to the solver API that got rejected by its
+/// authentication or size limits.
+pub fn api_rejection(reason: &str) {
+    let instance = Instance::current();
+    get()
+        .api_rejections
+        .with_label_values(&[&instance.solver, &instance.chain, reason])
+        .inc();
 }
 
 pub fn request_sent() {
-    get().solve_requests.inc();
+    let instance = Instance::current();
+    get()
+        .solve_requests
+        .with_label_values(&[&instance.solver, &instance.chain])
+        .inc();
+}
+
+pub fn slippage(class: slippage::TokenClass, relative: &BigDecimal) {
+    let instance = Instance::current();
+    get()
+        .slippage_bps
+        .with_label_values(&[&instance.solver, &instance.chain, class.as_str()])
+        .observe(
+            (relative * BigDecimal::from(10_000))
+                .to_f64()
+                .unwrap_or_default(),
+        );
+}
+
+/// Records the economics of a solution for a single order. The gas ratio is
+/// only recorded if a heuristic gas is given, that is if the gas of the
+/// solution was simulated.
+pub fn solution(
+    dex: &str,
+    order_class: order::Class,
+    surplus: Option<Surplus>,
+    surplus_fee: Option<eth::Ether>,
+    heuristic_gas: Option<eth::Gas>,
+    gas: Option<eth::Gas>,
+) {
+    let instance = Instance::current();
+    let labels = [
+        instance.solver.as_str(),
+        instance.chain.as_str(),
+        dex,
+        class(order_class),
+    ];
+    match surplus {
+        Some(Surplus::Positive(surplus)) => get()
+            .solution_surplus_eth
+            .with_label_values(&labels)
+            .observe(ether(surplus)),
+        Some(Surplus::Negative(shortfall)) => get()
+            .solution_negative_surplus_eth
+            .with_label_values(&labels)
+            .observe(ether(shortfall)),
+        None => (),
+    }
+    if let Some(surplus_fee) = surplus_fee {
+        get()
+            .solution_surplus_fee_eth
+            .with_label_values(&labels)
+            .observe(ether(surplus_fee));
+    }
+    let Some(gas) = gas.and_then(|gas| u64::try_from(gas.0).ok()) else {
+        return;
+    };
+    get()
+        .solution_gas
+        .with_label_values(&labels)
+        .observe(gas as f64);
+    if gas > 0
+        && let Some(heuristic_gas) = heuristic_gas
+        && let Ok(heuristic_gas) = u64::try_from(heuristic_gas.0)
+    {
+        get()
+            .solution_gas_ratio
+            .with_label_values(&labels)
+            .observe(heuristic_gas as f64 / gas as f64);
+    }
+}
+
+/// The surplus of a solution in ETH.
+pub enum Surplus {
+    Positive(eth::Ether),
+    Negative(eth::Ether),
+}
+
+fn class(class: order::Class) -> &'static str {
+    match class {
+        order::Class::Market => "market",
+        order::Class::Limit => "limit",
+    }
+}
+
+fn ether(value: eth::Ether) -> f64 {
+    (u256_to_bigdecimal(&value.0) / BigDecimal::from(10_u64.pow(18)))
+        .to_f64()
+        .unwrap_or_default()
+}
+
+/// Starts timing a request to the DEX API. The time is recorded when the
+/// returned timer is dropped.
+pub fn dex_request(dex: &str, endpoint: &str) -> prometheus::HistogramTimer {
+    let instance = Instance::current();
+    get()
+        .dex_request_seconds
+        .with_label_values(&[&instance.solver, &instance.chain, dex, endpoint])
+        .start_timer()
+}
+
+/// Starts timing a simulation. The time is recorded when the returned timer is
+/// dropped.
+pub fn simulation(kind: &str) -> prometheus::HistogramTimer {
+    let instance = Instance::current();
+    get()
+        .simulation_seconds
+        .with_label_values(&[&instance.solver, &instance.chain, kind])
+        .start_timer()
+}
+
+pub fn simulation_failed(kind: &str, reason: &str) {
+    let instance = Instance::current();
+    get()
+        .simulation_failures
+        .with_label_values(&[&instance.solver, &instance.chain, kind, reason])
+        .inc();
 }
 
 /// Get the metrics instance.
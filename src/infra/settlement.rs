@@ -0,0 +1,208 @@
+//! Encoding of domain solutions into `GPv2Settlement.settle` calls.
+
+use {
+    crate::{
+        domain::{eth, order, solution},
+        util,
+    },
+    alloy::{
+        primitives::{Address, Bytes, FixedBytes, U256},
+        sol_types::SolCall,
+    },
+    contracts::{
+        ERC20,
+        GPv2Settlement::{GPv2Interaction, GPv2Trade},
+    },
+    std::collections::BTreeMap,
+};
+
+/// The arguments of a `GPv2Settlement.settle` call.
+pub struct Settlement {
+    pub tokens: Vec<Address>,
+    pub clearing_prices: Vec<U256>,
+    pub trades: Vec<GPv2Trade::Data>,
+    pub interactions: [Vec<GPv2Interaction::Data>; 3],
+}
+
+impl Settlement {
+    /// Encodes a solution the same way the driver would when submitting it
+    /// on-chain. That is, every trade gets its own custom clearing prices
+    /// (which account for the solver fee) and allowances get approved right
+    /// before the interaction that requires them.
+    pub fn encode(solution: &solution::Solution) -> Result<Self, Error> {
+        // Use a sorted map so that the encoding is deterministic.
+        let prices = solution
+            .prices
+            .0
+            .iter()
+            .map(|(token, price)| (*token, *price))
+            .collect::<BTreeMap<_, _>>();
+        let mut tokens = prices.keys().map(|token| token.0).collect::<Vec<_>>();
+        let mut clearing_prices = prices.values().copied().collect::<Vec<_>>();
+
+        let mut pre_interactions = Vec::new();
+        let mut post_interactions = Vec::new();
+        let mut trades = Vec::with_capacity(solution.trades.len());
+        for trade in &solution.trades {
+            let solution::Trade::Fulfillment(fulfillment) = trade;
+            let order = fulfillment.order();
+            let price = |token: eth::TokenAddress| {
+                prices
+                    .get(&token)
+                    .copied()
+                    .ok_or(Error::MissingClearingPrice(token))
+            };
+            let (sell_price, buy_price) = (price(order.sell.token)?, price(order.buy.token)?);
+
+            let executed = fulfillment.executed().amount;
+            let fee = fulfillment
+                .surplus_fee()
+                .map(|fee| fee.amount)
+                .unwrap_or_default();
+            let (custom_sell_price, custom_buy_price, executed_amount) = match order.side {
+                order::Side::Sell => (
+                    util::math::div_ceil(
+                        executed.checked_mul(sell_price).ok_or(Error::Math)?,
+                        buy_price,
+                    )
+                    .ok_or(Error::Math)?,
+                    executed.checked_add(fee).ok_or(Error::Math)?,
+                    executed.checked_add(fee).ok_or(Error::Math)?,
+                ),
+                order::Side::Buy => (
+                    executed,
+                    executed
+                        .checked_mul(buy_price)
+                        .ok_or(Error::Math)?
+                        .checked_div(sell_price)
+                        .ok_or(Error::Math)?
+                        .checked_add(fee)
+                        .ok_or(Error::Math)?,
+                    executed,
+                ),
+            };
+
+            let sell_token_index = U256::from(tokens.len());
+            tokens.push(order.sell.token.0);
+            clearing_prices.push(custom_sell_price);
+            let buy_token_index = U256::from(tokens.len());
+            tokens.push(order.buy.token.0);
+            clearing_prices.push(custom_buy_price);
+
+            trades.push(GPv2Trade::Data {
+                sellTokenIndex: sell_token_index,
+                buyTokenIndex: buy_token_index,
+                receiver: order.signed.receiver.unwrap_or_default(),
+                sellAmount: order.signed.sell_amount,
+                buyAmount: order.signed.buy_amount,
+                validTo: order.signed.valid_to,
+                appData: FixedBytes(order.signed.app_data),
+                feeAmount: U256::ZERO,
+                flags: flags(order),
+                executedAmount: executed_amount,
+                signature: signature(order),
+            });
+            pre_interactions.extend(order.signed.pre_interactions.iter().map(interaction));
+            post_interactions.extend(order.signed.post_interactions.iter().map(interaction));
+        }
+
+        pre_interactions.extend(solution.pre_interactions.iter().map(interaction));
+        let interactions = solution
+            .interactions
+            .iter()
+            .flat_map(|interaction| match interaction {
+                solution::Interaction::Custom(interaction) if interaction.internalize => vec![],
+                solution::Interaction::Custom(interaction) => interaction
+                    .allowances
+                    .iter()
+                    .flat_map(approve)
+                    .chain([GPv2Interaction::Data {
+                        target: interaction.target,
+                        value: interaction.value.0,
+                        callData: Bytes::copy_from_slice(&interaction.calldata),
+                    }])
+                    .collect(),
+            })
+            .collect();
+        post_interactions.extend(solution.post_interactions.iter().map(interaction));
+
+        Ok(Self {
+            tokens,
+            clearing_prices,
+            trades,
+            interactions: [pre_interactions, interactions, post_interactions],
+        })
+    }
+}
+
+fn interaction(interaction: &eth::Interaction) -> GPv2Interaction::Data {
+    GPv2Interaction::Data {
+        target: interaction.target,
+        value: interaction.value.0,
+        callData: Bytes::copy_from_slice(&interaction.calldata),
+    }
+}
+
+/// Encodes the approval interactions for an allowance. The allowance gets
+/// reset first since some tokens (notably USDT) revert when changing a
+/// non-zero allowance to another non-zero value.
+fn approve(allowance: &solution::Allowance) -> [GPv2Interaction::Data; 2] {
+    let call = |amount| GPv2Interaction::Data {
+        target: allowance.asset.token.0,
+        value: U256::ZERO,
+        callData: ERC20::ERC20::approveCall {
+            spender: allowance.spender,
+            amount,
+        }
+        .abi_encode()
+        .into(),
+    };
+    [call(U256::ZERO), call(allowance.asset.amount)]
+}
+
+/// Encodes the trade flags bitfield as specified by the `GPv2Trade` library.
+fn flags(order: &order::Order) -> U256 {
+    let kind = match order.side {
+        order::Side::Sell => 0b0,
+        order::Side::Buy => 0b1,
+    };
+    let partially_fillable = if order.partially_fillable { 0b10 } else { 0b0 };
+    let sell_token_balance = match order.signed.sell_token_balance {
+        order::SellTokenBalance::Erc20 => 0b0000,
+        order::SellTokenBalance::External => 0b1000,
+        order::SellTokenBalance::Internal => 0b1100,
+    };
+    let buy_token_balance = match order.signed.buy_token_balance {
+        order::BuyTokenBalance::Erc20 => 0b00000,
+        order::BuyTokenBalance::Internal => 0b10000,
+    };
+    let signing_scheme = match order.signed.signing_scheme {
+        order::SigningScheme::Eip712 => 0b0000000,
+        order::SigningScheme::EthSign => 0b0100000,
+        order::SigningScheme::Eip1271 => 0b1000000,
+        order::SigningScheme::PreSign => 0b1100000,
+    };
+    U256::from(kind | partially_fillable | sell_token_balance | buy_token_balance | signing_scheme)
+}
+
+/// Encodes the signature in the format expected by the settlement contract.
+/// Smart contract signatures and pre-signatures are prefixed by the owner.
+fn signature(order: &order::Order) -> Bytes {
+    match order.signed.signing_scheme {
+        order::SigningScheme::Eip712 | order::SigningScheme::EthSign => {
+            order.signed.signature.clone().into()
+        }
+        order::SigningScheme::Eip1271 => [order.owner().as_slice(), &order.signed.signature]
+            .concat()
+            .into(),
+        order::SigningScheme::PreSign => Bytes::copy_from_slice(order.owner().as_slice()),
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("missing clearing price for token {0:?}")]
+    MissingClearingPrice(eth::TokenAddress),
+    #[error("arithmetic overflow computing custom clearing prices")]
+    Math,
+}
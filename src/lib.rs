@@ -1,7 +1,11 @@
 mod api;
 mod domain;
 mod infra;
+mod mock_dex;
+mod quote;
+mod replay;
 mod run;
+mod solve;
 #[cfg(test)]
 mod tests;
 mod util;
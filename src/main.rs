@@ -2,6 +2,6 @@
 static GLOBAL: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
 
 #[tokio::main]
-async fn main() {
-    solvers::start(std::env::args()).await;
+async fn main() -> std::process::ExitCode {
+    solvers::start(std::env::args()).await
 }
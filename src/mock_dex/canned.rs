@@ -0,0 +1,168 @@
+//! Canned DEX API responses.
+//!
+//! Every swap is quoted at a 1:1 exchange rate in token atoms, executed by a
+//! dummy router contract. Balancer SOR requests specify amounts in whole
+//! tokens, which are assumed to have 18 decimals.
+
+use {
+    crate::infra::cli,
+    bigdecimal::BigDecimal,
+    serde_json::json,
+    std::{collections::HashMap, str::FromStr},
+};
+
+/// The address of the dummy router that executes the mocked swaps.
+const ROUTER: &str = "0xdeaddeaddeaddeaddeaddeaddeaddeaddeaddead";
+
+/// The gas estimate of the mocked swaps.
+const GAS: u64 = 100_000;
+
+/// Returns the canned response for a request to the DEX API, or `None` if the
+/// request is not a known API request.
+pub fn respond(
+    dex: cli::MockedDex,
+    path: &str,
+    request: Option<&serde_json::Value>,
+) -> Option<serde_json::Value> {
+    let (endpoint, query) = path.split_once('?').unwrap_or((path, ""));
+    let endpoint = endpoint.trim_end_matches('/');
+    let query = reqwest::Url::parse(&format!("http://mock/?{query}"))
+        .ok()?
+        .query_pairs()
+        .into_owned()
+        .collect::<HashMap<_, _>>();
+
+    match dex {
+        cli::MockedDex::ZeroEx if endpoint.ends_with("quote") => zeroex(&query),
+        cli::MockedDex::OneInch if endpoint.ends_with("approve/spender") => {
+            Some(json!({ "address": ROUTER }))
+        }
+        cli::MockedDex::OneInch if endpoint.ends_with("liquidity-sources") => {
+            Some(json!({ "protocols": [{ "id": "MOCK" }] }))
+        }
+        cli::MockedDex::OneInch if endpoint.ends_with("swap") => oneinch(&query),
+        cli::MockedDex::ParaSwap if endpoint.ends_with("swap") => paraswap(&query),
+        cli::MockedDex::Balancer => balancer(request?),
+        _ => None,
+    }
+}
+
+fn zeroex(query: &HashMap<String, String>) -> Option<serde_json::Value> {
+    let amount = query.get("sellAmount")?;
+    Some(json!({
+        "liquidityAvailable": true,
+        "sellAmount": amount,
+        "buyAmount": amount,
+        "transaction": {
+            "to": ROUTER,
+            "data": "0x",
+            "value": "0",
+            "gas": GAS.to_string(),
+        },
+        "issues": {
+            "allowance": {
+                "spender": ROUTER,
+                "actual": "0",
+            },
+        },
+    }))
+}
+
+fn oneinch(query: &HashMap<String, String>) -> Option<serde_json::Value> {
+    let amount = query.get("amount")?;
+    Some(json!({
+        "fromTokenAmount": amount,
+        "toTokenAmount": amount,
+        "tx": {
+            "to": ROUTER,
+            "data": "0x",
+            "value": "0",
+            "gas": GAS,
+        },
+    }))
+}
+
+fn paraswap(query: &HashMap<String, String>) -> Option<serde_json::Value> {
+    let amount = query.get("amount")?;
+    Some(json!({
+        "priceRoute": {
+            "srcAmount": amount,
+            "destAmount": amount,
+            "gasCost": GAS.to_string(),
+            "tokenTransferProxy": ROUTER,
+        },
+        "txParams": {
+            "to": ROUTER,
+            "data": "0x",
+            "value": "0",
+        },
+    }))
+}
+
+fn balancer(request: &serde_json::Value) -> Option<serde_json::Value> {
+    let variables = request.get("variables")?;
+    let token_in = variables.get("tokenIn")?.as_str()?;
+    let token_out = variables.get("tokenOut")?.as_str()?;
+    let amount = BigDecimal::from_str(variables.get("swapAmount")?.as_str()?).ok()?
+        * BigDecimal::from(10_u64.pow(18));
+    let amount = amount.with_scale(0).to_string();
+    Some(json!({
+        "data": {
+            "sorGetSwapPaths": {
+                "tokenAddresses": [token_in, token_out],
+                "swaps": [{
+                    "poolId": format!("{ROUTER}{}", "0".repeat(24)),
+                    "assetInIndex": 0,
+                    "assetOutIndex": 1,
+                    "amount": amount,
+                    "userData": "0x",
+                }],
+                "paths": [],
+                "swapAmountRaw": amount,
+                "returnAmountRaw": amount,
+                "tokenIn": token_in,
+                "tokenOut": token_out,
+                "protocolVersion": 2,
+            },
+        },
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_at_one_to_one() {
+        let quote = respond(
+            cli::MockedDex::ZeroEx,
+            "swap/allowance-holder/quote?chainId=1&sellAmount=1000&slippageBps=100",
+            None,
+        )
+        .unwrap();
+        assert_eq!(quote["sellAmount"], "1000");
+        assert_eq!(quote["buyAmount"], "1000");
+
+        let quote = respond(
+            cli::MockedDex::Balancer,
+            "",
+            Some(&json!({
+                "query": "",
+                "variables": {
+                    "chain": "MAINNET",
+                    "swapAmount": "1.5",
+                    "swapType": "EXACT_IN",
+                    "tokenIn": "0x1111111111111111111111111111111111111111",
+                    "tokenOut": "0x2222222222222222222222222222222222222222",
+                },
+            })),
+        )
+        .unwrap();
+        assert_eq!(
+            quote["data"]["sorGetSwapPaths"]["returnAmountRaw"],
+            "1500000000000000000"
+        );
+
+        assert_eq!(respond(cli::MockedDex::ParaSwap, "prices", None), None);
+    }
+}
@@ -0,0 +1,146 @@
+//! A mock DEX API for running the solver engine locally, without API keys.
+//!
+//! Requests are answered from recorded fixtures if available and with canned
+//! responses otherwise. Latency, rate limiting and errors can be injected to
+//! see how the solver engine copes with a misbehaving API.
+
+use {
+    crate::infra::{
+        cli,
+        fixture::{self, Exchanges, Fixture},
+    },
+    anyhow::{Context, Result},
+    axum::response::IntoResponse,
+    std::{
+        num::NonZeroUsize,
+        path::Path,
+        sync::{
+            Arc,
+            Mutex,
+            atomic::{self, AtomicUsize},
+        },
+        time::Duration,
+    },
+    tokio::net::TcpListener,
+};
+
+mod canned;
+
+struct Mock {
+    dex: cli::MockedDex,
+    fixtures: Option<Mutex<Exchanges>>,
+    latency: Duration,
+    rate_limit_every: Option<NonZeroUsize>,
+    error_every: Option<NonZeroUsize>,
+    requests: AtomicUsize,
+}
+
+/// Serves the mock DEX API until the process is stopped.
+pub async fn run(args: cli::MockDex) {
+    if let Err(err) = serve(args).await {
+        tracing::error!(?err, "mock DEX API stopped");
+    }
+}
+
+async fn serve(args: cli::MockDex) -> Result<()> {
+    let fixtures = match &args.fixtures {
+        Some(dir) => Some(Mutex::new(Exchanges::new(load(dir).await?, false))),
+        None => None,
+    };
+    let mock = Arc::new(Mock {
+        dex: args.dex,
+        fixtures,
+        latency: args.latency,
+        rate_limit_every: args.rate_limit_every,
+        error_every: args.error_every,
+        requests: AtomicUsize::new(0),
+    });
+    let app = axum::Router::new().fallback(handle).with_state(mock);
+
+    let listener = TcpListener::bind(args.addr).await?;
+    tracing::info!(dex = ?args.dex, addr = %listener.local_addr()?, "serving mock DEX API");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+/// Loads the DEX API exchanges of all fixtures in a directory.
+async fn load(dir: &Path) -> Result<Vec<fixture::Exchange>> {
+    let mut exchanges = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir)
+        .await
+        .with_context(|| format!("failed to read {}", dir.display()))?;
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if let (Some(name), Some("json")) = (
+            path.file_stem().and_then(|name| name.to_str()),
+            path.extension().and_then(|extension| extension.to_str()),
+        ) {
+            let fixture = Fixture::load(dir, name)
+                .await
+                .with_context(|| format!("invalid fixture {}", path.display()))?;
+            exchanges.extend(fixture.dex);
+        }
+    }
+    Ok(exchanges)
+}
+
+async fn handle(
+    axum::extract::State(mock): axum::extract::State<Arc<Mock>>,
+    method: axum::http::Method,
+    uri: axum::http::Uri,
+    body: axum::body::Bytes,
+) -> axum::response::Response {
+    let n = mock.requests.fetch_add(1, atomic::Ordering::Relaxed) + 1;
+    tokio::time::sleep(mock.latency).await;
+
+    let every = |interval: Option<NonZeroUsize>| interval.is_some_and(|i| n % i.get() == 0);
+    if every(mock.rate_limit_every) {
+        return axum::http::StatusCode::TOO_MANY_REQUESTS.into_response();
+    }
+    if every(mock.error_every) {
+        return axum::http::StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let path = uri
+        .path_and_query()
+        .map(|path| path.as_str().trim_start_matches('/'))
+        .unwrap_or_default();
+    let request = if body.is_empty() {
+        None
+    } else {
+        match serde_json::from_slice::<serde_json::Value>(&body) {
+            Ok(request) => Some(request),
+            Err(_) => return axum::http::StatusCode::BAD_REQUEST.into_response(),
+        }
+    };
+
+    if let Some(fixtures) = &mock.fixtures {
+        let recorded = match method {
+            axum::http::Method::GET => Some(fixture::Method::Get),
+            axum::http::Method::POST => Some(fixture::Method::Post),
+            _ => None,
+        }
+        .and_then(|method| {
+            fixtures
+                .lock()
+                .unwrap()
+                .respond(method, path, request.as_ref())
+        });
+        if let Some((status, response)) = recorded {
+            return (
+                axum::http::StatusCode::from_u16(status)
+                    .unwrap_or(axum::http::StatusCode::INTERNAL_SERVER_ERROR),
+                axum::response::Json(response),
+            )
+                .into_response();
+        }
+    }
+
+    match canned::respond(mock.dex, path, request.as_ref()) {
+        Some(response) => axum::response::Json(response).into_response(),
+        None => {
+            tracing::debug!(%method, %path, "unknown mock DEX API request");
+            axum::http::StatusCode::NOT_FOUND.into_response()
+        }
+    }
+}
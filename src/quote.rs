@@ -0,0 +1,295 @@
+//! Quoting a single swap from the command line.
+//!
+//! This shows every step between the DEX API and a solution for a single
+//! pair: the raw DEX API response, the swap it gets parsed into, the simulated
+//! gas of the swap and the checks an order with a limit price would run. It
+//! helps telling apart whether the DEX API, the mapping of its responses or
+//! the limit checks are the reason for a pair not getting solved.
+//!
+//! The DEX API traffic is captured by the proxies of a [`fixture::Recorder`],
+//! whose redirected solver configuration, including the API keys, only exists
+//! in memory and is dropped once the solver is created. No fixture gets
+//! written since the recording is taken instead of finished.
+
+use {
+    crate::{
+        domain::{auction, eth, order, quote, solver::dex::Probe},
+        infra::{
+            cli,
+            config,
+            fixture::{self, Exchange},
+        },
+    },
+    alloy::sol_types::SolInterface,
+    anyhow::{Context, Result},
+    std::{
+        collections::HashMap,
+        fmt::{self, Display, Formatter},
+    },
+};
+
+/// Quotes the swap and prints the report. Returns whether a swap was found.
+pub async fn run(args: cli::Quote) -> bool {
+    match quote(&args).await {
+        Ok(report) => {
+            print!("{report}");
+            report.probe.swap.is_ok()
+        }
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            false
+        }
+    }
+}
+
+/// Everything that happened while quoting a swap.
+pub struct Report {
+    /// The DEX API requests and their raw responses.
+    pub exchanges: Vec<Exchange>,
+    pub probe: Probe,
+}
+
+pub async fn quote(args: &cli::Quote) -> Result<Report> {
+    let recorder = fixture::Recorder::start(&std::env::temp_dir(), &args.solver).await?;
+    let solver = crate::run::create_solvers(&recorder.solver, None, &Default::default())
+        .await
+        .pop()
+        .context("no solver configured")?;
+    config::forget(recorder.solver.config());
+
+    let token = |decimals| auction::Token {
+        decimals,
+        reference_price: None,
+        available_balance: eth::U256::ZERO,
+        trusted: false,
+    };
+    let request = quote::Request {
+        sell: eth::TokenAddress(args.sell_token),
+        buy: eth::TokenAddress(args.buy_token),
+        side: match args.side {
+            cli::Side::Sell => order::Side::Sell,
+            cli::Side::Buy => order::Side::Buy,
+        },
+        amount: args.amount,
+        owner: args.owner,
+        tokens: auction::Tokens(HashMap::from([
+            (
+                eth::TokenAddress(args.sell_token),
+                token(args.sell_token_decimals),
+            ),
+            (
+                eth::TokenAddress(args.buy_token),
+                token(args.buy_token_decimals),
+            ),
+        ])),
+        gas_price: auction::GasPrice(eth::Ether(eth::U256::ZERO)),
+    };
+
+    let recording = recorder.record();
+    let probe = recording.scope(solver.probe(request, args.limit)).await;
+    Ok(Report {
+        exchanges: recording.take().dex,
+        probe,
+    })
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Probe {
+            order,
+            slippage,
+            swap,
+            gas,
+            limit,
+        } = &self.probe;
+        let (side, counter) = match order.side {
+            order::Side::Sell => ("sell", order.buy),
+            order::Side::Buy => ("buy", order.sell),
+        };
+        let amount = order.amount();
+        writeln!(
+            f,
+            "order: {side} {} of {} for {}",
+            amount.amount, amount.token.0, counter.0,
+        )?;
+        write!(f, "slippage: {}", slippage.as_factor())?;
+        if let Some(bps) = slippage.as_bps() {
+            write!(f, " ({bps} bps)")?;
+        }
+        writeln!(f)?;
+
+        for exchange in &self.exchanges {
+            let method = match exchange.method {
+                fixture::Method::Get => "GET",
+                fixture::Method::Post => "POST",
+            };
+            writeln!(f, "\n{method} {} -> {}", exchange.path, exchange.status)?;
+            if let Some(request) = &exchange.request {
+                writeln!(f, "{}", pretty(request))?;
+            }
+            writeln!(f, "{}", pretty(&exchange.response))?;
+        }
+
+        let swap = match swap {
+            Ok(swap) => swap,
+            Err(err) => return writeln!(f, "\nno swap: {err}"),
+        };
+        writeln!(f, "\nswap: {swap:#?}")?;
+        writeln!(f, "\ncalls:")?;
+        for call in &swap.calls {
+            writeln!(f, "  to {} with a value of {}:", call.to, call.value.0)?;
+            for line in decode(&call.calldata).lines() {
+                writeln!(f, "    {line}")?;
+            }
+        }
+
+        match gas {
+            Some(Ok(Some(gas))) => writeln!(
+                f,
+                "\nsimulated gas: {} (the DEX API estimated {})",
+                gas.0, swap.gas.0
+            )?,
+            Some(Ok(None)) => writeln!(
+                f,
+                "\nthe swap can't be simulated, the DEX API estimated {} gas",
+                swap.gas.0
+            )?,
+            Some(Err(err)) => writeln!(f, "\nsimulation failed: {err}")?,
+            None => (),
+        }
+        if let Some(limit) = limit {
+            let check = |passed| if passed { "passed" } else { "failed" };
+            writeln!(f, "limit price: {}", check(limit.limit_price))?;
+            writeln!(f, "minimum surplus: {}", check(limit.minimum_surplus))?;
+        }
+        Ok(())
+    }
+}
+
+alloy::sol! {
+    #[sol(all_derives)]
+    interface IERC20 {
+        function approve(address spender, uint256 amount) external returns (bool);
+        function transfer(address to, uint256 amount) external returns (bool);
+    }
+
+    /// The contract that 0x swaps are executed through.
+    #[sol(all_derives)]
+    interface IAllowanceHolder {
+        function exec(
+            address operator,
+            address token,
+            uint256 amount,
+            address payable target,
+            bytes calldata data
+        ) external payable returns (bytes memory);
+    }
+
+    #[sol(all_derives)]
+    interface IPermit2 {
+        function approve(
+            address token,
+            address spender,
+            uint160 amount,
+            uint48 expiration
+        ) external;
+    }
+
+    #[sol(all_derives)]
+    interface IBalancerV2Vault {
+        struct BatchSwapStep {
+            bytes32 poolId;
+            uint256 assetInIndex;
+            uint256 assetOutIndex;
+            uint256 amount;
+            bytes userData;
+        }
+
+        struct FundManagement {
+            address sender;
+            bool fromInternalBalance;
+            address payable recipient;
+            bool toInternalBalance;
+        }
+
+        function batchSwap(
+            uint8 kind,
+            BatchSwapStep[] swaps,
+            address[] assets,
+            FundManagement funds,
+            int256[] limits,
+            uint256 deadline
+        ) external payable returns (int256[] memory);
+    }
+
+    #[sol(all_derives)]
+    interface IBalancerV3BatchRouter {
+        struct SwapPathStep {
+            address pool;
+            address tokenOut;
+            bool isBuffer;
+        }
+
+        struct SwapPathExactAmountIn {
+            address tokenIn;
+            SwapPathStep[] steps;
+            uint256 exactAmountIn;
+            uint256 minAmountOut;
+        }
+
+        struct SwapPathExactAmountOut {
+            address tokenIn;
+            SwapPathStep[] steps;
+            uint256 maxAmountIn;
+            uint256 exactAmountOut;
+        }
+
+        function swapExactIn(
+            SwapPathExactAmountIn[] paths,
+            uint256 deadline,
+            bool wethIsEth,
+            bytes userData
+        ) external payable;
+
+        function swapExactOut(
+            SwapPathExactAmountOut[] paths,
+            uint256 deadline,
+            bool wethIsEth,
+            bytes userData
+        ) external payable;
+    }
+}
+
+/// Decodes the calldata of a call to a contract that swaps get executed
+/// through. The calldata of other contracts, like the routers of 1inch and
+/// ParaSwap, is split into its selector and 32 byte words instead.
+fn decode(calldata: &[u8]) -> String {
+    if let Ok(call) = IERC20::IERC20Calls::abi_decode(calldata) {
+        return format!("{call:#?}");
+    }
+    if let Ok(call) = IAllowanceHolder::IAllowanceHolderCalls::abi_decode(calldata) {
+        return format!("{call:#?}");
+    }
+    if let Ok(call) = IPermit2::IPermit2Calls::abi_decode(calldata) {
+        return format!("{call:#?}");
+    }
+    if let Ok(call) = IBalancerV2Vault::IBalancerV2VaultCalls::abi_decode(calldata) {
+        return format!("{call:#?}");
+    }
+    if let Ok(call) = IBalancerV3BatchRouter::IBalancerV3BatchRouterCalls::abi_decode(calldata) {
+        return format!("{call:#?}");
+    }
+
+    let Some((selector, arguments)) = calldata.split_first_chunk::<4>() else {
+        return format!("no selector in 0x{}", hex::encode(calldata));
+    };
+    let mut decoded = format!("unknown function 0x{}", hex::encode(selector));
+    for word in arguments.chunks(32) {
+        decoded.push_str(&format!("\n  0x{}", hex::encode(word)));
+    }
+    decoded
+}
+
+fn pretty(json: &serde_json::Value) -> String {
+    serde_json::to_string_pretty(json).expect("JSON serializes")
+}
@@ -0,0 +1,119 @@
+//! Structural differences between the solutions of two replay runs.
+
+use std::fmt::{self, Display, Formatter};
+
+/// A value that differs between two JSON documents.
+#[derive(Debug, Eq, PartialEq)]
+pub struct Difference {
+    /// The path to the value, e.g. `[0].prices.0xabc`.
+    pub path: String,
+    /// The previous value, or `None` if the value was added.
+    pub old: Option<serde_json::Value>,
+    /// The new value, or `None` if the value was removed.
+    pub new: Option<serde_json::Value>,
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let path = if self.path.is_empty() {
+            "."
+        } else {
+            &self.path
+        };
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, "{path}: {old} -> {new}"),
+            (None, Some(new)) => write!(f, "{path}: added {new}"),
+            (Some(old), None) => write!(f, "{path}: removed {old}"),
+            (None, None) => write!(f, "{path}: unchanged"),
+        }
+    }
+}
+
+/// Returns the differences between two JSON documents, down to the
+/// individual values that changed.
+pub fn json(old: &serde_json::Value, new: &serde_json::Value) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    compare(String::new(), old, new, &mut differences);
+    differences
+}
+
+fn compare(
+    path: String,
+    old: &serde_json::Value,
+    new: &serde_json::Value,
+    differences: &mut Vec<Difference>,
+) {
+    match (old, new) {
+        (serde_json::Value::Object(old), serde_json::Value::Object(new)) => {
+            for (key, old) in old {
+                let path = format!("{path}.{key}");
+                match new.get(key) {
+                    Some(new) => compare(path, old, new, differences),
+                    None => differences.push(Difference {
+                        path,
+                        old: Some(old.clone()),
+                        new: None,
+                    }),
+                }
+            }
+            for (key, new) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+                differences.push(Difference {
+                    path: format!("{path}.{key}"),
+                    old: None,
+                    new: Some(new.clone()),
+                });
+            }
+        }
+        (serde_json::Value::Array(old), serde_json::Value::Array(new)) => {
+            for i in 0..old.len().max(new.len()) {
+                let path = format!("{path}[{i}]");
+                match (old.get(i), new.get(i)) {
+                    (Some(old), Some(new)) => compare(path, old, new, differences),
+                    (old, new) => differences.push(Difference {
+                        path,
+                        old: old.cloned(),
+                        new: new.cloned(),
+                    }),
+                }
+            }
+        }
+        (old, new) if old != new => differences.push(Difference {
+            path,
+            old: Some(old.clone()),
+            new: Some(new.clone()),
+        }),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, serde_json::json};
+
+    #[test]
+    fn finds_changed_values() {
+        let old = json!([
+            {"id": 0, "prices": {"0x01": "1", "0x02": "2"}, "gas": 100},
+            {"id": 1},
+        ]);
+        let new = json!([
+            {"id": 0, "prices": {"0x01": "1", "0x02": "3"}, "score": "4"},
+        ]);
+
+        let mut differences = json(&old, &new)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        differences.sort();
+        assert_eq!(
+            differences,
+            [
+                "[0].gas: removed 100",
+                r#"[0].prices.0x02: "2" -> "3""#,
+                r#"[0].score: added "4""#,
+                r#"[1]: removed {"id":1}"#,
+            ],
+        );
+        assert!(json(&old, &old).is_empty());
+    }
+}
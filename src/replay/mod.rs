@@ -0,0 +1,187 @@
+//! Replaying recorded auctions against recorded DEX API and node responses.
+//!
+//! Replays run the solver engine exactly as it would run in production,
+//! except that its DEX API and node URLs point to local fixture servers. This
+//! makes it possible to check how a change affects the solutions for real
+//! auctions, without depending on the current state of the DEX APIs or the
+//! chain.
+
+use {
+    crate::{
+        api,
+        infra::{
+            cli,
+            config,
+            fixture::{self, Fixture},
+        },
+    },
+    anyhow::{Context, Result},
+    serde::{Deserialize, Serialize},
+    std::collections::HashMap,
+    tokio::fs,
+};
+
+mod diff;
+
+/// The solutions for a single replayed auction.
+#[derive(Debug, Deserialize, Serialize)]
+struct Record {
+    /// The auction ID, or `line-<n>` for auctions without an ID.
+    auction: String,
+    solutions: serde_json::Value,
+}
+
+/// Replays the recorded auctions. Returns whether the solutions are the same
+/// as the ones of the previous run, or `true` if there is no previous run to
+/// compare to.
+pub async fn run(args: cli::Replay) -> bool {
+    match replay(&args).await {
+        Ok(unchanged) => unchanged,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            false
+        }
+    }
+}
+
+async fn replay(args: &cli::Replay) -> Result<bool> {
+    let dex = fixture::Server::start(false).await?;
+    let node = fixture::Server::start(true).await?;
+
+    let init = Fixture::load(&args.fixtures, fixture::INIT).await?;
+    dex.serve(init.dex);
+    node.serve(init.node);
+    let servers = fixture::Endpoints {
+        dex: dex.url.clone(),
+        node: node.url.clone(),
+    };
+    // Polling for new blocks makes requests that don't belong to any auction.
+    let (config, _) = fixture::redirect(&args.solver, &servers, false).await?;
+    let solver = crate::run::create_solvers(
+        &args.solver.with_config(config.clone()),
+        None,
+        &Default::default(),
+    )
+    .await
+    .pop()
+    .context("no solver configured")?;
+    config::forget(&config);
+    report(fixture::INIT, &dex, &node);
+
+    let auctions = fs::read_to_string(&args.auctions)
+        .await
+        .with_context(|| format!("failed to read {}", args.auctions.display()))?;
+    let time_limit = chrono::Duration::from_std(args.time_limit).context("invalid time limit")?;
+    let mut records = Vec::new();
+    for (i, line) in auctions.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        // Orders get validated like when serving the auction.
+        let mut auction = api::dto::auction::from_json(line.as_bytes())
+            .map_err(|err| anyhow::anyhow!("invalid auction on line {}: {err}", i + 1))?;
+        let name = auction
+            .id
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| format!("line-{}", i + 1));
+
+        let fixture = Fixture::load(&args.fixtures, &name)
+            .await
+            .with_context(|| format!("invalid fixture for auction {name}"))?;
+        dex.serve(fixture.dex);
+        node.serve(fixture.node);
+
+        auction.deadline = chrono::Utc::now() + time_limit;
+        let auction = api::dto::auction::to_domain(&auction)
+            .map_err(|err| anyhow::anyhow!("invalid auction {name}: {err}"))?;
+        let solutions = solver.solve(auction).await;
+        let mut solutions = serde_json::to_value(api::dto::solution::from_domain(&solutions))?;
+        let mut solutions = solutions["solutions"].take();
+        if let Some(solutions) = solutions.as_array_mut() {
+            solutions.sort_by_key(|solution| solution["id"].as_u64());
+        }
+        report(&name, &dex, &node);
+
+        records.push(Record {
+            auction: name,
+            solutions,
+        });
+    }
+
+    let output = records
+        .iter()
+        .map(|record| serde_json::to_string(record).map(|line| line + "\n"))
+        .collect::<Result<String, _>>()?;
+    fs::write(&args.output, output)
+        .await
+        .with_context(|| format!("failed to write {}", args.output.display()))?;
+
+    let Some(previous) = &args.previous else {
+        return Ok(true);
+    };
+    let previous = fs::read_to_string(previous)
+        .await
+        .with_context(|| format!("failed to read {}", previous.display()))?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(serde_json::from_str::<Record>)
+        .map(|record| record.map(|record| (record.auction, record.solutions)))
+        .collect::<Result<HashMap<_, _>, _>>()
+        .context("invalid previous solutions")?;
+    Ok(compare(&previous, &records))
+}
+
+/// Logs how well the recorded responses matched the requests of the replayed
+/// auction.
+fn report(name: &str, dex: &fixture::Server, node: &fixture::Server) {
+    let dex = dex.serve(Vec::new());
+    let node = node.serve(Vec::new());
+    if dex.missing > 0 || node.missing > 0 {
+        tracing::warn!(
+            auction = %name,
+            dex = dex.missing,
+            node = node.missing,
+            "requests without recorded responses"
+        );
+    }
+    if dex.unused > 0 || node.unused > 0 {
+        tracing::debug!(
+            auction = %name,
+            dex = dex.unused,
+            node = node.unused,
+            "unused recorded responses"
+        );
+    }
+}
+
+/// Prints the differences to the previous solutions and returns whether the
+/// solutions are unchanged.
+fn compare(previous: &HashMap<String, serde_json::Value>, records: &[Record]) -> bool {
+    let mut changed = 0;
+    for record in records {
+        let Some(old) = previous.get(&record.auction) else {
+            println!("auction {}: not in previous run", record.auction);
+            changed += 1;
+            continue;
+        };
+        let differences = diff::json(old, &record.solutions);
+        if differences.is_empty() {
+            continue;
+        }
+        changed += 1;
+        println!("auction {}:", record.auction);
+        for difference in differences {
+            println!("  {difference}");
+        }
+    }
+    for auction in previous
+        .keys()
+        .filter(|auction| !records.iter().any(|record| &&record.auction == auction))
+    {
+        println!("auction {auction}: not in this run");
+        changed += 1;
+    }
+
+    println!("{changed} of {} auctions changed", records.len());
+    changed == 0
+}
@@ -3,28 +3,46 @@ use tokio::signal::unix::{self, SignalKind};
 use {
     crate::{
         domain::solver::{self, Solver},
-        infra::{blockchain, cli, config, dex},
+        infra::{blockchain, cli, config, dex, fixture},
     },
     clap::Parser,
-    std::net::SocketAddr,
+    std::{net::SocketAddr, process::ExitCode, sync::Arc, time::Duration},
     tokio::sync::oneshot,
 };
 
-pub async fn start(args: impl IntoIterator<Item = String>) {
+/// Runs the solver engine with the command line arguments and returns the
+/// exit status of the process.
+pub async fn start(args: impl IntoIterator<Item = String>) -> ExitCode {
     observe::panic_hook::install();
     let args = cli::Args::parse_from(args);
-    run_with(args, None).await;
+    run_with(args, None).await
 }
 
+/// Runs the solver engine like [`start`], optionally sending the address the
+/// API binds to. Commands that don't serve the API return their exit status
+/// instead of exiting the process.
 pub async fn run(
     args: impl IntoIterator<Item = String>,
     bind: Option<oneshot::Sender<SocketAddr>>,
-) {
+) -> ExitCode {
     let args = cli::Args::parse_from(args);
-    run_with(args, bind).await;
+    run_with(args, bind).await
 }
 
-async fn run_with(args: cli::Args, bind: Option<oneshot::Sender<SocketAddr>>) {
+async fn run_with(args: cli::Args, bind: Option<oneshot::Sender<SocketAddr>>) -> ExitCode {
+    // Checking the configuration happens before setting up logging, so that
+    // the resolved configuration is the only output.
+    if let cli::Command::CheckConfig { solver } = &args.command {
+        return exit_code(check_config(solver).await);
+    }
+    // So do single auctions and quotes, so that their output can be piped.
+    if let cli::Command::Solve(solve) = args.command {
+        return exit_code(crate::solve::run(solve).await);
+    }
+    if let cli::Command::Quote(quote) = args.command {
+        return exit_code(crate::quote::run(quote).await);
+    }
+
     let obs_config = observe::Config::new(
         &args.log,
         tracing::Level::ERROR.into(),
@@ -36,56 +54,253 @@ async fn run_with(args: cli::Args, bind: Option<oneshot::Sender<SocketAddr>>) {
     observe::heap_dump_handler::spawn_heap_dump_handler();
     tracing::info!("running solver engine with {args:#?}");
 
-    let solver = match args.command {
-        cli::Command::ZeroEx { config } => {
-            let config = config::dex::zeroex::file::load(&config).await;
-            Solver::Dex(solver::Dex::new(
-                dex::Dex::ZeroEx(
-                    dex::zeroex::ZeroEx::new(config.zeroex).expect("invalid 0x configuration"),
-                ),
-                config.base.clone(),
-            ))
+    let instances = match args.command {
+        cli::Command::Solver(command) => vec![(None, command)],
+        cli::Command::Instances { config } => config::instances::load(&config)
+            .await
+            .into_iter()
+            .map(|instance| (Some(instance.name), instance.solver))
+            .collect(),
+        cli::Command::Replay(replay) => return exit_code(crate::replay::run(replay).await),
+        cli::Command::MockDex(mock) => {
+            crate::mock_dex::run(mock).await;
+            return ExitCode::SUCCESS;
         }
-        cli::Command::Balancer { config } => {
-            let config = config::dex::balancer::file::load(&config).await;
-            let web3 = blockchain::rpc(&config.base.node_url);
-            let query_swap_provider = Box::new(dex::balancer::OnChainQuerySwapProvider::new(
-                config.sor.queries,
-                config.sor.v3_batch_router,
-                config.base.node_url.clone(),
-                config.sor.settlement,
-            ));
-            Solver::Dex(solver::Dex::new(
-                dex::Dex::Balancer(Box::new(
-                    dex::balancer::Sor::new(config.sor, web3.provider, query_swap_provider)
-                        .expect("invalid Balancer configuration"),
-                )),
-                config.base.clone(),
-            ))
+        cli::Command::CheckConfig { .. } | cli::Command::Solve(_) | cli::Command::Quote(_) => {
+            unreachable!("configuration checks, single auctions and quotes return early")
         }
-        cli::Command::OneInch { config } => {
-            let config = config::dex::oneinch::file::load(&config).await;
-            Solver::Dex(solver::Dex::new(
-                dex::Dex::OneInch(dex::oneinch::OneInch::new(config.oneinch).await),
-                config.base.clone(),
+    };
+    let recorder = match &args.record {
+        Some(dir) => {
+            let [(None, command)] = instances.as_slice() else {
+                panic!("recording is not supported for named solver instances");
+            };
+            Some(Arc::new(
+                fixture::Recorder::start(dir, command)
+                    .await
+                    .expect("failed to start recording"),
             ))
         }
-        cli::Command::ParaSwap { config } => {
-            let config = config::dex::paraswap::file::load(&config).await;
-            Solver::Dex(solver::Dex::new(
-                dex::Dex::ParaSwap(dex::paraswap::ParaSwap::new(config.paraswap)),
-                config.base.clone(),
-            ))
+        None => None,
+    };
+
+    // Instances that use the same DEX API key share its rate limiter.
+    let rate_limiters = config::dex::RateLimiters::default();
+    let mut solvers = Vec::new();
+    let mut reloads = Vec::new();
+    for (name, command) in &instances {
+        let instance = match &recorder {
+            Some(recorder) => {
+                recorder
+                    .init(create_solvers(
+                        &recorder.solver,
+                        name.as_deref(),
+                        &rate_limiters,
+                    ))
+                    .await
+            }
+            None => create_solvers(command, name.as_deref(), &rate_limiters).await,
         }
+        .into_iter()
+        .map(Arc::new)
+        .collect::<Vec<_>>();
+        solvers.extend(instance.iter().cloned());
+        reloads.push(reload_config(
+            create_reloader(command, rate_limiters.clone()).await,
+            instance,
+        ));
+    }
+
+    let debug_token = match &args.debug_token_file {
+        Some(path) => Some(
+            cli::Secret::read(path)
+                .await
+                .expect("failed to read the debug token"),
+        ),
+        None => None,
     };
 
-    crate::api::Api {
+    let api = crate::api::Api {
         addr: args.addr,
-        solver,
+        solvers: solvers.clone(),
+        recorder,
+        auth: crate::api::Auth {
+            token: args.api_token,
+            hmac_secret: args.api_hmac_secret,
+            allowlist: args.api_allowlist,
+            max_body_size: args.max_body_size,
+            seen_signatures: Default::default(),
+        },
+        debug_token,
+        shutdown_delay: args.shutdown_delay,
+        tls: args
+            .tls_cert
+            .zip(args.tls_key)
+            .map(|(cert, key)| crate::api::Tls { cert, key }),
+        compression: args.compression,
+        request_timeout: args.request_timeout,
+        max_concurrent_solves: args.max_concurrent_solves,
+        validate_openapi: args.validate_openapi,
+    }
+    .serve(bind, shutdown_signal());
+    tokio::select! {
+        result = api => result.unwrap(),
+        _ = futures::future::join_all(reloads) => unreachable!("configuration reloading stopped"),
+    }
+    ExitCode::SUCCESS
+}
+
+/// The exit status of a command that either succeeded or failed.
+fn exit_code(success: bool) -> ExitCode {
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Creates the solver engines for every chain of the configuration file,
+/// optionally as a named instance. The solver engines use the given rate
+/// limiters for their DEX API keys.
+pub(crate) async fn create_solvers(
+    command: &cli::Solver,
+    name: Option<&str>,
+    rate_limiters: &config::dex::RateLimiters,
+) -> Vec<Solver> {
+    let mut solvers = Vec::new();
+    match command {
+        cli::Solver::ZeroEx { config: path } => {
+            for mut config in config::dex::zeroex::file::load(path, rate_limiters).await {
+                config.base.name = name.map(ToOwned::to_owned);
+                solvers.push(Solver::Dex(solver::Dex::new(
+                    dex::Dex::ZeroEx(
+                        dex::zeroex::ZeroEx::new(config.zeroex).expect("invalid 0x configuration"),
+                    ),
+                    config.base,
+                )));
+            }
+        }
+        cli::Solver::Balancer { config: path } => {
+            for mut config in config::dex::balancer::file::load(path, rate_limiters).await {
+                config.base.name = name.map(ToOwned::to_owned);
+                let web3 = blockchain::rpc(&config.base.node_url);
+                let query_swap_provider = Box::new(dex::balancer::OnChainQuerySwapProvider::new(
+                    config.sor.queries,
+                    config.sor.v3_batch_router,
+                    config.base.node_url.clone(),
+                    config.sor.settlement,
+                ));
+                solvers.push(Solver::Dex(solver::Dex::new(
+                    dex::Dex::Balancer(Box::new(
+                        dex::balancer::Sor::new(config.sor, web3.provider, query_swap_provider)
+                            .expect("invalid Balancer configuration"),
+                    )),
+                    config.base,
+                )));
+            }
+        }
+        cli::Solver::OneInch { config: path } => {
+            for mut config in config::dex::oneinch::file::load(path, rate_limiters).await {
+                config.base.name = name.map(ToOwned::to_owned);
+                solvers.push(Solver::Dex(solver::Dex::new(
+                    dex::Dex::OneInch(dex::oneinch::OneInch::new(config.oneinch).await),
+                    config.base,
+                )));
+            }
+        }
+        cli::Solver::ParaSwap { config: path } => {
+            for mut config in config::dex::paraswap::file::load(path, rate_limiters).await {
+                config.base.name = name.map(ToOwned::to_owned);
+                solvers.push(Solver::Dex(solver::Dex::new(
+                    dex::Dex::ParaSwap(dex::paraswap::ParaSwap::new(config.paraswap)),
+                    config.base,
+                )));
+            }
+        }
+    }
+    solvers
+}
+
+/// Creates the reloader for the runtime-tunable parts of the solver
+/// configuration.
+async fn create_reloader(
+    command: &cli::Solver,
+    rate_limiters: config::dex::RateLimiters,
+) -> config::dex::Reloader {
+    match command {
+        cli::Solver::ZeroEx { config } => {
+            config::dex::zeroex::file::reloader(config, rate_limiters).await
+        }
+        cli::Solver::Balancer { config } => {
+            config::dex::balancer::file::reloader(config, rate_limiters).await
+        }
+        cli::Solver::OneInch { config } => {
+            config::dex::oneinch::file::reloader(config, rate_limiters).await
+        }
+        cli::Solver::ParaSwap { config } => {
+            config::dex::paraswap::file::reloader(config, rate_limiters).await
+        }
+    }
+}
+
+/// Validates the configuration of a solver without starting it. Prints the
+/// resolved configuration and returns `true` if it is valid, otherwise prints
+/// all problems that were found.
+async fn check_config(solver: &cli::Solver) -> bool {
+    let result = match solver {
+        cli::Solver::Balancer { config } => config::dex::balancer::file::check(config).await,
+        cli::Solver::ZeroEx { config } => config::dex::zeroex::file::check(config).await,
+        cli::Solver::OneInch { config } => config::dex::oneinch::file::check(config).await,
+        cli::Solver::ParaSwap { config } => config::dex::paraswap::file::check(config).await,
+    };
+    match result {
+        Ok(resolved) => {
+            print!(
+                "{}",
+                toml::to_string_pretty(&resolved).expect("configuration serializes to TOML")
+            );
+            true
+        }
+        Err(problems) => {
+            for problem in &problems.0 {
+                eprintln!("error: {problem}");
+            }
+            false
+        }
+    }
+}
+
+/// How often the configuration file is checked for changes.
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Reloads the runtime-tunable parts of the solver configuration whenever the
+/// configuration file changes or the process receives a SIGHUP.
+async fn reload_config(mut reloader: config::dex::Reloader, solvers: Vec<Arc<Solver>>) {
+    #[cfg(unix)]
+    let mut hangup = unix::signal(SignalKind::hangup()).unwrap();
+    let mut interval = tokio::time::interval(CONFIG_POLL_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        #[cfg(unix)]
+        tokio::select! {
+            _ = interval.tick() => (),
+            _ = hangup.recv() => tracing::info!("received SIGHUP; reloading configuration"),
+        }
+        #[cfg(windows)]
+        interval.tick().await;
+
+        match reloader.reload().await {
+            Ok(Some(reloads)) => {
+                for (solver, reload) in solvers.iter().zip(reloads) {
+                    solver.reload(reload);
+                }
+                tracing::info!("reloaded configuration");
+            }
+            Ok(None) => (),
+            Err(err) => tracing::error!(%err, "rejected configuration reload"),
+        }
     }
-    .serve(bind, shutdown_signal())
-    .await
-    .unwrap();
 }
 
 #[cfg(unix)]
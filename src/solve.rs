@@ -0,0 +1,160 @@
+//! Solving a single auction from the command line.
+//!
+//! The auction runs through the same pipeline as `/solve` requests, including
+//! the DEX API calls, the simulations and the fill amounts of partially
+//! fillable orders, but without the HTTP server. This makes it easy to
+//! reproduce how the solver engine handles a specific auction.
+
+use {
+    crate::{
+        api,
+        domain::{eth, order, solver::dex::history},
+        infra::{cli, metrics::Outcome},
+    },
+    anyhow::{Context, Result},
+    std::fmt::{self, Display, Formatter},
+    tokio::fs,
+};
+
+/// The outcome of solving a single auction.
+pub struct Solved {
+    /// The solutions, in the format of `/solve` responses.
+    pub solutions: serde_json::Value,
+    /// How every order was handled, if requested.
+    pub explanation: Option<String>,
+}
+
+/// Solves the auction and prints the solutions and, if requested, the
+/// explanation. Returns whether the auction could be solved.
+pub async fn run(args: cli::Solve) -> bool {
+    match solve(&args).await {
+        Ok(solved) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&solved.solutions).expect("JSON serializes")
+            );
+            // The explanation goes to stderr, so that the solutions can be
+            // piped on their own.
+            if let Some(explanation) = solved.explanation {
+                eprint!("{explanation}");
+            }
+            true
+        }
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            false
+        }
+    }
+}
+
+pub async fn solve(args: &cli::Solve) -> Result<Solved> {
+    let json = fs::read(&args.auction)
+        .await
+        .with_context(|| format!("failed to read {}", args.auction.display()))?;
+    let mut auction =
+        api::dto::auction::from_json(&json).map_err(|err| anyhow::anyhow!("{err}"))?;
+    let time_limit = chrono::Duration::from_std(args.time_limit).context("invalid time limit")?;
+    auction.deadline = chrono::Utc::now() + time_limit;
+    let auction = api::dto::auction::to_domain(&auction).map_err(|err| anyhow::anyhow!("{err}"))?;
+
+    let solver = crate::run::create_solvers(&args.solver, None, &Default::default())
+        .await
+        .pop()
+        .context("no solver configured")?;
+    let orders = auction.orders.clone();
+    let (mut solutions, handled) = solver.solve_explained(auction).await;
+    // Orders are solved concurrently, so solutions arrive in any order.
+    solutions.sort_by_key(|solution| solution.id.0);
+
+    Ok(Solved {
+        solutions: serde_json::to_value(api::dto::solution::from_domain(&solutions))?,
+        explanation: args.explain.then(|| explain(&orders, &handled)),
+    })
+}
+
+/// Describes how every order of the auction was handled.
+fn explain(orders: &[order::Order], handled: &[history::Order]) -> String {
+    orders
+        .iter()
+        .filter_map(|order| {
+            let handled = handled.iter().find(|handled| handled.uid == order.uid)?;
+            Some(Explanation { order, handled }.to_string())
+        })
+        .collect()
+}
+
+/// How an order was handled.
+struct Explanation<'a> {
+    order: &'a order::Order,
+    handled: &'a history::Order,
+}
+
+impl Display for Explanation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let Self { order, handled } = self;
+        writeln!(f, "order {}", order.uid)?;
+        match order.side {
+            order::Side::Sell => writeln!(
+                f,
+                "  limit: sell {} for at least {}",
+                Amount(&order.sell),
+                Amount(&order.buy),
+            )?,
+            order::Side::Buy => writeln!(
+                f,
+                "  limit: buy {} for at most {}",
+                Amount(&order.buy),
+                Amount(&order.sell),
+            )?,
+        }
+        if let Some(slippage) = &handled.slippage {
+            write!(f, "  slippage: {}", slippage.as_factor())?;
+            if let Some(bps) = slippage.as_bps() {
+                write!(f, " ({bps} bps)")?;
+            }
+            writeln!(f)?;
+        }
+        if let Some(swap) = &handled.swap {
+            writeln!(
+                f,
+                "  quote: {} for {} using {} gas",
+                Amount(&swap.input),
+                Amount(&swap.output),
+                swap.gas.0,
+            )?;
+        }
+        if let Some(gas) = handled.gas {
+            writeln!(f, "  gas: {}", gas.0)?;
+        }
+        write!(f, "  outcome: {}", describe(handled.outcome))?;
+        if let Some(error) = &handled.error {
+            write!(f, " ({error})")?;
+        }
+        writeln!(f)
+    }
+}
+
+struct Amount<'a>(&'a eth::Asset);
+
+impl Display for Amount<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} of {}", self.0.amount, self.0.token.0)
+    }
+}
+
+fn describe(outcome: Option<Outcome>) -> &'static str {
+    match outcome {
+        Some(Outcome::Solved) => "solved",
+        Some(Outcome::NotFound) => "rejected, the DEX API found no swap",
+        Some(Outcome::OutOfPrice) => "rejected, the swap doesn't satisfy the limit price",
+        Some(Outcome::MinSurplusFailed) => "rejected, the swap doesn't provide the minimum surplus",
+        Some(Outcome::FeeExceedsSurplus) => "rejected, the swap doesn't cover the network fee",
+        Some(Outcome::SimulationFailed) => "rejected, the simulation failed",
+        Some(Outcome::RateLimited) => "rejected, the DEX API rate limited the request",
+        Some(Outcome::Deadline) => "not solved before the deadline",
+        Some(Outcome::Unsupported) => "rejected, the DEX API doesn't support the order",
+        Some(Outcome::Error) => "rejected, the DEX API failed",
+        Some(Outcome::BackedOff) => "skipped, the order was backed off after reverting",
+        None => "skipped, there is nothing to swap",
+    }
+}
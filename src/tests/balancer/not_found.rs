@@ -16,7 +16,20 @@ async fn test() {
     let solution = engine
         .solve(json!({
             "id": "1",
-            "tokens": {},
+            "tokens": {
+                "0x1111111111111111111111111111111111111111": {
+                    "decimals": 18,
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": false,
+                },
+                "0x2222222222222222222222222222222222222222": {
+                    "decimals": 18,
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": false,
+                },
+            },
             "orders": [
                 {
                     "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
@@ -0,0 +1,141 @@
+//! Tests that gas simulations of limit orders get sent as separate `eth_call`s
+//! of a JSON-RPC batch request when batching is configured.
+
+use {
+    crate::tests::{self, dex, mock},
+    alloy::primitives::{Address, U256, address},
+    serde_json::json,
+};
+
+/// The owner of the orders in [`dex::auction`].
+const OWNER: Address = address!("5b1e2c2762667331bc91648052f646d1b0d35984");
+
+/// A successful `eth_call` response returning the specified simulated gas.
+fn gas_response(gas: u64) -> serde_json::Value {
+    json!({
+        "id": 0,
+        "jsonrpc": "2.0",
+        "result": format!("0x{}", hex::encode(U256::from(gas).to_be_bytes::<32>())),
+    })
+}
+
+#[tokio::test]
+async fn simulates_gas_through_batch_request() {
+    let api = mock::http::setup(dex::quote_api()).await;
+    let node = mock::http::setup(vec![mock::http::Expectation::Post {
+        path: mock::http::Path::Any,
+        req: mock::http::RequestBody::Any,
+        res: json!([gas_response(200_000)]),
+    }])
+    .await;
+
+    let engine = tests::SolverEngine::new(
+        "zeroex",
+        dex::config(
+            &node.address,
+            &api.address,
+            "gas-simulation-batch-window = '50ms'",
+        ),
+    )
+    .await;
+    let solution = engine.solve(dex::auction("limit")).await.unwrap();
+
+    // The simulated gas plus the default gas offset.
+    assert_eq!(solution["solutions"].as_array().unwrap().len(), 1);
+    assert_eq!(solution["solutions"][0]["gas"], json!(306391));
+}
+
+#[tokio::test]
+async fn discards_reverting_simulations() {
+    let api = mock::http::setup(dex::quote_api()).await;
+    let node = mock::http::setup(vec![mock::http::Expectation::Post {
+        path: mock::http::Path::Any,
+        req: mock::http::RequestBody::Any,
+        res: json!([{
+            "id": 0,
+            "jsonrpc": "2.0",
+            "error": {
+                "code": 3,
+                "message": "execution reverted",
+            },
+        }]),
+    }])
+    .await;
+
+    let engine = tests::SolverEngine::new(
+        "zeroex",
+        dex::config(
+            &node.address,
+            &api.address,
+            "gas-simulation-batch-window = '50ms'",
+        ),
+    )
+    .await;
+    let solution = engine.solve(dex::auction("limit")).await.unwrap();
+
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": []
+        }),
+    );
+}
+
+/// Swaps of the same owner selling the same token would share balances and
+/// allowances if they were executed in a single call, so every swap has to be
+/// simulated in its own `eth_call` with its own state overrides.
+#[tokio::test]
+async fn isolates_swaps_of_the_same_owner_and_token() {
+    let api = mock::http::setup([dex::quote_api(), dex::quote_api()].concat()).await;
+    let node = mock::http::setup(vec![mock::http::Expectation::Post {
+        path: mock::http::Path::Any,
+        req: mock::http::RequestBody::Check(|req| {
+            let calls = req.as_array().expect("expected a batch request");
+            assert_eq!(calls.len(), 2);
+            for call in calls {
+                assert_eq!(call["method"], "eth_call");
+                let params = call["params"].as_array().unwrap();
+                let to = serde_json::from_value::<Address>(params[0]["to"].clone()).unwrap();
+                assert_eq!(to, OWNER);
+                let overrides = params[2]
+                    .as_object()
+                    .unwrap()
+                    .keys()
+                    .map(|account| account.parse::<Address>().unwrap())
+                    .collect::<Vec<_>>();
+                assert_eq!(overrides.len(), 2);
+                assert!(overrides.contains(&OWNER));
+            }
+            assert_ne!(calls[0]["id"], calls[1]["id"]);
+        }),
+        res: json!([gas_response(200_000), gas_response(300_000)]),
+    }])
+    .await;
+
+    let mut auction = dex::auction("limit");
+    let mut order = auction["orders"][0].clone();
+    order["uid"] = json!(format!("0x{}", "2b".repeat(56)));
+    auction["orders"].as_array_mut().unwrap().push(order);
+
+    let engine = tests::SolverEngine::new(
+        "zeroex",
+        dex::config(
+            &node.address,
+            &api.address,
+            "gas-simulation-batch-window = '50ms'\nconcurrent-requests = 2",
+        ),
+    )
+    .await;
+    let solution = engine.solve(auction).await.unwrap();
+
+    // Each order gets the gas of its own simulation plus the default gas
+    // offset.
+    let mut gas = solution["solutions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|solution| solution["gas"].as_u64().unwrap())
+        .collect::<Vec<_>>();
+    gas.sort();
+    assert_eq!(gas, [306391, 406391]);
+}
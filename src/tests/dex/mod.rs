@@ -1,4 +1,108 @@
 //! Test cases that are specific to the dex solver but not the underlying APIs.
 
+use {
+    crate::tests::{self, mock},
+    serde_json::json,
+    std::net::SocketAddr,
+};
+
+mod gas_simulation_batching;
+mod native_token;
 mod partial_fill;
+mod settlement_simulation;
 mod wrong_execution;
+
+/// A 0x API quote for selling 1 WETH for ZRX.
+pub fn quote_api() -> Vec<mock::http::Expectation> {
+    vec![mock::http::Expectation::Get {
+        path: mock::http::Path::exact(
+            "swap/allowance-holder/quote?chainId=1&\
+             buyToken=0xe41d2489571d322189246dafa5ebde1f4699f498&\
+             sellToken=0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2&sellAmount=1000000000000000000&\
+             taker=0x9008d19f58aabd9ed0d60971565aa8510560ab41&slippageBps=100",
+        ),
+        res: json!({
+            "liquidityAvailable": true,
+            "sellAmount": "1000000000000000000",
+            "buyAmount": "5876422636675954000000",
+            "transaction": {
+                "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                "data": "0x6af479b2",
+                "gas": "127886",
+            },
+            "issues": {
+                "allowance": {
+                    "spender": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                    "actual": "1000000000000000000",
+                },
+            },
+        }),
+    }]
+}
+
+/// A 0x solver configuration using the specified node and API, with `settings`
+/// added to the top level.
+pub fn config(node: &SocketAddr, api: &SocketAddr, settings: &str) -> tests::Config {
+    tests::Config::String(format!(
+        r"
+node-url = 'http://{node}'
+{settings}
+[dex]
+chain-id = '1'
+endpoint = 'http://{api}/swap/allowance-holder/'
+api-key = 'SUPER_SECRET_API_KEY'
+        ",
+    ))
+}
+
+/// An auction selling 1 WETH for ZRX with an order of the specified class.
+pub fn auction(class: &str) -> serde_json::Value {
+    json!({
+        "id": "1",
+        "tokens": {
+            "0xe41d2489571d322189246dafa5ebde1f4699f498": {
+                "decimals": 18,
+                "symbol": "ZRX",
+                "referencePrice": "4327903683155778",
+                "availableBalance": "0",
+                "trusted": true,
+            },
+            "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2": {
+                "decimals": 18,
+                "symbol": "WETH",
+                "referencePrice": "1000000000000000000",
+                "availableBalance": "0",
+                "trusted": true,
+            },
+        },
+        "orders": [
+            {
+                "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                          2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                          2a2a2a2a",
+                "sellToken": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                "buyToken": "0xe41d2489571d322189246dafa5ebde1f4699f498",
+                "sellAmount": "1000000000000000000",
+                "buyAmount": "200000000000000000000",
+                "fullSellAmount": "1000000000000000000",
+                "fullBuyAmount": "200000000000000000000",
+                "kind": "sell",
+                "partiallyFillable": false,
+                "class": class,
+                "sellTokenSource": "erc20",
+                "buyTokenDestination": "erc20",
+                "preInteractions": [],
+                "postInteractions": [],
+                "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                "validTo": 0,
+                "appData": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "signingScheme": "presign",
+                "signature": "0x",
+            }
+        ],
+        "liquidity": [],
+        "effectiveGasPrice": "15000000000",
+        "deadline": "2106-01-01T00:00:00.000Z",
+        "surplusCapturingJitOrderOwners": []
+    })
+}
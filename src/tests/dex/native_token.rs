@@ -0,0 +1,199 @@
+//! Tests that orders buying the native token get routed through the wrapped
+//! native token and unwrapped before paying out the order.
+
+use {
+    crate::tests::{self, dex, mock, zeroex},
+    serde_json::json,
+};
+
+fn quote_api() -> Vec<mock::http::Expectation> {
+    vec![mock::http::Expectation::Get {
+        path: mock::http::Path::exact(
+            "swap/allowance-holder/quote?chainId=1&\
+             buyToken=0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2&\
+             sellToken=0xe41d2489571d322189246dafa5ebde1f4699f498&\
+             sellAmount=5876422636675954000000&taker=0x9008d19f58aabd9ed0d60971565aa8510560ab41&\
+             slippageBps=100",
+        ),
+        res: json!({
+            "liquidityAvailable": true,
+            "sellAmount": "5876422636675954000000",
+            "buyAmount": "1000000000000000000",
+            "transaction": {
+                "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                "data": "0x6af479b2",
+                "gas": "127886",
+            },
+            "issues": {
+                "allowance": {
+                    "spender": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                    "actual": "0",
+                },
+            },
+        }),
+    }]
+}
+
+fn auction(class: &str) -> serde_json::Value {
+    json!({
+        "id": "1",
+        "tokens": {
+            "0xe41d2489571d322189246dafa5ebde1f4699f498": {
+                "decimals": 18,
+                "symbol": "ZRX",
+                "referencePrice": "4327903683155778",
+                "availableBalance": "0",
+                "trusted": true,
+            },
+            "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE": {
+                "decimals": 18,
+                "symbol": "ETH",
+                "referencePrice": "1000000000000000000",
+                "availableBalance": "0",
+                "trusted": true,
+            },
+        },
+        "orders": [
+            {
+                "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                          2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                          2a2a2a2a",
+                "sellToken": "0xe41d2489571d322189246dafa5ebde1f4699f498",
+                "buyToken": "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE",
+                "sellAmount": "5876422636675954000000",
+                "buyAmount": "500000000000000000",
+                "fullSellAmount": "5876422636675954000000",
+                "fullBuyAmount": "500000000000000000",
+                "kind": "sell",
+                "partiallyFillable": false,
+                "class": class,
+                "sellTokenSource": "erc20",
+                "buyTokenDestination": "erc20",
+                "preInteractions": [],
+                "postInteractions": [],
+                "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                "validTo": 0,
+                "appData": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "signingScheme": "presign",
+                "signature": "0x",
+            }
+        ],
+        "liquidity": [],
+        "effectiveGasPrice": "15000000000",
+        "deadline": "2106-01-01T00:00:00.000Z",
+        "surplusCapturingJitOrderOwners": []
+    })
+}
+
+#[tokio::test]
+async fn buy_native_token() {
+    let api = mock::http::setup(quote_api()).await;
+
+    let engine = tests::SolverEngine::new("zeroex", zeroex::config(&api.address)).await;
+    let solution = engine.solve(auction("market")).await.unwrap();
+
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": [{
+                "id": 0,
+                "prices": {
+                    "0xe41d2489571d322189246dafa5ebde1f4699f498": "1000000000000000000",
+                    "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee": "5876422636675954000000",
+                },
+                "trades": [
+                    {
+                        "kind": "fulfillment",
+                        "order": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                    2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                                    2a2a2a2a",
+                        "executedAmount": "5876422636675954000000",
+                    }
+                ],
+                "preInteractions": [],
+                "postInteractions": [],
+                "interactions": [
+                    {
+                        "kind": "custom",
+                        "internalize": false,
+                        "target": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                        "value": "0",
+                        "callData": "0x6af479b2",
+                        "allowances": [
+                            {
+                                "token": "0xe41d2489571d322189246dafa5ebde1f4699f498",
+                                "spender": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                                "amount": "5876422636675954000000",
+                            },
+                        ],
+                        "inputs": [
+                            {
+                                "token": "0xe41d2489571d322189246dafa5ebde1f4699f498",
+                                "amount": "5876422636675954000000",
+                            },
+                        ],
+                        "outputs": [
+                            {
+                                "token": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                                "amount": "1000000000000000000",
+                            },
+                        ],
+                    },
+                    {
+                        "kind": "custom",
+                        "internalize": false,
+                        "target": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "value": "0",
+                        "callData": "0x2e1a7d4d\
+                                       0000000000000000000000000000000000000000000000000de0b6b3a7640000",
+                        "allowances": [],
+                        "inputs": [
+                            {
+                                "token": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                                "amount": "1000000000000000000",
+                            },
+                        ],
+                        "outputs": [
+                            {
+                                "token": "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee",
+                                "amount": "1000000000000000000",
+                            },
+                        ],
+                    },
+                ],
+                "gas": 264277,
+            }]
+        }),
+    );
+}
+
+#[tokio::test]
+async fn simulates_gas_with_unwrap() {
+    let api = mock::http::setup(quote_api()).await;
+    let node = mock::http::setup(vec![mock::http::Expectation::Post {
+        path: mock::http::Path::Any,
+        req: mock::http::RequestBody::Any,
+        res: json!({
+            "id": 0,
+            "jsonrpc": "2.0",
+            "result": format!("0x{:064x}", 200_000),
+        }),
+    }])
+    .await;
+
+    let engine =
+        tests::SolverEngine::new("zeroex", dex::config(&node.address, &api.address, "")).await;
+    let solution = engine.solve(auction("limit")).await.unwrap();
+
+    // The simulation executes the unwrap of the swap output, so the simulated
+    // gas plus the default gas offset is used without adding the unwrap gas
+    // on top.
+    let solution = &solution["solutions"][0];
+    assert_eq!(solution["gas"], json!(306391));
+    let interactions = solution["interactions"].as_array().unwrap();
+    assert_eq!(interactions.len(), 2);
+    assert_eq!(
+        interactions[1]["target"],
+        "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"
+    );
+}
@@ -0,0 +1,65 @@
+//! Tests that solutions get verified by simulating the complete settlement
+//! when configured to do so.
+
+use {
+    crate::tests::{self, dex, mock},
+    serde_json::json,
+};
+
+#[tokio::test]
+async fn attaches_simulated_gas() {
+    let api = mock::http::setup(dex::quote_api()).await;
+    let node = mock::http::setup(vec![mock::http::Expectation::Post {
+        path: mock::http::Path::Any,
+        req: mock::http::RequestBody::Any,
+        res: json!({
+            "id": 0,
+            "jsonrpc": "2.0",
+            "result": "0x30d40",
+        }),
+    }])
+    .await;
+
+    let engine = tests::SolverEngine::new(
+        "zeroex",
+        dex::config(&node.address, &api.address, "simulate-settlement = true"),
+    )
+    .await;
+    let solution = engine.solve(dex::auction("market")).await.unwrap();
+
+    assert_eq!(solution["solutions"].as_array().unwrap().len(), 1);
+    assert_eq!(solution["solutions"][0]["gas"], json!(200000));
+}
+
+#[tokio::test]
+async fn discards_reverting_solutions() {
+    let api = mock::http::setup(dex::quote_api()).await;
+    let node = mock::http::setup(vec![mock::http::Expectation::Post {
+        path: mock::http::Path::Any,
+        req: mock::http::RequestBody::Any,
+        res: json!({
+            "id": 0,
+            "jsonrpc": "2.0",
+            "error": {
+                "code": 3,
+                "message": "execution reverted: GPv2: limit price not respected",
+                "data": "0x",
+            },
+        }),
+    }])
+    .await;
+
+    let engine = tests::SolverEngine::new(
+        "zeroex",
+        dex::config(&node.address, &api.address, "simulate-settlement = true"),
+    )
+    .await;
+    let solution = engine.solve(dex::auction("market")).await.unwrap();
+
+    assert_eq!(
+        solution,
+        json!({
+            "solutions": []
+        }),
+    );
+}
@@ -1,4 +1,6 @@
 use {
+    crate::infra::fixture,
+    axum::response::IntoResponse,
     std::{
         fmt::{self, Debug, Formatter},
         net::SocketAddr,
@@ -66,6 +68,12 @@ pub enum Expectation {
         req: RequestBody,
         res: serde_json::Value,
     },
+    /// Responds to the wrapped expectation with the specified status instead
+    /// of 200.
+    Status {
+        status: u16,
+        expectation: Box<Expectation>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -77,10 +85,43 @@ pub enum RequestBody {
     /// excluding the specified paths which are represented as dot-separated
     /// strings.
     Partial(serde_json::Value, Vec<&'static str>),
+    /// The received `[RequestBody]` has to pass the assertions of the
+    /// provided function.
+    Check(fn(&serde_json::Value)),
     /// Any `[RequestBody]` will be accepted.
     Any,
 }
 
+impl From<fixture::Exchange> for Expectation {
+    /// Expects a recorded request, ignoring JSON-RPC request IDs, and responds
+    /// with the recorded status.
+    fn from(exchange: fixture::Exchange) -> Self {
+        let path = Path::exact(exchange.path);
+        let expectation = match exchange.method {
+            fixture::Method::Get => Expectation::Get {
+                path,
+                res: exchange.response,
+            },
+            fixture::Method::Post => Expectation::Post {
+                path,
+                req: match exchange.request {
+                    Some(req) if req.is_object() => RequestBody::Partial(req, vec!["id"]),
+                    Some(req) => RequestBody::Exact(req),
+                    None => RequestBody::Any,
+                },
+                res: exchange.response,
+            },
+        };
+        match exchange.status {
+            200 => expectation,
+            status => Expectation::Status {
+                status,
+                expectation: Box::new(expectation),
+            },
+        }
+    }
+}
+
 /// Drop handle that will verify that the server task didn't panic throughout
 /// the test and that all the expectations have been met.
 pub struct ServerHandle {
@@ -135,7 +176,7 @@ pub async fn setup(mut expectations: Vec<Expectation>) -> ServerHandle {
                 |axum::extract::State(state),
                  axum::extract::Path(path),
                  axum::extract::RawQuery(query)| async move {
-                    axum::response::Json(get(state, Some(path), query))
+                    respond(get(state, Some(path), query))
                 },
             )
                 .post(
@@ -143,7 +184,7 @@ pub async fn setup(mut expectations: Vec<Expectation>) -> ServerHandle {
                      axum::extract::Path(path),
                      axum::extract::RawQuery(query),
                      axum::extract::Json(req)| async move {
-                        axum::response::Json(post(state, Some(path), query, req))
+                        respond(post(state, Some(path), query, req))
                     },
                 ),
         )
@@ -153,14 +194,14 @@ pub async fn setup(mut expectations: Vec<Expectation>) -> ServerHandle {
             "/",
             axum::routing::get(
                 |axum::extract::State(state), axum::extract::RawQuery(query)| async move {
-                    axum::response::Json(get(state, None, query))
+                    respond(get(state, None, query))
                 },
             )
                 .post(
                     |axum::extract::State(state),
                      axum::extract::RawQuery(query),
                      axum::extract::Json(req)| async move {
-                        axum::response::Json(post(state, None, query, req))
+                        respond(post(state, None, query, req))
                     },
                 ),
         )
@@ -202,9 +243,28 @@ where
         .expect("ignore this panic; it was caused by the previous panic")
 }
 
-fn get(state: State, path: Option<String>, query: Option<String>) -> serde_json::Value {
-    let expectation = state.expectations.lock().unwrap().pop();
-    let assertions = || {
+/// Splits off the status to respond with from an expectation.
+fn with_status(expectation: Option<Expectation>) -> (u16, Option<Expectation>) {
+    match expectation {
+        Some(Expectation::Status {
+            status,
+            expectation,
+        }) => (status, Some(*expectation)),
+        expectation => (200, expectation),
+    }
+}
+
+fn respond((status, res): (u16, serde_json::Value)) -> axum::response::Response {
+    (
+        axum::http::StatusCode::from_u16(status).unwrap(),
+        axum::response::Json(res),
+    )
+        .into_response()
+}
+
+fn get(state: State, path: Option<String>, query: Option<String>) -> (u16, serde_json::Value) {
+    let (status, expectation) = with_status(state.expectations.lock().unwrap().pop());
+    let assertions = move || {
         let (expected_path, res) = match expectation {
             Some(Expectation::Get { path, res }) => (path, res),
             Some(other) => panic!("expected GET request but got {other:?}"),
@@ -213,7 +273,7 @@ fn get(state: State, path: Option<String>, query: Option<String>) -> serde_json:
 
         let full_path = full_path(path, query);
         assert_eq!(full_path, expected_path, "GET request has unexpected path");
-        res
+        (status, res)
     };
     assert_and_propagate_panics(assertions, &state.failed_assert)
 }
@@ -223,8 +283,8 @@ fn post(
     path: Option<String>,
     query: Option<String>,
     req: serde_json::Value,
-) -> serde_json::Value {
-    let expectation = state.expectations.lock().unwrap().pop();
+) -> (u16, serde_json::Value) {
+    let (status, expectation) = with_status(state.expectations.lock().unwrap().pop());
 
     let assertions = move || {
         let (expected_path, expected_req, mut res) = match expectation {
@@ -244,22 +304,35 @@ fn post(
                     .collect::<Vec<&str>>();
                 assert_json_matches_excluding!(req, value, &exclude_paths)
             }
+            RequestBody::Check(check) => check(&req),
             RequestBody::Any => (),
         }
 
-        // If this is a JSON-RPC request, echo the request ID back in the response
-        if let Some(req_id) = req.get("id")
-            && let Some(res_obj) = res.as_object_mut()
-        {
-            res_obj.insert("id".to_string(), req_id.clone());
+        // If this is a JSON-RPC request, echo the request ID back in the
+        // response. Batch requests get the IDs echoed in order.
+        match (&req, &mut res) {
+            (serde_json::Value::Array(reqs), serde_json::Value::Array(ress)) => {
+                for (req, res) in reqs.iter().zip(ress) {
+                    echo_id(req, res);
+                }
+            }
+            (req, res) => echo_id(req, res),
         }
 
-        res
+        (status, res)
     };
 
     assert_and_propagate_panics(assertions, &state.failed_assert)
 }
 
+fn echo_id(req: &serde_json::Value, res: &mut serde_json::Value) {
+    if let Some(req_id) = req.get("id")
+        && let Some(res_obj) = res.as_object_mut()
+    {
+        res_obj.insert("id".to_string(), req_id.clone());
+    }
+}
+
 fn full_path(path: Option<String>, query: Option<String>) -> String {
     let path = path.unwrap_or_default();
     match query {
@@ -5,9 +5,10 @@
 //! each file in `tests/`, which makes `cargo test` slower.
 
 use {
+    crate::api::openapi::Schema,
     anyhow::Context,
     reqwest::Url,
-    std::io::Write,
+    std::{io::Write, process::ExitCode},
     tokio::{sync::oneshot, task::JoinHandle},
 };
 
@@ -23,7 +24,7 @@ pub struct SolverEngine {
     url: Url,
     #[allow(dead_code)] // only needed for Drop handling
     tempfile: Option<tempfile::TempPath>,
-    handle: JoinHandle<()>,
+    handle: JoinHandle<ExitCode>,
 }
 
 /// Solver configuration.
@@ -37,14 +38,25 @@ impl SolverEngine {
     /// Creates a new solver engine handle for the specified command
     /// configuration.
     pub async fn new(command: &str, config: Config) -> Self {
+        Self::with_args(command, config, []).await
+    }
+
+    /// Creates a new solver engine handle with additional command line
+    /// arguments.
+    pub async fn with_args(
+        command: &str,
+        config: Config,
+        extra: impl IntoIterator<Item = String>,
+    ) -> Self {
         let (bind, bind_receiver) = oneshot::channel();
 
         let mut args = vec![
             "/test/solvers/path".to_owned(),
             "--addr=0.0.0.0:0".to_owned(),
             "--log=solvers=trace".to_owned(),
-            command.to_owned(),
         ];
+        args.extend(extra);
+        args.push(command.to_owned());
         let tempfile = match config {
             Config::None => None,
             Config::String(config) => {
@@ -70,8 +82,19 @@ impl SolverEngine {
 
     /// Solves a raw JSON auction.
     pub async fn solve(&self, auction: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+        self.solve_at("solve", auction).await
+    }
+
+    /// Solves a raw JSON auction with the solve endpoint at the specified
+    /// path.
+    pub async fn solve_at(
+        &self,
+        path: &str,
+        auction: serde_json::Value,
+    ) -> anyhow::Result<serde_json::Value> {
+        assert_spec(Schema::request("/solve", "post"), &auction);
         let client = reqwest::Client::new();
-        let url = shared::url::join(&self.url, "solve");
+        let url = shared::url::join(&self.url, path);
         let response = client.post(url).json(&auction).send().await?;
 
         if !response.status().is_success() {
@@ -80,13 +103,93 @@ impl SolverEngine {
             anyhow::bail!("HTTP {}: {:?}", status, text);
         }
 
-        response
+        let solutions = response
+            .json()
+            .await
+            .context("Failed to parse JSON response")?;
+        assert_spec(Schema::response("/solve", "post", 200), &solutions);
+        Ok(solutions)
+    }
+
+    /// Quotes a raw JSON swap request.
+    pub async fn quote(&self, request: serde_json::Value) -> anyhow::Result<serde_json::Value> {
+        assert_spec(Schema::request("/quote", "post"), &request);
+        let client = reqwest::Client::new();
+        let url = shared::url::join(&self.url, "quote");
+        let response = client.post(url).json(&request).send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await?;
+            anyhow::bail!("HTTP {}: {:?}", status, text);
+        }
+
+        let quote = response
+            .json()
+            .await
+            .context("Failed to parse JSON response")?;
+        assert_spec(Schema::response("/quote", "post", 200), &quote);
+        Ok(quote)
+    }
+
+    /// Estimates the native price of a token.
+    pub async fn native_price(&self, token: &str) -> anyhow::Result<serde_json::Value> {
+        let url = shared::url::join(&self.url, &format!("native_price/{token}"));
+        let response = reqwest::get(url).await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await?;
+            anyhow::bail!("HTTP {}: {:?}", status, text);
+        }
+
+        let price = response
             .json()
             .await
-            .context("Failed to parse JSON response")
+            .context("Failed to parse JSON response")?;
+        assert_spec(
+            Schema::response("/native_price/{token}", "get", 200),
+            &price,
+        );
+        Ok(price)
+    }
+
+    /// Sends a raw JSON solution notification.
+    pub async fn notify(&self, notification: serde_json::Value) -> anyhow::Result<()> {
+        assert_spec(Schema::request("/notify", "post"), &notification);
+        let client = reqwest::Client::new();
+        let url = shared::url::join(&self.url, "notify");
+        let response = client.post(url).json(&notification).send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let text = response.text().await?;
+            anyhow::bail!("HTTP {}: {:?}", status, text);
+        }
+        Ok(())
     }
 }
 
+/// Asserts that a request or response matches the OpenAPI specification, so
+/// that the specification can't drift from what the engine actually accepts
+/// and returns.
+pub fn assert_spec(schema: Schema, value: &serde_json::Value) {
+    let violations = schema.violations(value);
+    assert!(
+        violations.is_empty(),
+        "{value} violates the OpenAPI specification: {violations:#?}"
+    );
+}
+
+/// Writes a secret, such as the debug token, to a temporary file that can be
+/// passed to the solver engine. The file is removed when the returned path is
+/// dropped.
+pub fn secret_file(secret: &str) -> tempfile::TempPath {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    writeln!(file, "{secret}").unwrap();
+    file.into_temp_path()
+}
+
 impl Drop for SolverEngine {
     fn drop(&mut self) {
         self.handle.abort();
@@ -0,0 +1,203 @@
+//! This test ensures that the solver endpoints reject requests that don't
+//! authenticate, come from networks that aren't allowed or are too large,
+//! while probes stay accessible.
+
+use {
+    crate::tests::{self, mock, zeroex},
+    hmac::{Hmac, Mac},
+    serde_json::json,
+    sha2::Sha256,
+    std::time::{Duration, SystemTime},
+};
+
+fn auction() -> serde_json::Value {
+    json!({
+        "id": "1",
+        "tokens": {},
+        "orders": [],
+        "liquidity": [],
+        "effectiveGasPrice": "15000000000",
+        "deadline": "2106-01-01T00:00:00.000Z",
+        "surplusCapturingJitOrderOwners": []
+    })
+}
+
+async fn solve(
+    engine: &tests::SolverEngine,
+    headers: &[(&str, String)],
+    body: Vec<u8>,
+) -> reqwest::StatusCode {
+    let mut request = reqwest::Client::new()
+        .post(shared::url::join(&engine.url, "solve"))
+        .header("content-type", "application/json")
+        .body(body);
+    for (name, value) in headers {
+        request = request.header(*name, value);
+    }
+    request.send().await.unwrap().status()
+}
+
+/// The `X-Timestamp` and `X-Signature` headers of a body signed at the
+/// specified time.
+fn sign(secret: &str, timestamp: SystemTime, body: &[u8]) -> [(&'static str, String); 2] {
+    let timestamp = timestamp
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        .to_string();
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
+    mac.update(format!("{timestamp}.").as_bytes());
+    mac.update(body);
+    [
+        ("x-timestamp", timestamp),
+        ("x-signature", hex::encode(mac.finalize().into_bytes())),
+    ]
+}
+
+#[tokio::test]
+async fn token_or_signature() {
+    let api = mock::http::setup(vec![]).await;
+    let engine = tests::SolverEngine::with_args(
+        "zeroex",
+        zeroex::config(&api.address),
+        [
+            "--api-token=SUPER_SECRET_TOKEN".to_owned(),
+            "--api-hmac-secret=SUPER_SECRET_KEY".to_owned(),
+        ],
+    )
+    .await;
+    let body = serde_json::to_vec(&auction()).unwrap();
+
+    assert_eq!(
+        solve(&engine, &[], body.clone()).await,
+        reqwest::StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        solve(
+            &engine,
+            &[("authorization", "Bearer WRONG_TOKEN".to_owned())],
+            body.clone()
+        )
+        .await,
+        reqwest::StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(
+        solve(
+            &engine,
+            &[("authorization", "Bearer SUPER_SECRET_TOKEN".to_owned())],
+            body.clone()
+        )
+        .await,
+        reqwest::StatusCode::OK
+    );
+    let signed = sign("SUPER_SECRET_KEY", SystemTime::now(), &body);
+    assert_eq!(
+        solve(&engine, &signed, body.clone()).await,
+        reqwest::StatusCode::OK
+    );
+    assert_eq!(
+        solve(
+            &engine,
+            &sign("WRONG_KEY", SystemTime::now(), &body),
+            body.clone()
+        )
+        .await,
+        reqwest::StatusCode::UNAUTHORIZED
+    );
+
+    // Signed requests can't be replayed, neither right away nor later, and the
+    // timestamp can't be changed without invalidating the signature.
+    assert_eq!(
+        solve(&engine, &signed, body.clone()).await,
+        reqwest::StatusCode::UNAUTHORIZED
+    );
+    let [_, signature] = sign(
+        "SUPER_SECRET_KEY",
+        SystemTime::now() - Duration::from_secs(600),
+        &body,
+    );
+    assert_eq!(
+        solve(
+            &engine,
+            &sign(
+                "SUPER_SECRET_KEY",
+                SystemTime::now() - Duration::from_secs(600),
+                &body
+            ),
+            body.clone()
+        )
+        .await,
+        reqwest::StatusCode::UNAUTHORIZED
+    );
+    let [timestamp, _] = sign("SUPER_SECRET_KEY", SystemTime::now(), &body);
+    assert_eq!(
+        solve(&engine, &[timestamp, signature], body.clone()).await,
+        reqwest::StatusCode::UNAUTHORIZED
+    );
+
+    // Probes don't need to authenticate.
+    let healthz = reqwest::get(shared::url::join(&engine.url, "healthz"))
+        .await
+        .unwrap();
+    assert_eq!(healthz.status(), reqwest::StatusCode::OK);
+
+    let metrics = reqwest::get(shared::url::join(&engine.url, "metrics"))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(metrics.contains(r#"reason="invalid_signature""#));
+    assert!(metrics.contains(r#"reason="expired_signature""#));
+    assert!(metrics.contains(r#"reason="replayed_signature""#));
+    assert!(metrics.contains(r#"reason="unauthorized""#));
+}
+
+#[tokio::test]
+async fn allowlist() {
+    let api = mock::http::setup(vec![]).await;
+    let allowed = tests::SolverEngine::with_args(
+        "zeroex",
+        zeroex::config(&api.address),
+        ["--api-allowlist=10.0.0.0/8,127.0.0.0/8".to_owned()],
+    )
+    .await;
+    let denied = tests::SolverEngine::with_args(
+        "zeroex",
+        zeroex::config(&api.address),
+        ["--api-allowlist=10.0.0.0/8".to_owned()],
+    )
+    .await;
+    let body = serde_json::to_vec(&auction()).unwrap();
+
+    assert_eq!(
+        solve(&allowed, &[], body.clone()).await,
+        reqwest::StatusCode::OK
+    );
+    assert_eq!(
+        solve(&denied, &[], body).await,
+        reqwest::StatusCode::FORBIDDEN
+    );
+}
+
+#[tokio::test]
+async fn body_size_limit() {
+    let api = mock::http::setup(vec![]).await;
+    let engine = tests::SolverEngine::with_args(
+        "zeroex",
+        zeroex::config(&api.address),
+        ["--max-body-size=1024".to_owned()],
+    )
+    .await;
+
+    let body = serde_json::to_vec(&auction()).unwrap();
+    assert_eq!(solve(&engine, &[], body).await, reqwest::StatusCode::OK);
+
+    let mut large = auction();
+    large["id"] = json!("1".repeat(1024));
+    let body = serde_json::to_vec(&large).unwrap();
+    assert_eq!(
+        solve(&engine, &[], body).await,
+        reqwest::StatusCode::PAYLOAD_TOO_LARGE
+    );
+}
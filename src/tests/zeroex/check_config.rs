@@ -0,0 +1,35 @@
+//! This test ensures that checking a configuration returns the exit status
+//! instead of exiting the process.
+
+use {
+    crate::tests::{self, zeroex},
+    std::{io::Write, process::ExitCode},
+};
+
+async fn check_config(config: &str) -> ExitCode {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    file.write_all(config.as_bytes()).unwrap();
+    crate::run(
+        [
+            "/test/solvers/path".to_owned(),
+            "check-config".to_owned(),
+            "zeroex".to_owned(),
+            format!("--config={}", file.path().display()),
+        ],
+        None,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn exit_status() {
+    let tests::Config::String(config) = zeroex::config(&"127.0.0.1:0".parse().unwrap()) else {
+        unreachable!()
+    };
+
+    assert_eq!(check_config(&config).await, ExitCode::SUCCESS);
+    assert_eq!(
+        check_config(&format!("unknown-field = true\n{config}")).await,
+        ExitCode::FAILURE
+    );
+}
@@ -0,0 +1,92 @@
+//! These tests ensure that the solver engine accepts compressed auctions and
+//! compresses its responses when compression is enabled, and that the body
+//! size limit applies to the decompressed body.
+
+use {
+    crate::tests::{self, mock, zeroex},
+    flate2::{Compression, read::GzDecoder, write::GzEncoder},
+    serde_json::json,
+    std::io::{Read, Write},
+};
+
+fn gzip(body: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[tokio::test]
+async fn test() {
+    let api = mock::http::setup(vec![]).await;
+    let engine = tests::SolverEngine::with_args(
+        "zeroex",
+        zeroex::config(&api.address),
+        ["--compression".to_owned()],
+    )
+    .await;
+
+    let auction = json!({
+        "id": "1",
+        "tokens": {},
+        "orders": [],
+        "liquidity": [],
+        "effectiveGasPrice": "15000000000",
+        "deadline": "2106-01-01T00:00:00.000Z",
+        "surplusCapturingJitOrderOwners": []
+    });
+    let response = reqwest::Client::new()
+        .post(shared::url::join(&engine.url, "solve"))
+        .header("content-type", "application/json")
+        .header("content-encoding", "gzip")
+        .body(gzip(&serde_json::to_vec(&auction).unwrap()))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(
+        response.json::<serde_json::Value>().await.unwrap(),
+        json!({ "solutions": [] })
+    );
+
+    // Tiny responses don't get compressed, so check a larger one.
+    let response = reqwest::Client::new()
+        .get(shared::url::join(&engine.url, "metrics"))
+        .header("accept-encoding", "gzip")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["content-encoding"], "gzip");
+    let mut metrics = String::new();
+    GzDecoder::new(&response.bytes().await.unwrap()[..])
+        .read_to_string(&mut metrics)
+        .unwrap();
+    assert!(metrics.contains("solver_engine_"));
+}
+
+#[tokio::test]
+async fn decompressed_body_size_limit() {
+    let api = mock::http::setup(vec![]).await;
+    let engine = tests::SolverEngine::with_args(
+        "zeroex",
+        zeroex::config(&api.address),
+        [
+            "--compression".to_owned(),
+            "--max-body-size=1024".to_owned(),
+        ],
+    )
+    .await;
+
+    // A body that compresses to well below the limit, but decompresses to
+    // far more.
+    let body = gzip(&vec![b' '; 1024 * 1024]);
+    assert!(body.len() < 1024);
+    let response = reqwest::Client::new()
+        .post(shared::url::join(&engine.url, "solve"))
+        .header("content-type", "application/json")
+        .header("content-encoding", "gzip")
+        .body(body)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+}
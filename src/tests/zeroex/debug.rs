@@ -0,0 +1,131 @@
+//! This test ensures that the debug API requires the configured token and
+//! shows the outcomes of the orders of recent auctions.
+
+use {
+    crate::{
+        api::openapi::Schema,
+        tests::{self, mock, zeroex},
+    },
+    serde_json::json,
+};
+
+#[tokio::test]
+async fn test() {
+    let api = mock::http::setup(vec![mock::http::Expectation::Get {
+        path: mock::http::Path::Any,
+        res: json!({
+            "code": 100,
+            "reason": "Validation Failed",
+            "validationErrors": [
+                {
+                    "field": "buyAmount",
+                    "code": 1004,
+                    "reason": "INSUFFICIENT_ASSET_LIQUIDITY",
+                    "description": "We are not able to fulfill an order for this token pair \
+                                    at the requested amount due to a lack of liquidity",
+                },
+            ],
+        }),
+    }])
+    .await;
+
+    let token = tests::secret_file("SUPER_SECRET_TOKEN");
+    let engine = tests::SolverEngine::with_args(
+        "zeroex",
+        zeroex::config(&api.address),
+        [format!("--debug-token-file={}", token.display())],
+    )
+    .await;
+
+    let solution = engine
+        .solve(json!({
+            "id": "1",
+            "tokens": {
+                "0x1111111111111111111111111111111111111111": {
+                    "decimals": 18,
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": false,
+                },
+                "0x2222222222222222222222222222222222222222": {
+                    "decimals": 18,
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": false,
+                },
+            },
+            "orders": [
+                {
+                    "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a",
+                    "sellToken": "0x1111111111111111111111111111111111111111",
+                    "buyToken": "0x2222222222222222222222222222222222222222",
+                    "sellAmount": "1000000000000000000",
+                    "buyAmount": "1000000000000000000",
+                    "fullSellAmount": "1000000000000000000",
+                    "fullBuyAmount": "1000000000000000000",
+                    "kind": "sell",
+                    "partiallyFillable": false,
+                    "class": "market",
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "validTo": 0,
+                    "appData": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                },
+            ],
+            "liquidity": [],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }))
+        .await
+        .unwrap();
+    assert_eq!(solution, json!({ "solutions": [] }));
+
+    let client = reqwest::Client::new();
+    let url = shared::url::join(&engine.url, "debug/auctions");
+    let response = client.get(url.clone()).send().await.unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let auctions = client
+        .get(url)
+        .bearer_auth("SUPER_SECRET_TOKEN")
+        .send()
+        .await
+        .unwrap()
+        .json::<serde_json::Value>()
+        .await
+        .unwrap();
+    tests::assert_spec(Schema::response("/debug/auctions", "get", 200), &auctions);
+    assert_eq!(auctions[0]["id"], "1");
+    assert_eq!(
+        auctions[0]["orders"][0]["order"],
+        "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+         2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a",
+    );
+    assert_eq!(auctions[0]["orders"][0]["outcome"], "not_found");
+    assert!(auctions[0]["orders"][0]["error"].is_string());
+
+    let status = client
+        .get(shared::url::join(&engine.url, "debug/status"))
+        .bearer_auth("SUPER_SECRET_TOKEN")
+        .send()
+        .await
+        .unwrap()
+        .json::<serde_json::Value>()
+        .await
+        .unwrap();
+    tests::assert_spec(Schema::response("/debug/status", "get", 200), &status);
+    assert_eq!(status["rateLimiter"]["consecutiveFailures"], 0);
+    assert_eq!(
+        status["rateLimiter"]["backoffUntil"],
+        serde_json::Value::Null
+    );
+    assert_eq!(status["block"], serde_json::Value::Null);
+}
@@ -0,0 +1,71 @@
+//! This test ensures that a single solver engine serves several named solver
+//! instances, each under its own path and with the DEX API of its own
+//! configuration.
+
+use {
+    crate::tests::{self, mock, zeroex},
+    serde_json::json,
+    std::{io::Write, net::SocketAddr},
+};
+
+fn config(api: &SocketAddr, slippage: &str) -> tempfile::TempPath {
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        file,
+        r"
+node-url = 'http://localhost:8545'
+relative-slippage = '{slippage}'
+[dex]
+chain-id = '1'
+endpoint = 'http://{api}/swap/allowance-holder/'
+api-key = 'SUPER_SECRET_API_KEY'
+        ",
+    )
+    .unwrap();
+    file.into_temp_path()
+}
+
+#[tokio::test]
+async fn test() {
+    let tight = mock::http::setup(vec![zeroex::not_found()]).await;
+    let loose = mock::http::setup(vec![zeroex::not_found()]).await;
+    let tight_config = config(&tight.address, "0.001");
+    let loose_config = config(&loose.address, "0.01");
+
+    let engine = tests::SolverEngine::new(
+        "instances",
+        tests::Config::String(format!(
+            r"
+[[instances]]
+name = 'zeroex-tight'
+solver = 'zeroex'
+config = '{}'
+
+[[instances]]
+name = 'zeroex-loose'
+solver = 'zeroex'
+config = '{}'
+            ",
+            tight_config.display(),
+            loose_config.display(),
+        )),
+    )
+    .await;
+
+    for path in ["zeroex-tight/solve", "zeroex-loose/1/solve"] {
+        let solution = engine.solve_at(path, zeroex::auction()).await.unwrap();
+        assert_eq!(solution, json!({ "solutions": [] }));
+    }
+
+    // Without an instance name in the path, there is no solver to route to.
+    assert!(engine.solve(zeroex::auction()).await.is_err());
+
+    let metrics = reqwest::get(shared::url::join(&engine.url, "metrics"))
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(metrics.contains(r#"solver="zeroex-tight""#));
+    assert!(metrics.contains(r#"solver="zeroex-loose""#));
+}
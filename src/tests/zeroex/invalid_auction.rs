@@ -0,0 +1,171 @@
+//! This test ensures that invalid auctions are rejected with the path of every
+//! invalid field.
+
+use {
+    crate::tests::{self, mock, zeroex},
+    serde_json::json,
+};
+
+fn order(uid: &str, sell_amount: &str) -> serde_json::Value {
+    json!({
+        "uid": uid,
+        "sellToken": "0x1111111111111111111111111111111111111111",
+        "buyToken": "0x2222222222222222222222222222222222222222",
+        "sellAmount": sell_amount,
+        "buyAmount": "1000000000000000000",
+        "fullSellAmount": "1000000000000000000",
+        "fullBuyAmount": "1000000000000000000",
+        "kind": "sell",
+        "partiallyFillable": false,
+        "class": "market",
+        "sellTokenSource": "erc20",
+        "buyTokenDestination": "erc20",
+        "preInteractions": [],
+        "postInteractions": [],
+        "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+        "validTo": 0,
+        "appData": "0x0000000000000000000000000000000000000000000000000000000000000000",
+        "signingScheme": "presign",
+        "signature": "0x",
+    })
+}
+
+const UID: &str = "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                   2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                   2a2a2a2a";
+
+async fn solve(
+    engine: &tests::SolverEngine,
+    auction: serde_json::Value,
+) -> (reqwest::StatusCode, serde_json::Value) {
+    let response = reqwest::Client::new()
+        .post(shared::url::join(&engine.url, "solve"))
+        .json(&auction)
+        .send()
+        .await
+        .unwrap();
+    (response.status(), response.json().await.unwrap())
+}
+
+fn fields(error: &serde_json::Value) -> Vec<(&str, &str)> {
+    error["invalidFields"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|field| {
+            (
+                field["path"].as_str().unwrap(),
+                field["kind"].as_str().unwrap(),
+            )
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test() {
+    let api = mock::http::setup(vec![]).await;
+    let engine = tests::SolverEngine::new("zeroex", zeroex::config(&api.address)).await;
+
+    let (status, error) = solve(
+        &engine,
+        json!({
+            "id": "1",
+            "tokens": {
+                "0x1111111111111111111111111111111111111111": {
+                    "decimals": 18,
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "0",
+                    "trusted": false,
+                },
+            },
+            "orders": [order(UID, "1000000000000000000"), order(UID, "0")],
+            "liquidity": [],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2000-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }),
+    )
+    .await;
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+    assert_eq!(error["message"], "invalid auction");
+    assert_eq!(
+        fields(&error),
+        [
+            ("deadline", "deadlinePassed"),
+            ("orders[0].buyToken", "unknownToken"),
+            ("orders[1].uid", "duplicateUid"),
+            ("orders[1].sellAmount", "zeroAmount"),
+            ("orders[1].buyToken", "unknownToken"),
+        ],
+    );
+
+    // Malformed orders are reported next to invalid ones.
+    let (status, error) = solve(
+        &engine,
+        json!({
+            "id": "1",
+            "tokens": {},
+            "orders": [
+                order("0x2a2a", "1000000000000000000"),
+                order(UID, "0"),
+            ],
+            "liquidity": [],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }),
+    )
+    .await;
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+    assert_eq!(
+        fields(&error),
+        [
+            ("orders[0].uid", "malformed"),
+            ("orders[1].sellAmount", "zeroAmount"),
+            ("orders[1].sellToken", "unknownToken"),
+            ("orders[1].buyToken", "unknownToken"),
+        ],
+    );
+
+    // Every malformed field of the auction is reported, next to the passed
+    // deadline and the malformed orders.
+    let (status, error) = solve(
+        &engine,
+        json!({
+            "id": "1",
+            "tokens": [],
+            "orders": [order("0x2a2a", "1000000000000000000")],
+            "liquidity": {},
+            "effectiveGasPrice": "cheap",
+            "deadline": "2000-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }),
+    )
+    .await;
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+    let mut fields = fields(&error);
+    assert_eq!(fields[0], ("deadline", "deadlinePassed"));
+    fields.sort();
+    assert_eq!(
+        fields,
+        [
+            ("deadline", "deadlinePassed"),
+            ("effectiveGasPrice", "malformed"),
+            ("liquidity", "malformed"),
+            ("orders[0].uid", "malformed"),
+            ("tokens", "malformed"),
+        ],
+    );
+
+    let response = reqwest::Client::new()
+        .post(shared::url::join(&engine.url, "solve"))
+        .header("content-type", "text/plain")
+        .body("{}")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.status(),
+        reqwest::StatusCode::UNSUPPORTED_MEDIA_TYPE
+    );
+}
@@ -1,9 +1,27 @@
-use {crate::tests, std::net::SocketAddr};
+use {
+    crate::tests::{self, mock},
+    serde_json::json,
+    std::net::SocketAddr,
+};
 
+mod auth;
+mod check_config;
+mod compression;
+mod debug;
+mod instances;
+mod invalid_auction;
 mod market_order;
+mod multi_chain;
+mod native_price;
 mod not_found;
+mod notify;
+mod openapi;
 mod options;
 mod out_of_price;
+mod quote;
+mod readyz;
+mod record;
+mod solve;
 
 /// Creates a temporary file containing the config of the given solver.
 pub fn config(solver_addr: &SocketAddr) -> tests::Config {
@@ -17,3 +35,73 @@ api-key = 'SUPER_SECRET_API_KEY'
         ",
     ))
 }
+
+/// A 0x API response for any quote, without liquidity for the swap.
+pub fn not_found() -> mock::http::Expectation {
+    mock::http::Expectation::Get {
+        path: mock::http::Path::glob("swap/allowance-holder/quote*"),
+        res: json!({
+            "code": 100,
+            "reason": "Validation Failed",
+            "validationErrors": [
+                {
+                    "field": "buyAmount",
+                    "code": 1004,
+                    "reason": "INSUFFICIENT_ASSET_LIQUIDITY",
+                    "description": "We are not able to fulfill an order for this token pair \
+                                    at the requested amount due to a lack of liquidity",
+                },
+            ],
+        }),
+    }
+}
+
+/// An auction with a single market order, selling `0x1111..` for `0x2222..`.
+pub fn auction() -> serde_json::Value {
+    json!({
+        "id": "1",
+        "tokens": {
+            "0x1111111111111111111111111111111111111111": {
+                "decimals": 18,
+                "referencePrice": "1000000000000000000",
+                "availableBalance": "0",
+                "trusted": false,
+            },
+            "0x2222222222222222222222222222222222222222": {
+                "decimals": 18,
+                "referencePrice": "1000000000000000000",
+                "availableBalance": "0",
+                "trusted": false,
+            },
+        },
+        "orders": [
+            {
+                "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                          2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                          2a2a2a2a",
+                "sellToken": "0x1111111111111111111111111111111111111111",
+                "buyToken": "0x2222222222222222222222222222222222222222",
+                "sellAmount": "1000000000000000000",
+                "buyAmount": "1000000000000000000",
+                "fullSellAmount": "1000000000000000000",
+                "fullBuyAmount": "1000000000000000000",
+                "kind": "sell",
+                "partiallyFillable": false,
+                "class": "market",
+                "sellTokenSource": "erc20",
+                "buyTokenDestination": "erc20",
+                "preInteractions": [],
+                "postInteractions": [],
+                "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                "validTo": 0,
+                "appData": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "signingScheme": "presign",
+                "signature": "0x",
+            },
+        ],
+        "liquidity": [],
+        "effectiveGasPrice": "15000000000",
+        "deadline": "2106-01-01T00:00:00.000Z",
+        "surplusCapturingJitOrderOwners": []
+    })
+}
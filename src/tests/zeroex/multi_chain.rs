@@ -0,0 +1,87 @@
+//! This test ensures that a single solver engine solves auctions for every
+//! chain of a multi-chain configuration, with the DEX API of the chain that
+//! the auction was sent to.
+
+use {
+    crate::tests::{self, mock, zeroex},
+    serde_json::json,
+};
+
+/// A quote of the DEX API of the given chain, buying the given amount.
+fn quote(chain_id: u64, buy_amount: &str) -> mock::http::Expectation {
+    mock::http::Expectation::Get {
+        path: mock::http::Path::glob(format!("swap/allowance-holder/quote?chainId={chain_id}&*")),
+        res: json!({
+            "liquidityAvailable": true,
+            "sellAmount": "1000000000000000000",
+            "buyAmount": buy_amount,
+            "transaction": {
+                "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                "data": "0x6af479b2",
+                "gas": "100000",
+            },
+            "issues": {
+                "allowance": {
+                    "spender": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                    "actual": "0",
+                },
+            },
+        }),
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    let mainnet = mock::http::setup(vec![quote(1, "2000000000000000000")]).await;
+    let gnosis = mock::http::setup(vec![quote(100, "3000000000000000000")]).await;
+
+    let engine = tests::SolverEngine::new(
+        "zeroex",
+        tests::Config::String(format!(
+            r"
+node-url = 'http://localhost:8545'
+[dex]
+api-key = 'SUPER_SECRET_API_KEY'
+
+[[chains]]
+[chains.dex]
+chain-id = '1'
+endpoint = 'http://{}/swap/allowance-holder/'
+
+[[chains]]
+node-url = 'http://localhost:8546'
+[chains.dex]
+chain-id = '100'
+endpoint = 'http://{}/swap/allowance-holder/'
+            ",
+            mainnet.address, gnosis.address,
+        )),
+    )
+    .await;
+
+    for (path, buy_amount) in [
+        ("1/solve", "2000000000000000000"),
+        ("100/solve", "3000000000000000000"),
+    ] {
+        let solution = engine.solve_at(path, zeroex::auction()).await.unwrap();
+        let solutions = solution["solutions"].as_array().unwrap();
+        assert_eq!(solutions.len(), 1);
+        assert_eq!(
+            solutions[0]["prices"],
+            json!({
+                "0x1111111111111111111111111111111111111111": buy_amount,
+                "0x2222222222222222222222222222222222222222": "1000000000000000000",
+            }),
+        );
+        assert_eq!(
+            solutions[0]["interactions"][0]["outputs"],
+            json!([{
+                "token": "0x2222222222222222222222222222222222222222",
+                "amount": buy_amount,
+            }]),
+        );
+    }
+
+    // Without a chain in the path, there is no solver to route to.
+    assert!(engine.solve(zeroex::auction()).await.is_err());
+}
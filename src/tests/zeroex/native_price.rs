@@ -0,0 +1,51 @@
+//! This test ensures that native token prices get estimated by selling the
+//! reference amount of the wrapped native token.
+
+use {
+    crate::tests::{self, mock, zeroex},
+    serde_json::json,
+};
+
+#[tokio::test]
+async fn test() {
+    let api = mock::http::setup(vec![mock::http::Expectation::Get {
+        path: mock::http::Path::exact(
+            "swap/allowance-holder/quote?chainId=1&\
+             buyToken=0xe41d2489571d322189246dafa5ebde1f4699f498&\
+             sellToken=0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2&sellAmount=1000000000000000000&\
+             taker=0x9008d19f58aabd9ed0d60971565aa8510560ab41&slippageBps=100",
+        ),
+        res: json!({
+            "liquidityAvailable": true,
+            "sellAmount": "1000000000000000000",
+            "buyAmount": "5876422636675954000000",
+            "transaction": {
+                "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                "data": "0x6af479b2",
+                "gas": "127886",
+            },
+            "issues": {
+                "allowance": {
+                    "spender": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                    "actual": "1000000000000000000",
+                },
+            },
+        }),
+    }])
+    .await;
+
+    let engine = tests::SolverEngine::new("zeroex", zeroex::config(&api.address)).await;
+
+    let price = engine
+        .native_price("0xe41d2489571d322189246dafa5ebde1f4699f498")
+        .await
+        .unwrap();
+    assert_eq!(price, json!({ "price": "170171558757328" }));
+
+    // The wrapped native token is priced without querying the DEX API.
+    let price = engine
+        .native_price("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")
+        .await
+        .unwrap();
+    assert_eq!(price, json!({ "price": "1000000000000000000" }));
+}
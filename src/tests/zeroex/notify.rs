@@ -0,0 +1,100 @@
+//! This test ensures that orders of a solution that reverted on chain get
+//! backed off and are not solved again in the next auction.
+
+use {
+    crate::tests::{self, mock, zeroex},
+    serde_json::json,
+};
+
+#[tokio::test]
+async fn reverted_orders_are_backed_off() {
+    let api = mock::http::setup(vec![mock::http::Expectation::Get {
+        path: mock::http::Path::Any,
+        res: json!({
+            "liquidityAvailable": true,
+            "sellAmount": "1000000000000000000",
+            "buyAmount": "5876422636675954000000",
+            "transaction": {
+                "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                "data": "0x",
+                "gas": "127886",
+            },
+            "issues": {
+                "allowance": {
+                    "spender": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                    "actual": "1000000000000000000",
+                },
+            },
+        }),
+    }])
+    .await;
+
+    let auction = |id: &str| {
+        json!({
+            "id": id,
+            "tokens": {
+                "0xe41d2489571d322189246dafa5ebde1f4699f498": {
+                    "decimals": 18,
+                    "symbol": "ZRX",
+                    "referencePrice": "4327903683155778",
+                    "availableBalance": "1583034704488033979459",
+                    "trusted": true,
+                },
+                "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "482725140468789680",
+                    "trusted": true,
+                },
+            },
+            "orders": [
+                {
+                    "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a",
+                    "sellToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    "buyToken": "0xe41d2489571d322189246dafa5ebde1f4699f498",
+                    "sellAmount": "1000000000000000000",
+                    "buyAmount": "200000000000000000000",
+                    "fullSellAmount": "1000000000000000000",
+                    "fullBuyAmount": "200000000000000000000",
+                    "kind": "sell",
+                    "partiallyFillable": false,
+                    "class": "market",
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "validTo": 0,
+                    "appData": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": [],
+            "effectiveGasPrice": "15000000000",
+            "deadline": "2106-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        })
+    };
+
+    let engine = tests::SolverEngine::new("zeroex", zeroex::config(&api.address)).await;
+    let solution = engine.solve(auction("1")).await.unwrap();
+    assert_eq!(solution["solutions"].as_array().unwrap().len(), 1);
+
+    engine
+        .notify(json!({
+            "auctionId": "1",
+            "solutionId": 0,
+            "kind": "revert",
+            "transaction": "0x0101010101010101010101010101010101010101010101010101010101010101",
+        }))
+        .await
+        .unwrap();
+
+    // The order is backed off, so the DEX API doesn't get queried again.
+    let solution = engine.solve(auction("2")).await.unwrap();
+    assert_eq!(solution, json!({ "solutions": [] }));
+}
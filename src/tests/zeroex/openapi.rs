@@ -0,0 +1,113 @@
+//! This test ensures that the OpenAPI specification is served and that every
+//! route it documents is served, also under the chain ID and instance name of
+//! a solver.
+
+use {
+    crate::{
+        api::{GLOBAL_ROUTES, ROUTES, openapi::SPEC},
+        tests::{self, zeroex},
+    },
+    std::{io::Write, net::SocketAddr},
+};
+
+#[tokio::test]
+async fn serves_spec() {
+    let engine = tests::SolverEngine::new(
+        "zeroex",
+        zeroex::config(&SocketAddr::from(([127, 0, 0, 1], 9))),
+    )
+    .await;
+
+    let response = reqwest::get(shared::url::join(&engine.url, "openapi.yml"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), SPEC);
+}
+
+#[tokio::test]
+async fn serves_every_route() {
+    // Nothing listens on the DEX API address, so requests fail quickly, but
+    // they must still be routed.
+    let token = tests::secret_file("SUPER_SECRET_TOKEN");
+    let engine = tests::SolverEngine::with_args(
+        "zeroex",
+        zeroex::config(&SocketAddr::from(([127, 0, 0, 1], 9))),
+        [format!("--debug-token-file={}", token.display())],
+    )
+    .await;
+    for prefix in ["", "1/"] {
+        assert_routed(&engine.url, prefix).await;
+    }
+
+    let mut config = tempfile::NamedTempFile::new().unwrap();
+    write!(
+        config,
+        r"
+node-url = 'http://localhost:8545'
+[dex]
+chain-id = '1'
+endpoint = 'http://127.0.0.1:9/swap/allowance-holder/'
+api-key = 'SUPER_SECRET_API_KEY'
+        ",
+    )
+    .unwrap();
+    let config = config.into_temp_path();
+    let engine = tests::SolverEngine::with_args(
+        "instances",
+        tests::Config::String(format!(
+            r"
+[[instances]]
+name = 'zeroex-named'
+solver = 'zeroex'
+config = '{}'
+            ",
+            config.display(),
+        )),
+        [format!("--debug-token-file={}", token.display())],
+    )
+    .await;
+    for prefix in ["zeroex-named/", "zeroex-named/1/"] {
+        assert_routed(&engine.url, prefix).await;
+    }
+}
+
+/// Asserts that every route, except for global ones if there is a prefix, is
+/// routed under the prefix. Debug routes reject requests without the token,
+/// which also shows that they are routed, whereas a handler might
+/// legitimately respond with 404.
+async fn assert_routed(url: &reqwest::Url, prefix: &str) {
+    let client = reqwest::Client::new();
+    for (method, path) in ROUTES {
+        if !prefix.is_empty() && GLOBAL_ROUTES.contains(path) {
+            continue;
+        }
+        let path = path
+            .trim_start_matches('/')
+            .replace("{token}", "0x1111111111111111111111111111111111111111")
+            .replace("{uid}", &format!("0x{}", "2a".repeat(56)));
+        let response = client
+            .request(
+                method.to_uppercase().parse().unwrap(),
+                shared::url::join(url, &format!("{prefix}{path}")),
+            )
+            .json(&serde_json::json!({}))
+            .send()
+            .await
+            .unwrap();
+        let status = response.status();
+        if path.starts_with("debug/") {
+            assert_eq!(
+                status,
+                reqwest::StatusCode::UNAUTHORIZED,
+                "{method} /{prefix}{path}"
+            );
+        } else {
+            assert!(
+                status != reqwest::StatusCode::NOT_FOUND
+                    && status != reqwest::StatusCode::METHOD_NOT_ALLOWED,
+                "{method} /{prefix}{path} is not routed: {status}",
+            );
+        }
+    }
+}
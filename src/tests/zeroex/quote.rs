@@ -0,0 +1,169 @@
+//! This test ensures that a single swap can be quoted without an auction,
+//! both over the API and from the command line.
+
+use {
+    crate::{
+        infra::cli,
+        tests::{self, mock, zeroex},
+    },
+    serde_json::json,
+    std::io::Write,
+};
+
+#[tokio::test]
+async fn sell() {
+    let api = mock::http::setup(vec![mock::http::Expectation::Get {
+        path: mock::http::Path::exact(
+            "swap/allowance-holder/quote?chainId=1&\
+             buyToken=0xe41d2489571d322189246dafa5ebde1f4699f498&\
+             sellToken=0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2&sellAmount=1000000000000000000&\
+             taker=0x9008d19f58aabd9ed0d60971565aa8510560ab41&slippageBps=100",
+        ),
+        res: json!({
+            "liquidityAvailable": true,
+            "sellAmount": "1000000000000000000",
+            "buyAmount": "5876422636675954000000",
+            "transaction": {
+                "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                "data": "0x6af479b2",
+                "gas": "127886",
+            },
+            "issues": {
+                "allowance": {
+                    "spender": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                    "actual": "1000000000000000000",
+                },
+            },
+        }),
+    }])
+    .await;
+
+    let engine = tests::SolverEngine::new("zeroex", zeroex::config(&api.address)).await;
+    let quote = engine
+        .quote(json!({
+            "sellToken": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+            "buyToken": "0xe41d2489571d322189246dafa5ebde1f4699f498",
+            "side": "sell",
+            "amount": "1000000000000000000",
+        }))
+        .await
+        .unwrap();
+
+    assert_eq!(quote["sellAmount"], "1000000000000000000");
+    assert_eq!(quote["buyAmount"], "5876422636675954000000");
+    assert_eq!(quote["gas"], "127886");
+    assert_eq!(
+        quote["calls"],
+        json!([{
+            "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+            "value": "0",
+            "calldata": "0x6af479b2",
+        }]),
+    );
+    assert_eq!(
+        quote["solution"]["trades"][0]["executedAmount"],
+        "1000000000000000000"
+    );
+    assert_eq!(
+        quote["solution"]["prices"],
+        json!({
+            "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2": "5876422636675954000000",
+            "0xe41d2489571d322189246dafa5ebde1f4699f498": "1000000000000000000",
+        }),
+    );
+}
+
+#[tokio::test]
+async fn command() {
+    let api = mock::http::setup(vec![mock::http::Expectation::Get {
+        path: mock::http::Path::Any,
+        res: json!({
+            "liquidityAvailable": true,
+            "sellAmount": "1000000000000000000",
+            "buyAmount": "5876422636675954000000",
+            "transaction": {
+                "to": "0x0000000000001ff3684f28c67538d4d072c22734",
+                // `exec(operator, WETH, 1e18, target, 0x6af479b2)`
+                "data": "0x2213bc0b\
+                    000000000000000000000000def1c0ded9bec7f1a1670819833240f027b25eff\
+                    000000000000000000000000c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2\
+                    0000000000000000000000000000000000000000000000000de0b6b3a7640000\
+                    000000000000000000000000def1c0ded9bec7f1a1670819833240f027b25eff\
+                    00000000000000000000000000000000000000000000000000000000000000a0\
+                    0000000000000000000000000000000000000000000000000000000000000004\
+                    6af479b200000000000000000000000000000000000000000000000000000000",
+                "gas": "127886",
+            },
+            "issues": {
+                "allowance": {
+                    "spender": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                    "actual": "1000000000000000000",
+                },
+            },
+        }),
+    }])
+    .await;
+
+    let tests::Config::String(config) = zeroex::config(&api.address) else {
+        unreachable!()
+    };
+    let mut config_file = tempfile::NamedTempFile::new().unwrap();
+    config_file.write_all(config.as_bytes()).unwrap();
+
+    let report = crate::quote::quote(&cli::Quote {
+        sell_token: "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"
+            .parse()
+            .unwrap(),
+        buy_token: "0xe41d2489571d322189246dafa5ebde1f4699f498"
+            .parse()
+            .unwrap(),
+        side: cli::Side::Sell,
+        amount: "1000000000000000000".parse().unwrap(),
+        // More than the swap buys.
+        limit: Some("6000000000000000000000".parse().unwrap()),
+        owner: "0x5b1e2c2762667331bc91648052f646d1b0d35984"
+            .parse()
+            .unwrap(),
+        sell_token_decimals: None,
+        buy_token_decimals: None,
+        solver: cli::Solver::ZeroEx {
+            config: config_file.path().to_owned(),
+        },
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(report.exchanges.len(), 1);
+    assert!(
+        report.exchanges[0]
+            .path
+            .starts_with("swap/allowance-holder/quote?")
+    );
+    assert_eq!(
+        report.exchanges[0].response["buyAmount"],
+        "5876422636675954000000"
+    );
+
+    let swap = report.probe.swap.as_ref().unwrap();
+    assert_eq!(
+        swap.output.amount,
+        "5876422636675954000000"
+            .parse::<alloy::primitives::U256>()
+            .unwrap()
+    );
+    assert_eq!(swap.gas.0, alloy::primitives::U256::from(127886));
+    // There is no node to simulate the swap with.
+    assert!(matches!(report.probe.gas, Some(Err(_))));
+    let limit = report.probe.limit.as_ref().unwrap();
+    assert!(!limit.limit_price);
+    assert!(!limit.minimum_surplus);
+
+    let report = report.to_string().to_lowercase();
+    assert!(report.contains("(100 bps)"));
+    assert!(report.contains("to 0x0000000000001ff3684f28c67538d4d072c22734 with a value of 0:"));
+    assert!(report.contains("exec("));
+    assert!(report.contains("token: 0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"));
+    assert!(report.contains("amount: 1000000000000000000"));
+    assert!(report.contains("data: 0x6af479b2"));
+    assert!(report.contains("limit price: failed"));
+}
@@ -0,0 +1,137 @@
+//! This test ensures that the readiness endpoint checks the node and the DEX
+//! API and explains which checks failed.
+
+use {
+    crate::{
+        api::openapi::Schema,
+        tests::{self, mock},
+    },
+    serde_json::json,
+    std::net::SocketAddr,
+};
+
+fn config(node: &SocketAddr, api: &SocketAddr) -> tests::Config {
+    tests::Config::String(format!(
+        r"
+node-url = 'http://{node}'
+[dex]
+chain-id = '1'
+endpoint = 'http://{api}/swap/allowance-holder/'
+api-key = 'SUPER_SECRET_API_KEY'
+        ",
+    ))
+}
+
+fn probe() -> mock::http::Expectation {
+    mock::http::Expectation::Get {
+        path: mock::http::Path::exact("swap/allowance-holder/quote"),
+        res: json!({
+            "name": "INPUT_INVALID",
+            "message": "Validation Failed",
+        }),
+    }
+}
+
+async fn readyz(engine: &tests::SolverEngine) -> (reqwest::StatusCode, serde_json::Value) {
+    let response = reqwest::get(shared::url::join(&engine.url, "readyz"))
+        .await
+        .unwrap();
+    let status = response.status();
+    let body = response.json().await.unwrap();
+    tests::assert_spec(Schema::response("/readyz", "get", status.as_u16()), &body);
+    (status, body)
+}
+
+#[tokio::test]
+async fn ready() {
+    let api = mock::http::setup(vec![probe()]).await;
+    let node = mock::http::setup(vec![mock::http::Expectation::Post {
+        path: mock::http::Path::Any,
+        req: mock::http::RequestBody::Any,
+        res: json!({
+            "id": 0,
+            "jsonrpc": "2.0",
+            "result": "0x1312d00",
+        }),
+    }])
+    .await;
+
+    let engine = tests::SolverEngine::new("zeroex", config(&node.address, &api.address)).await;
+
+    let healthz = reqwest::get(shared::url::join(&engine.url, "healthz"))
+        .await
+        .unwrap();
+    assert_eq!(healthz.status(), reqwest::StatusCode::OK);
+
+    assert_eq!(
+        readyz(&engine).await,
+        (
+            reqwest::StatusCode::OK,
+            json!({
+                "ready": true,
+                "checks": {
+                    "shutdown": { "status": "ok" },
+                    "node": { "status": "ok" },
+                    "blockStream": { "status": "skipped" },
+                    "dex": { "status": "ok" },
+                },
+            }),
+        ),
+    );
+}
+
+#[tokio::test]
+async fn node_unavailable() {
+    let api = mock::http::setup(vec![probe()]).await;
+    let node = mock::http::setup(vec![mock::http::Expectation::Post {
+        path: mock::http::Path::Any,
+        req: mock::http::RequestBody::Any,
+        res: json!({
+            "id": 0,
+            "jsonrpc": "2.0",
+            "error": {
+                "code": -32000,
+                "message": "node is syncing",
+            },
+        }),
+    }])
+    .await;
+
+    let engine = tests::SolverEngine::new("zeroex", config(&node.address, &api.address)).await;
+
+    let (status, readiness) = readyz(&engine).await;
+    assert_eq!(status, reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(readiness["ready"], json!(false));
+    assert_eq!(readiness["checks"]["node"]["status"], json!("failed"));
+    assert!(
+        readiness["checks"]["node"]["error"]
+            .as_str()
+            .unwrap()
+            .contains("node is syncing")
+    );
+    assert_eq!(readiness["checks"]["dex"], json!({ "status": "ok" }));
+}
+
+#[tokio::test]
+async fn reuses_dex_probe() {
+    // The mock DEX API only expects a single probe.
+    let api = mock::http::setup(vec![probe()]).await;
+    let block_number = || mock::http::Expectation::Post {
+        path: mock::http::Path::Any,
+        req: mock::http::RequestBody::Any,
+        res: json!({
+            "id": 0,
+            "jsonrpc": "2.0",
+            "result": "0x1312d00",
+        }),
+    };
+    let node = mock::http::setup(vec![block_number(), block_number()]).await;
+
+    let engine = tests::SolverEngine::new("zeroex", config(&node.address, &api.address)).await;
+
+    for _ in 0..2 {
+        let (status, readiness) = readyz(&engine).await;
+        assert_eq!(status, reqwest::StatusCode::OK);
+        assert_eq!(readiness["checks"]["dex"], json!({ "status": "ok" }));
+    }
+}
@@ -0,0 +1,142 @@
+//! This test ensures that the DEX API traffic of an auction gets recorded into
+//! a fixture without secrets, and that the fixture can be loaded into the mock
+//! HTTP server to reproduce the auction.
+
+use {
+    crate::{
+        infra::fixture::{self, Fixture},
+        tests::{self, mock, zeroex},
+    },
+    serde_json::json,
+};
+
+#[tokio::test]
+async fn test() {
+    let api = mock::http::setup(vec![mock::http::Expectation::Status {
+        status: 400,
+        expectation: Box::new(mock::http::Expectation::Get {
+            path: mock::http::Path::Any,
+            res: json!({
+                "code": 100,
+                "reason": "Validation Failed",
+                "validationErrors": [
+                    {
+                        "field": "buyAmount",
+                        "code": 1004,
+                        "reason": "INSUFFICIENT_ASSET_LIQUIDITY",
+                        "description": "We are not able to fulfill an order for this token pair \
+                                        at the requested amount due to a lack of liquidity",
+                    },
+                ],
+            }),
+        }),
+    }])
+    .await;
+    let dir = tempfile::tempdir().unwrap();
+
+    let auction = json!({
+        "id": "1",
+        "tokens": {
+            "0x1111111111111111111111111111111111111111": {
+                "decimals": 18,
+                "referencePrice": "1000000000000000000",
+                "availableBalance": "0",
+                "trusted": false,
+            },
+            "0x2222222222222222222222222222222222222222": {
+                "decimals": 18,
+                "referencePrice": "1000000000000000000",
+                "availableBalance": "0",
+                "trusted": false,
+            },
+        },
+        "orders": [
+            {
+                "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                          2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                          2a2a2a2a",
+                "sellToken": "0x1111111111111111111111111111111111111111",
+                "buyToken": "0x2222222222222222222222222222222222222222",
+                "sellAmount": "1000000000000000000",
+                "buyAmount": "1000000000000000000",
+                "fullSellAmount": "1000000000000000000",
+                "fullBuyAmount": "100000000000000000000",
+                "kind": "sell",
+                "partiallyFillable": false,
+                "class": "market",
+                "sellTokenSource": "erc20",
+                "buyTokenDestination": "erc20",
+                "preInteractions": [],
+                "postInteractions": [],
+                "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                "validTo": 0,
+                "appData": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "signingScheme": "presign",
+                "signature": "0x",
+            },
+        ],
+        "liquidity": [],
+        "effectiveGasPrice": "15000000000",
+        "deadline": "2106-01-01T00:00:00.000Z",
+        "surplusCapturingJitOrderOwners": []
+    });
+
+    let engine = tests::SolverEngine::with_args(
+        "zeroex",
+        zeroex::config(&api.address),
+        [format!("--record={}", dir.path().display())],
+    )
+    .await;
+    let solution = engine.solve(auction.clone()).await.unwrap();
+    assert_eq!(solution, json!({ "solutions": [] }));
+
+    let recorded = std::fs::read_to_string(Fixture::path(dir.path(), "1")).unwrap();
+    assert!(!recorded.contains("SUPER_SECRET_API_KEY"));
+    let recorded = Fixture::load(dir.path(), "1").await.unwrap();
+    assert_eq!(recorded.dex.len(), 1);
+    assert_eq!(recorded.dex[0].status, 400);
+    assert!(
+        recorded.dex[0]
+            .path
+            .starts_with("swap/allowance-holder/quote?")
+    );
+    assert!(recorded.node.is_empty());
+    assert!(Fixture::path(dir.path(), fixture::INIT).exists());
+    let auctions = std::fs::read_to_string(dir.path().join(fixture::recorder::AUCTIONS)).unwrap();
+    assert_eq!(
+        auctions
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>(),
+        [auction.clone()],
+    );
+
+    // The replayed response has the recorded status.
+    let replay = mock::http::setup(recorded.dex.into_iter().map(Into::into).collect()).await;
+    let engine = tests::SolverEngine::new("zeroex", zeroex::config(&replay.address)).await;
+    assert_eq!(engine.solve(auction).await.unwrap(), solution);
+}
+
+#[tokio::test]
+async fn concurrent_auctions() {
+    let api = mock::http::setup(vec![zeroex::not_found(), zeroex::not_found()]).await;
+    let dir = tempfile::tempdir().unwrap();
+
+    let engine = tests::SolverEngine::with_args(
+        "zeroex",
+        zeroex::config(&api.address),
+        [format!("--record={}", dir.path().display())],
+    )
+    .await;
+    let mut second = zeroex::auction();
+    second["id"] = json!("2");
+    let (first, second) = tokio::join!(engine.solve(zeroex::auction()), engine.solve(second));
+    first.unwrap();
+    second.unwrap();
+
+    // Every fixture only contains the DEX API requests of its own auction.
+    for id in ["1", "2"] {
+        let recorded = Fixture::load(dir.path(), id).await.unwrap();
+        assert_eq!(recorded.dex.len(), 1);
+    }
+}
@@ -0,0 +1,121 @@
+//! This test ensures that a single auction can be solved from the command line
+//! and that the explanation tells why an order wasn't solved.
+
+use {
+    crate::{
+        infra::cli,
+        tests::{mock, zeroex},
+    },
+    serde_json::json,
+    std::{io::Write, time::Duration},
+};
+
+#[tokio::test]
+async fn explains_rejected_swap() {
+    let api = mock::http::setup(vec![mock::http::Expectation::Get {
+        path: mock::http::Path::Any,
+        res: json!({
+            "liquidityAvailable": true,
+            "sellAmount": "1000000000000000000",
+            "buyAmount": "5876422636675954000000",
+            "transaction": {
+                "to": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                "data": "0x6af479b2",
+                "gas": "127886",
+            },
+            "issues": {
+                "allowance": {
+                    "spender": "0xdef1c0ded9bec7f1a1670819833240f027b25eff",
+                    "actual": "1000000000000000000",
+                },
+            },
+        }),
+    }])
+    .await;
+
+    let crate::tests::Config::String(config) = zeroex::config(&api.address) else {
+        unreachable!()
+    };
+    let mut config_file = tempfile::NamedTempFile::new().unwrap();
+    config_file.write_all(config.as_bytes()).unwrap();
+    let mut auction_file = tempfile::NamedTempFile::new().unwrap();
+    serde_json::to_writer(
+        &mut auction_file,
+        &json!({
+            "id": "1",
+            "tokens": {
+                "0xe41d2489571d322189246dafa5ebde1f4699f498": {
+                    "decimals": 18,
+                    "symbol": "ZRX",
+                    "referencePrice": "4327903683155778",
+                    "availableBalance": "1583034704488033979459",
+                    "trusted": true,
+                },
+                "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2": {
+                    "decimals": 18,
+                    "symbol": "WETH",
+                    "referencePrice": "1000000000000000000",
+                    "availableBalance": "482725140468789680",
+                    "trusted": true,
+                },
+            },
+            "orders": [
+                {
+                    "uid": "0x2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a2a\
+                              2a2a2a2a",
+                    "sellToken": "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2",
+                    "buyToken": "0xe41d2489571d322189246dafa5ebde1f4699f498",
+                    "sellAmount": "1000000000000000000",
+                    // More than the swap buys.
+                    "buyAmount": "6000000000000000000000",
+                    "fullSellAmount": "1000000000000000000",
+                    "fullBuyAmount": "6000000000000000000000",
+                    "kind": "sell",
+                    "partiallyFillable": false,
+                    "class": "market",
+                    "sellTokenSource": "erc20",
+                    "buyTokenDestination": "erc20",
+                    "preInteractions": [],
+                    "postInteractions": [],
+                    "owner": "0x5b1e2c2762667331bc91648052f646d1b0d35984",
+                    "validTo": 0,
+                    "appData": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                    "signingScheme": "presign",
+                    "signature": "0x",
+                }
+            ],
+            "liquidity": [],
+            "effectiveGasPrice": "15000000000",
+            // Deadlines of auctions solved from the command line get replaced.
+            "deadline": "2000-01-01T00:00:00.000Z",
+            "surplusCapturingJitOrderOwners": []
+        }),
+    )
+    .unwrap();
+
+    let solved = crate::solve::solve(&cli::Solve {
+        auction: auction_file.path().to_owned(),
+        explain: true,
+        time_limit: Duration::from_secs(10),
+        solver: cli::Solver::ZeroEx {
+            config: config_file.path().to_owned(),
+        },
+    })
+    .await
+    .unwrap();
+
+    assert_eq!(solved.solutions, json!({ "solutions": [] }));
+    let explanation = solved.explanation.unwrap().to_lowercase();
+    assert!(explanation.starts_with(&format!("order 0x{}\n", "2a".repeat(56))));
+    assert!(explanation.contains(
+        "limit: sell 1000000000000000000 of 0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2 for at \
+         least 6000000000000000000000 of 0xe41d2489571d322189246dafa5ebde1f4699f498"
+    ));
+    assert!(explanation.contains("(100 bps)"));
+    assert!(explanation.contains(
+        "quote: 1000000000000000000 of 0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2 for \
+         5876422636675954000000 of 0xe41d2489571d322189246dafa5ebde1f4699f498"
+    ));
+    assert!(explanation.contains("outcome: rejected, the swap doesn't satisfy the limit price"));
+}